@@ -1,43 +1,574 @@
-use crate::datasource::csv_table::CSVTable;
+use crate::datasource::csv_table::{CSVTable, CsvOptions};
+use crate::datasource::memory_table::MemoryTable;
 use crate::datasource::table::{Table, TableRef};
+use crate::datatype::field::Field;
+use crate::datatype::schema::Schema;
 use crate::error::{Error, Result};
+use crate::information_schema::information_schema_table;
 use crate::logical_plan::data_frame::DataFrame;
-use crate::logical_plan::logical_plan::{LogicalPlan, Scan};
+use crate::logical_plan::logical_plan::{EmptyRelation, LogicalPlan, Scan};
+use crate::physical_plan::expr::cast::cast_checked;
+use crate::planner::QueryPlanner;
+use crate::prepared_statement::PreparedStatement;
+use crate::sql::ast::{ColumnDef, CreateExternalTable, CreateTable, Query, Statement};
+use crate::sql::parser::Parser;
+use crate::sql::planner::SqlPlanner;
+use arrow::array::{as_primitive_array, ArrayRef, UInt64Array};
+use arrow::compute::can_cast_types;
+use arrow::datatypes::{DataType, Float64Type, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-#[derive(Default)]
+/// The maximum number of iterations of a recursive query unless configured otherwise
+pub const DEFAULT_MAX_RECURSIVE_ITERATIONS: usize = 100;
+
 /// Stores metadata for all tables, needs to implement Table trait
 pub struct Catalog {
-    tables: HashMap<String, Arc<dyn Table>>,
+    /// Tables are also registered by SQL statements, which only borrow the catalog
+    tables: RefCell<HashMap<String, Arc<dyn Table>>>,
+    /// Views share the namespace of the tables, each one is replaced by its plan when it is read
+    views: RefCell<HashMap<String, LogicalPlan>>,
+    max_recursive_iterations: usize,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self {
+            tables: RefCell::new(HashMap::new()),
+            views: RefCell::new(HashMap::new()),
+            max_recursive_iterations: DEFAULT_MAX_RECURSIVE_ITERATIONS,
+        }
+    }
 }
 
 impl Catalog {
     pub fn add_csv_table(&mut self, table_name: &str, csv_file: &str) -> Result<()> {
-        self.tables.insert(
+        self.add_csv_table_with_options(table_name, csv_file, &CsvOptions::default())
+    }
+
+    pub fn add_csv_table_with_options(
+        &mut self,
+        table_name: &str,
+        csv_file: &str,
+        options: &CsvOptions,
+    ) -> Result<()> {
+        self.tables.get_mut().insert(
             table_name.to_string(),
-            CSVTable::try_create_table(csv_file)?,
+            CSVTable::try_create_table_with_options(csv_file, options)?,
         );
         Ok(())
     }
 
     pub fn get_table_by_name(&self, table_name: &str) -> Result<TableRef> {
         self.tables
+            .borrow()
             .get(table_name)
             .cloned()
-            .ok_or_else(|| Error::NoSuchTable(format!("Table {} does not exist", table_name)))
+            .ok_or_else(|| Error::NoSuchTable(table_name.to_string()))
     }
 
+    /// Returns the DataFrame of a table, or the plan of a view.
+    /// The information_schema tables describe the tables and views at the time of the call.
     pub fn get_table_df(&self, table_name: &str) -> Result<DataFrame> {
-        let table = self
-            .tables
-            .get(table_name)
-            .cloned()
-            .ok_or_else(|| Error::NoSuchTable(format!("No table named: {}", table_name)))?;
+        if let Some(plan) = self.views.borrow().get(table_name) {
+            return Ok(DataFrame::new(plan.clone()));
+        }
+
+        let table = match self.tables.borrow().get(table_name) {
+            Some(table) => table.clone(),
+            None => {
+                information_schema_table(table_name, &self.tables.borrow(), &self.views.borrow())?
+                    .ok_or_else(|| Error::NoSuchTable(table_name.to_string()))?
+            }
+        };
         let plan = LogicalPlan::Scan(Scan {
             data_source: table,
             projection: None,
         });
         Ok(DataFrame::new(plan))
     }
+
+    /// Registers a view, reading it reads the rows of `plan`
+    pub fn add_view(&mut self, view_name: &str, plan: LogicalPlan) -> Result<()> {
+        self.create_view(view_name, plan, false)
+    }
+
+    pub fn drop_view(&mut self, view_name: &str) -> Result<()> {
+        self.remove_view(view_name, false)
+    }
+
+    fn create_view(&self, view_name: &str, plan: LogicalPlan, or_replace: bool) -> Result<()> {
+        if self.tables.borrow().contains_key(view_name) {
+            return Err(Error::LogicalPlanError(format!(
+                "Table {} already exists",
+                view_name
+            )));
+        }
+        if !or_replace && self.views.borrow().contains_key(view_name) {
+            return Err(Error::LogicalPlanError(format!(
+                "View {} already exists",
+                view_name
+            )));
+        }
+        self.views.borrow_mut().insert(view_name.to_string(), plan);
+        Ok(())
+    }
+
+    fn remove_view(&self, view_name: &str, if_exists: bool) -> Result<()> {
+        if self.tables.borrow().contains_key(view_name) {
+            return Err(Error::LogicalPlanError(format!(
+                "{} is a table, not a view",
+                view_name
+            )));
+        }
+        if self.views.borrow_mut().remove(view_name).is_none() && !if_exists {
+            return Err(Error::NoSuchTable(view_name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Sets how many iterations a recursive query of `sql` may run before it fails
+    pub fn set_max_recursive_iterations(&mut self, max_iterations: usize) {
+        self.max_recursive_iterations = max_iterations;
+    }
+
+    pub fn max_recursive_iterations(&self) -> usize {
+        self.max_recursive_iterations
+    }
+
+    /// Parses a SQL query and creates a DataFrame for its logical plan.
+    /// A DDL statement is executed right away and returns an empty DataFrame,
+    /// an INSERT is executed right away and returns the number of inserted rows.
+    /// An error about a table, a column or a function points at where the SQL text names it.
+    pub fn sql(&self, sql: &str) -> Result<DataFrame> {
+        let plan = match Parser::parse_sql(sql)? {
+            Statement::CreateExternalTable(create) => {
+                self.create_external_table(&create)?;
+                empty_relation()
+            }
+            Statement::CreateView {
+                name,
+                query,
+                or_replace,
+            } => {
+                let plan = SqlPlanner::new(self).query_to_plan(*query)?;
+                self.create_view(&name, plan, or_replace)?;
+                empty_relation()
+            }
+            Statement::DropView { name, if_exists } => {
+                self.remove_view(&name, if_exists)?;
+                empty_relation()
+            }
+            Statement::CreateTable(create) => {
+                self.create_table(create)?;
+                empty_relation()
+            }
+            Statement::Insert { table_name, source } => {
+                let count = self.insert_into(&table_name, *source)?;
+                count_relation(count)?
+            }
+            statement => SqlPlanner::new(self).statement_to_plan(statement)?,
+        };
+        Ok(DataFrame::new(plan))
+    }
+
+    /// Registers the file of a CREATE EXTERNAL TABLE statement as a table
+    fn create_external_table(&self, create: &CreateExternalTable) -> Result<()> {
+        if !self.can_create_table(&create.name, create.if_not_exists, create.or_replace)? {
+            return Ok(());
+        }
+
+        if create.file_type != "CSV" {
+            return Err(Error::LogicalPlanError(format!(
+                "Unsupported file type {}, only CSV is supported",
+                create.file_type
+            )));
+        }
+        let delimiter = u8::try_from(create.delimiter).map_err(|_| {
+            Error::LogicalPlanError(format!(
+                "The delimiter {} is not an ASCII character",
+                create.delimiter
+            ))
+        })?;
+        // Explicit columns replace the inferred schema
+        let schema = if create.columns.is_empty() {
+            None
+        } else {
+            Some(columns_schema(&create.columns))
+        };
+        let options = CsvOptions {
+            has_header: create.has_header,
+            delimiter,
+            schema,
+            ..CsvOptions::default()
+        };
+
+        let table = CSVTable::try_create_table_with_options(&create.location, &options)?;
+        self.tables.borrow_mut().insert(create.name.clone(), table);
+        Ok(())
+    }
+
+    /// Creates the memory table of a CREATE TABLE statement, it holds the rows of its query
+    fn create_table(&self, create: CreateTable) -> Result<()> {
+        if !self.can_create_table(&create.name, create.if_not_exists, create.or_replace)? {
+            return Ok(());
+        }
+
+        let plan = match create.query {
+            Some(query) => Some(SqlPlanner::new(self).query_to_plan(*query)?),
+            None => None,
+        };
+        let schema = match &plan {
+            Some(plan) if create.columns.is_empty() => Schema::new(
+                plan.schema()
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        Field::new(field.name(), field.data_type().clone(), field.is_nullable())
+                    })
+                    .collect(),
+            ),
+            _ => columns_schema(&create.columns),
+        };
+        let batches = match &plan {
+            Some(plan) => execute_into(plan, &schema)?,
+            None => vec![],
+        };
+
+        let table = MemoryTable::try_create_table(schema, batches)?;
+        self.tables.borrow_mut().insert(create.name, table);
+        Ok(())
+    }
+
+    /// Appends the rows of the query to a table
+    fn insert_into(&self, table_name: &str, source: Query) -> Result<usize> {
+        let table = self.get_table_by_name(table_name)?;
+        let plan = SqlPlanner::new(self).query_to_plan(source)?;
+        table.insert(execute_into(&plan, table.schema())?)
+    }
+
+    /// Whether a table named `name` should be created. It fails if a view has the name, or if
+    /// a table has it and it may not be replaced.
+    fn can_create_table(&self, name: &str, if_not_exists: bool, or_replace: bool) -> Result<bool> {
+        if self.views.borrow().contains_key(name) {
+            return Err(Error::LogicalPlanError(format!(
+                "View {} already exists",
+                name
+            )));
+        }
+        if self.tables.borrow().contains_key(name) {
+            if if_not_exists {
+                return Ok(false);
+            }
+            if !or_replace {
+                return Err(Error::LogicalPlanError(format!(
+                    "Table {} already exists",
+                    name
+                )));
+            }
+        }
+        Ok(true)
+    }
+
+    /// Parses and plans a SQL query with `$1` or `?` placeholders once,
+    /// the returned statement is executed with the parameter values
+    pub fn prepare(&self, sql: &str) -> Result<PreparedStatement> {
+        let statement = Parser::parse_sql(sql)?;
+        let plan = SqlPlanner::new(self).statement_to_plan(statement)?;
+        PreparedStatement::try_new(plan)
+    }
+}
+
+/// The plan returned by a DDL statement
+fn empty_relation() -> LogicalPlan {
+    LogicalPlan::EmptyRelation(EmptyRelation {
+        schema: Schema::new(vec![]),
+    })
+}
+
+/// The plan returned by an INSERT, a single row with the number of inserted rows
+fn count_relation(count: usize) -> Result<LogicalPlan> {
+    let schema = Schema::new(vec![Field::new("count", DataType::UInt64, false)]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema.clone().into()),
+        vec![Arc::new(UInt64Array::from(vec![count as u64])) as ArrayRef],
+    )?;
+    Ok(LogicalPlan::Scan(Scan {
+        data_source: MemoryTable::try_create_table(schema, vec![batch])?,
+        projection: None,
+    }))
+}
+
+fn columns_schema(columns: &[ColumnDef]) -> Schema {
+    Schema::new(
+        columns
+            .iter()
+            .map(|column| Field::new(&column.name, column.data_type.clone(), column.nullable))
+            .collect(),
+    )
+}
+
+/// Executes the plan of the rows written to a table, the columns are cast to the types of `schema`
+fn execute_into(plan: &LogicalPlan, schema: &Schema) -> Result<Vec<RecordBatch>> {
+    let fields = plan.schema().fields();
+    if fields.len() != schema.fields().len() {
+        return Err(Error::LogicalPlanError(format!(
+            "The table has {} columns, but the query returns {}",
+            schema.fields().len(),
+            fields.len()
+        )));
+    }
+    for (field, column) in fields.iter().zip(schema.fields()) {
+        if !can_cast_types(field.data_type(), column.data_type()) {
+            return Err(Error::LogicalPlanError(format!(
+                "Column {} is {:?}, the query returns {:?} for it",
+                column.name(),
+                column.data_type(),
+                field.data_type()
+            )));
+        }
+    }
+
+    let output_schema: SchemaRef = schema.clone().into();
+    QueryPlanner::create_physical_plan(plan)?
+        .execute()?
+        .iter()
+        .map(|batch| {
+            let columns = batch
+                .columns()
+                .iter()
+                .zip(schema.fields())
+                .map(|(column, field)| cast_column(column, field))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(RecordBatch::try_new(output_schema.clone(), columns)?)
+        })
+        .collect()
+}
+
+/// Casts a column written to a table, a value that cannot be stored exactly is an error
+fn cast_column(column: &ArrayRef, field: &Field) -> Result<ArrayRef> {
+    if column.data_type() == &DataType::Float64
+        && matches!(field.data_type(), DataType::Int64 | DataType::UInt64)
+    {
+        let values = as_primitive_array::<Float64Type>(column);
+        if let Some(value) = values.iter().flatten().find(|value| value.fract() != 0.0) {
+            return Err(Error::PhysicalPlanError(format!(
+                "Column {} is {:?}, {} cannot be stored in it without losing its fraction",
+                field.name(),
+                field.data_type(),
+                value
+            )));
+        }
+    }
+    cast_checked(column, field.data_type())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::logical_expr::LogicalExpr;
+    use crate::util::concat_batches;
+    use arrow::array::{Float64Array, Int64Array, StringArray};
+
+    fn execute(catalog: &Catalog, sql: &str) -> Result<Vec<RecordBatch>> {
+        QueryPlanner::create_physical_plan(catalog.sql(sql)?.plan())?.execute()
+    }
+
+    #[test]
+    fn create_external_table() -> Result<()> {
+        let catalog = Catalog::default();
+
+        // The schema is inferred from the file
+        assert!(execute(
+            &catalog,
+            "CREATE EXTERNAL TABLE test STORED AS CSV WITH HEADER ROW LOCATION 'data/test.csv'"
+        )?
+        .is_empty());
+        let batches = execute(&catalog, "SELECT name FROM test WHERE age > 24")?;
+        assert_eq!(
+            batches[0].column(0),
+            &(Arc::new(StringArray::from(vec!["Brian"])) as ArrayRef)
+        );
+
+        // The columns are given explicitly
+        catalog.sql(
+            "CREATE EXTERNAL TABLE city (id INT NOT NULL, name VARCHAR, city VARCHAR) \
+             STORED AS CSV DELIMITER ';' LOCATION 'data/city.csv'",
+        )?;
+        let schema = catalog.get_table_by_name("city")?.schema().clone();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert!(!schema.field(0).is_nullable());
+        assert!(schema.field(2).is_nullable());
+        let batches = execute(&catalog, "SELECT name FROM city WHERE city = 'Tokyo'")?;
+        assert_eq!(
+            batches[0].column(0),
+            &(Arc::new(StringArray::from(vec!["KamenRider"])) as ArrayRef)
+        );
+
+        // An existing table is only replaced with OR REPLACE
+        let create = "EXTERNAL TABLE city STORED AS CSV WITH HEADER LOCATION 'data/salary.csv'";
+        assert!(catalog.sql(&format!("CREATE {}", create)).is_err());
+        catalog.sql(&format!(
+            "CREATE {}",
+            create.replace("TABLE", "TABLE IF NOT EXISTS")
+        ))?;
+        assert_eq!(
+            catalog.get_table_by_name("city")?.schema().fields().len(),
+            3
+        );
+        catalog.sql(&format!("CREATE OR REPLACE {}", create))?;
+        assert_eq!(
+            catalog.get_table_by_name("city")?.schema().field(2).name(),
+            "salary"
+        );
+
+        assert!(catalog
+            .sql("CREATE EXTERNAL TABLE t STORED AS PARQUET LOCATION 'data/test.csv'")
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn create_and_drop_view() -> Result<()> {
+        let mut catalog = Catalog::default();
+        catalog.add_csv_table("test", "data/test.csv")?;
+        catalog.add_csv_table("salary", "data/salary.csv")?;
+
+        catalog.sql(
+            "CREATE VIEW rich AS SELECT test.name, salary.salary FROM test \
+             JOIN salary ON test.id = salary.id WHERE salary.salary > 5000",
+        )?;
+        let batches = execute(&catalog, "SELECT name FROM rich WHERE salary < 30000")?;
+        assert_eq!(
+            batches[0].column(0),
+            &(Arc::new(StringArray::from(vec!["Vincent Hu"])) as ArrayRef)
+        );
+
+        // The view is expanded inline, so the optimizer sees its tables
+        let plan = catalog.sql("SELECT name FROM rich")?.plan;
+        assert_eq!(
+            plan.display_indent().to_string(),
+            "Projection: name\
+           \n  SubqueryAlias: rich\
+           \n    Projection: test.name, salary.salary\
+           \n      Selection: salary.salary > 5000\
+           \n        Join: type=Inner, on=[test.id = salary.id]\
+           \n          SubqueryAlias: test\
+           \n            Scan: CSV file [id, name, age, score]\
+           \n          SubqueryAlias: salary\
+           \n            Scan: CSV file [id, name, salary, awards]"
+        );
+
+        // Views share the namespace of the tables
+        assert!(catalog
+            .sql("CREATE VIEW rich AS SELECT id FROM test")
+            .is_err());
+        assert!(catalog
+            .sql("CREATE VIEW test AS SELECT id FROM test")
+            .is_err());
+        catalog.sql("CREATE OR REPLACE VIEW rich AS SELECT id FROM test WHERE age > 24")?;
+        assert_eq!(catalog.get_table_df("rich")?.schema().fields().len(), 1);
+
+        // A view defined through the DataFrame API
+        let df = catalog.get_table_df("salary")?;
+        catalog.add_view(
+            "salaries",
+            df.project(vec![LogicalExpr::Column("salary".to_string())])?
+                .plan,
+        )?;
+        let batches = execute(&catalog, "SELECT MAX(salary) FROM salaries")?;
+        assert_eq!(batches[0].num_rows(), 1);
+
+        assert!(catalog.sql("DROP VIEW test").is_err());
+        catalog.sql("DROP VIEW rich")?;
+        assert!(catalog.sql("SELECT id FROM rich").is_err());
+        assert!(catalog.sql("DROP VIEW rich").is_err());
+        catalog.sql("DROP VIEW IF EXISTS rich")?;
+        catalog.drop_view("salaries")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_into_memory_table() -> Result<()> {
+        let mut catalog = Catalog::default();
+        catalog.add_csv_table("test", "data/test.csv")?;
+
+        catalog.sql("CREATE TABLE people (id INT NOT NULL, name VARCHAR, score DOUBLE)")?;
+        let batches = execute(
+            &catalog,
+            "INSERT INTO people VALUES (1, 'Alice', 99.5), (2, NULL, 60)",
+        )?;
+        assert_eq!(
+            batches[0].column(0),
+            &(Arc::new(UInt64Array::from(vec![2])) as ArrayRef)
+        );
+        execute(
+            &catalog,
+            "INSERT INTO people SELECT id, name, score FROM test WHERE age > 24",
+        )?;
+
+        let batches = execute(&catalog, "SELECT name, score FROM people")?;
+        let batch = concat_batches(&batches[0].schema(), &batches)?;
+        assert_eq!(
+            batch.column(0),
+            &(Arc::new(StringArray::from(vec![Some("Alice"), None, Some("Brian")])) as ArrayRef)
+        );
+        assert_eq!(
+            batch.column(1),
+            &(Arc::new(Float64Array::from(vec![99.5, 60.0, 99.97])) as ArrayRef)
+        );
+
+        // The rows must fit the schema of the table
+        assert!(catalog
+            .sql("INSERT INTO people VALUES (NULL, 'Bob', 1.0)")
+            .is_err());
+        assert!(catalog.sql("INSERT INTO people VALUES (3, 'Bob')").is_err());
+        assert!(catalog
+            .sql("INSERT INTO people VALUES ('x', 'Bob', 1.0)")
+            .is_err());
+        assert!(catalog
+            .sql("INSERT INTO test VALUES (6, 'Bob', 20, 1.0)")
+            .is_err());
+        // A value is not stored as NULL or truncated when it does not fit the column
+        catalog.sql("CREATE TABLE ages (age INT)")?;
+        assert!(matches!(
+            catalog.sql("INSERT INTO ages VALUES ('x')").err().unwrap(),
+            Error::ArrowError(_)
+        ));
+        assert!(matches!(
+            catalog.sql("INSERT INTO ages VALUES (99.5)").err().unwrap(),
+            Error::PhysicalPlanError(_)
+        ));
+        execute(&catalog, "INSERT INTO ages VALUES ('7')")?;
+        execute(&catalog, "INSERT INTO ages VALUES (8.0)")?;
+        let batches = execute(&catalog, "SELECT age FROM ages")?;
+        let batch = concat_batches(&batches[0].schema(), &batches)?;
+        assert_eq!(
+            batch.column(0),
+            &(Arc::new(Int64Array::from(vec![7, 8])) as ArrayRef)
+        );
+        assert_eq!(execute(&catalog, "SELECT id FROM people")?[0].num_rows(), 2);
+
+        // CREATE TABLE AS takes the columns of the query
+        catalog.sql("CREATE TABLE adults AS SELECT id, age FROM test WHERE age >= 24")?;
+        let batches = execute(&catalog, "SELECT * FROM adults")?;
+        assert_eq!(
+            batches[0].column(1),
+            &(Arc::new(Int64Array::from(vec![24, 24, 26])) as ArrayRef)
+        );
+        assert!(catalog
+            .sql("CREATE TABLE adults AS SELECT id, age FROM test")
+            .is_err());
+        catalog.sql("CREATE OR REPLACE TABLE adults AS SELECT id FROM adults WHERE age > 24")?;
+        assert_eq!(
+            catalog.get_table_by_name("adults")?.scan(None)?[0].num_rows(),
+            1
+        );
+
+        Ok(())
+    }
 }
@@ -1,18 +1,19 @@
 use super::table::{Table, TableRef};
 use crate::datatype::schema::Schema;
-use crate::error::Result;
+use crate::error::{Result, ResultExt};
+use crate::physical_plan::physical_plan::RecordBatchStream;
 use arrow::{csv, record_batch::RecordBatch};
 use std::env;
 use std::fs::File;
-use std::io::Seek;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 #[derive(Debug)]
-/// Stores schema and records read from a CSV file.
+/// Reads the records of a CSV file, the file is read again by every scan.
 pub struct CSVTable {
     schema: Schema,
-    batches: Vec<RecordBatch>,
+    path: PathBuf,
+    options: CsvOptions,
 }
 
 impl Table for CSVTable {
@@ -21,54 +22,89 @@ impl Table for CSVTable {
     }
 
     fn scan(&self, projection: Option<Vec<usize>>) -> Result<Vec<RecordBatch>> {
-        // If projection is not empty, select the specified columns.
-        if let Some(projection) = projection {
-            let batches = self
-                .batches
-                .iter()
-                .map(|record_batch| record_batch.project(projection.as_ref()).unwrap())
-                .collect::<Vec<_>>();
-            return Ok(batches);
-        }
-        // Otherwise return the entire batch.
-        Ok(self.batches.clone())
-    }
-
-    fn source_type(&self) -> String {
-        "CSV file".to_string()
+        self.scan_stream(projection)?.collect()
     }
-}
-
-impl CSVTable {
-    /// Creates a table from a CSV file.
-    pub fn try_create_table(filename: &str) -> Result<TableRef> {
-        let mut file = File::open(env::current_dir()?.join(Path::new(filename))).unwrap();
-
-        // Uses Arrow's CSV Reader to get the table's Schema.
-        let (arrow_schema, _) = csv::reader::infer_reader_schema(&mut file, b',', Some(3), true)?;
-        // Converts to our own Schema format.
-        let schema = Schema::from(&arrow_schema);
-
-        file.rewind()?;
 
+    /// Reads `batch_size` rows of the file at a time, only the selected columns are parsed
+    fn scan_stream(&self, projection: Option<Vec<usize>>) -> Result<RecordBatchStream<'_>> {
+        let file = File::open(&self.path)
+            .with_context(|| format!("Cannot open CSV file {}", self.path.display()))?;
         let reader = csv::Reader::new(
             file,
-            Arc::new(arrow_schema),
-            true,
-            Some(b','),
-            1_000_000,
-            None,
+            Arc::new(self.schema.clone().into()),
+            self.options.has_header,
+            Some(self.options.delimiter),
+            self.options.batch_size,
             None,
+            projection,
             None,
         );
+        Ok(Box::new(reader.map(move |batch| {
+            batch.with_context(|| format!("Cannot read CSV file {}", self.path.display()))
+        })))
+    }
 
-        let mut batches = vec![];
+    fn source_type(&self) -> String {
+        "CSV file".to_string()
+    }
+}
 
-        for record in reader.into_iter() {
-            batches.push(record?);
+/// Options for reading a CSV file
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Whether the first line of the file holds the column names
+    pub has_header: bool,
+    pub delimiter: u8,
+    /// The schema of the file, it is inferred from the first lines if not given
+    pub schema: Option<Schema>,
+    /// The number of rows read at a time
+    pub batch_size: usize,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            has_header: true,
+            delimiter: b',',
+            schema: None,
+            batch_size: 1024,
         }
+    }
+}
 
-        Ok(Arc::new(Self { schema, batches }))
+impl CSVTable {
+    /// Creates a table from a CSV file.
+    pub fn try_create_table(filename: &str) -> Result<TableRef> {
+        CSVTable::try_create_table_with_options(filename, &CsvOptions::default())
+    }
+
+    /// Creates a table from a CSV file read with the given options. Only the schema is read
+    /// here, the records are read when the table is scanned.
+    pub fn try_create_table_with_options(filename: &str, options: &CsvOptions) -> Result<TableRef> {
+        let path = env::current_dir()?.join(Path::new(filename));
+        let mut file =
+            File::open(&path).with_context(|| format!("Cannot open CSV file {}", filename))?;
+
+        let schema = match &options.schema {
+            Some(schema) => schema.clone(),
+            None => {
+                // Uses Arrow's CSV Reader to get the table's Schema.
+                let (arrow_schema, _) = csv::reader::infer_reader_schema(
+                    &mut file,
+                    options.delimiter,
+                    Some(3),
+                    options.has_header,
+                )?;
+                // Converts to our own Schema format.
+                Schema::from(&arrow_schema)
+            }
+        };
+
+        Ok(Arc::new(Self {
+            schema,
+            path,
+            options: options.clone(),
+        }))
     }
 }
 
@@ -80,6 +116,9 @@ mod tests {
     };
 
     use super::*;
+    use crate::datatype::field::Field;
+    use crate::physical_plan::limit::Limit;
+    use crate::physical_plan::scan::Scan;
 
     #[test]
     fn test_infer_schema_from_csv() -> Result<()> {
@@ -142,4 +181,61 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_read_with_options() -> Result<()> {
+        // The header is read as a row of strings
+        let options = CsvOptions {
+            has_header: false,
+            ..CsvOptions::default()
+        };
+        let table = CSVTable::try_create_table_with_options("data/test.csv", &options)?;
+        assert_eq!(table.schema().field(0).data_type(), &DataType::Utf8);
+        assert_eq!(table.scan(None)?[0].num_rows(), 6);
+
+        let options = CsvOptions {
+            schema: Some(Schema::new(vec![
+                Field::new("a", DataType::Int64, false),
+                Field::new("b", DataType::Utf8, false),
+                Field::new("c", DataType::Float64, false),
+                Field::new("d", DataType::Utf8, false),
+            ])),
+            ..CsvOptions::default()
+        };
+        let table = CSVTable::try_create_table_with_options("data/test.csv", &options)?;
+        let batches = table.scan(None)?;
+        assert_eq!(
+            &(Arc::new(Float64Array::from(vec![24.0, 24.0, 18.0, 18.0, 26.0])) as ArrayRef),
+            batches[0].column(2)
+        );
+        assert_eq!(
+            &(Arc::new(StringArray::from(vec![
+                "0", "100", "99.99", "99.98", "99.97"
+            ])) as ArrayRef),
+            batches[0].column(3)
+        );
+
+        Ok(())
+    }
+    #[test]
+    fn scan_reads_batches_lazily() -> Result<()> {
+        // The last row is not a number, only a scan that reaches it fails
+        let options = CsvOptions {
+            schema: Some(Schema::new(vec![Field::new("n", DataType::Int64, false)])),
+            batch_size: 2,
+            ..CsvOptions::default()
+        };
+        let table = CSVTable::try_create_table_with_options("data/bad_row.csv", &options)?;
+        assert!(table.scan(None).is_err());
+
+        let scan = Scan::new(table, None);
+        let batches = Limit::new(scan.clone(), 0, Some(1)).execute()?;
+        assert_eq!(
+            &(Arc::new(Int64Array::from(vec![1])) as ArrayRef),
+            batches[0].column(0)
+        );
+        assert_eq!(scan.metrics().unwrap().output_batches, 1);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,157 @@
+use super::table::{Table, TableRef};
+use crate::datatype::schema::Schema;
+use crate::error::{Error, Result};
+use arrow::array::Array;
+use arrow::record_batch::RecordBatch;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+#[derive(Debug)]
+/// Stores the records of a table in memory, rows are appended by `insert`.
+pub struct MemoryTable {
+    schema: Schema,
+    batches: RefCell<Vec<RecordBatch>>,
+}
+
+impl MemoryTable {
+    /// Creates a table holding the batches, they are checked against the schema like inserted batches.
+    pub fn try_create_table(schema: Schema, batches: Vec<RecordBatch>) -> Result<TableRef> {
+        let table = Self {
+            schema,
+            batches: RefCell::new(vec![]),
+        };
+        table.insert(batches)?;
+        Ok(Arc::new(table))
+    }
+
+    /// Converts a batch to the schema of the table. The columns must have the types of the
+    /// schema, and may only contain NULLs if their field is nullable.
+    fn check_batch(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let fields = self.schema.fields();
+        if batch.num_columns() != fields.len() {
+            return Err(Error::PhysicalPlanError(format!(
+                "The table has {} columns, but the inserted rows have {}",
+                fields.len(),
+                batch.num_columns()
+            )));
+        }
+
+        for (column, field) in batch.columns().iter().zip(fields) {
+            if column.data_type() != field.data_type() {
+                return Err(Error::PhysicalPlanError(format!(
+                    "Column {} is {:?}, but the inserted values are {:?}",
+                    field.name(),
+                    field.data_type(),
+                    column.data_type()
+                )));
+            }
+            if !field.is_nullable() && column.null_count() > 0 {
+                return Err(Error::PhysicalPlanError(format!(
+                    "Column {} cannot contain NULL values",
+                    field.name()
+                )));
+            }
+        }
+
+        Ok(RecordBatch::try_new(
+            Arc::new(self.schema.clone().into()),
+            batch.columns().to_vec(),
+        )?)
+    }
+}
+
+impl Table for MemoryTable {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn scan(&self, projection: Option<Vec<usize>>) -> Result<Vec<RecordBatch>> {
+        self.batches
+            .borrow()
+            .iter()
+            .map(|record_batch| match &projection {
+                Some(projection) => Ok(record_batch.project(projection)?),
+                None => Ok(record_batch.clone()),
+            })
+            .collect()
+    }
+
+    fn source_type(&self) -> String {
+        "Memory".to_string()
+    }
+
+    /// Nothing is inserted if any of the batches does not fit the schema
+    fn insert(&self, batches: Vec<RecordBatch>) -> Result<usize> {
+        let batches = batches
+            .iter()
+            .map(|batch| self.check_batch(batch))
+            .collect::<Result<Vec<_>>>()?;
+        let rows = batches.iter().map(|batch| batch.num_rows()).sum();
+        self.batches.borrow_mut().extend(batches);
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatype::field::Field;
+    use arrow::array::{ArrayRef, Int64Array, StringArray};
+    use arrow::datatypes::DataType;
+
+    #[test]
+    fn insert_into_memory_table() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        let table = MemoryTable::try_create_table(schema.clone(), vec![])?;
+        assert!(table.scan(None)?.is_empty());
+
+        let batch = |ids: Vec<Option<i64>>, names: Vec<Option<&str>>| {
+            RecordBatch::try_new(
+                Arc::new(schema.clone().into()),
+                vec![
+                    Arc::new(Int64Array::from(ids)) as ArrayRef,
+                    Arc::new(StringArray::from(names)) as ArrayRef,
+                ],
+            )
+        };
+
+        let rows = table.insert(vec![
+            batch(vec![Some(1), Some(2)], vec![Some("a"), None])?,
+            batch(vec![Some(3)], vec![Some("c")])?,
+        ])?;
+        assert_eq!(rows, 3);
+
+        // id is not nullable, so the whole insert fails
+        assert!(table
+            .insert(vec![
+                batch(vec![Some(4)], vec![Some("d")])?,
+                batch(vec![None], vec![Some("e")])?,
+            ])
+            .is_err());
+
+        let batches = table.scan(Some(vec![1]))?;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(
+            batches[0].column(0),
+            &(Arc::new(StringArray::from(vec![Some("a"), None])) as ArrayRef)
+        );
+
+        // The types must match exactly
+        let strings = RecordBatch::try_new(
+            Arc::new(arrow::datatypes::Schema::new(vec![
+                arrow::datatypes::Field::new("id", DataType::Utf8, false),
+                arrow::datatypes::Field::new("name", DataType::Utf8, false),
+            ])),
+            vec![
+                Arc::new(StringArray::from(vec!["5"])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["f"])) as ArrayRef,
+            ],
+        )?;
+        assert!(table.insert(vec![strings]).is_err());
+
+        Ok(())
+    }
+}
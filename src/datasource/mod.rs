@@ -1,2 +1,3 @@
 pub mod csv_table;
+pub mod memory_table;
 pub mod table;
@@ -1,5 +1,6 @@
 use crate::datatype::schema::Schema;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::physical_plan::physical_plan::RecordBatchStream;
 use arrow::record_batch::RecordBatch;
 use std::fmt::Debug;
 use std::sync::Arc;
@@ -14,6 +15,20 @@ pub trait Table: Debug {
     /// Scan the data source, selecting the specified columns
     fn scan(&self, projection: Option<Vec<usize>>) -> Result<Vec<RecordBatch>>;
 
+    /// Scan the data source lazily, one batch at a time
+    fn scan_stream(&self, projection: Option<Vec<usize>>) -> Result<RecordBatchStream<'_>> {
+        Ok(Box::new(self.scan(projection)?.into_iter().map(Ok)))
+    }
+
     /// Returns the type of data source
     fn source_type(&self) -> String;
+
+    /// Appends the batches to the data source and returns the number of inserted rows,
+    /// data sources are read-only unless they implement this
+    fn insert(&self, _batches: Vec<RecordBatch>) -> Result<usize> {
+        Err(Error::PhysicalPlanError(format!(
+            "Cannot insert into a {}, it is read-only",
+            self.source_type()
+        )))
+    }
 }
@@ -21,6 +21,7 @@ impl ColumnArray {
         }
     }
 
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_array(self) -> ArrayRef {
         match self {
             ColumnArray::Array(array_ref) => array_ref,
@@ -3,25 +3,51 @@ use arrow::datatypes::{self, DataType};
 #[derive(Debug, Clone)]
 /// Field provides the name and data type for a field within a schema,
 /// and specifies whether it allows null values or not.
+/// A field may be qualified by the name of the relation (table or alias) it comes from.
 pub struct Field {
     pub field: datatypes::Field,
+    pub relation: Option<String>,
 }
 
 impl Field {
     pub fn new(name: &str, data_type: DataType, nullable: bool) -> Self {
         Self {
             field: datatypes::Field::new(name, data_type, nullable),
+            relation: None,
         }
     }
 
     pub fn from(field: datatypes::Field) -> Self {
-        Self { field }
+        Self {
+            field,
+            relation: None,
+        }
+    }
+
+    /// Creates a copy of the field qualified by `relation`
+    pub fn with_relation(&self, relation: Option<String>) -> Self {
+        Self {
+            field: self.field.clone(),
+            relation,
+        }
     }
 
     pub fn name(&self) -> &String {
         self.field.name()
     }
 
+    /// Returns `relation.name` if the field is qualified, otherwise the bare name
+    pub fn qualified_name(&self) -> String {
+        match &self.relation {
+            Some(relation) => format!("{}.{}", relation, self.name()),
+            None => self.name().clone(),
+        }
+    }
+
+    pub fn relation(&self) -> Option<&String> {
+        self.relation.as_ref()
+    }
+
     pub fn data_type(&self) -> &DataType {
         self.field.data_type()
     }
@@ -31,6 +57,22 @@ impl Field {
     }
 }
 
+/// Returns the type that values of both types can be converted to,
+/// `None` if the types are not compatible
+pub fn common_type(left: &DataType, right: &DataType) -> Option<DataType> {
+    match (left, right) {
+        (left, right) if left == right => Some(left.clone()),
+        (DataType::Null, other) | (other, DataType::Null) => Some(other.clone()),
+        (DataType::Float64, DataType::Int64 | DataType::UInt64)
+        | (DataType::Int64 | DataType::UInt64, DataType::Float64) => Some(DataType::Float64),
+        // Unsigned values are cast to Int64, a value above i64::MAX is an error
+        (DataType::Int64, DataType::UInt64) | (DataType::UInt64, DataType::Int64) => {
+            Some(DataType::Int64)
+        }
+        _ => None,
+    }
+}
+
 impl From<Field> for datatypes::Field {
     fn from(f: Field) -> Self {
         f.field.clone()
@@ -1,12 +1,16 @@
 use crate::datatype::field::Field;
+use crate::error::{Error, Result};
 use arrow::{
     self,
     array::{
-        new_null_array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray, UInt64Array,
+        new_null_array, Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray,
+        UInt64Array,
     },
     datatypes::DataType,
 };
-use std::{iter::repeat, sync::Arc, vec};
+use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::{iter::repeat_n, sync::Arc, vec};
 
 #[derive(Debug, Clone)]
 /// Scalar values can be converted to array values
@@ -25,6 +29,61 @@ pub enum Scalar {
     Utf8(Option<String>),
 }
 
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Scalar::Null, Scalar::Null) => true,
+            (Scalar::Boolean(l), Scalar::Boolean(r)) => l == r,
+            // Compares the bit patterns so that floats can be used as hash keys.
+            (Scalar::Float64(l), Scalar::Float64(r)) => l.map(float_bits) == r.map(float_bits),
+            (Scalar::Int64(l), Scalar::Int64(r)) => l == r,
+            (Scalar::UInt64(l), Scalar::UInt64(r)) => l == r,
+            (Scalar::Utf8(l), Scalar::Utf8(r)) => l == r,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Scalar {}
+
+/// The bit pattern of a float, with -0.0 mapped to 0.0 and all NaNs to one NaN,
+/// so that values that are equal in SQL are one key
+fn float_bits(v: f64) -> u64 {
+    if v == 0.0 {
+        0.0f64.to_bits()
+    } else if v.is_nan() {
+        f64::NAN.to_bits()
+    } else {
+        v.to_bits()
+    }
+}
+
+impl Hash for Scalar {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Scalar::Null => {}
+            Scalar::Boolean(v) => v.hash(state),
+            Scalar::Float64(v) => v.map(float_bits).hash(state),
+            Scalar::Int64(v) => v.hash(state),
+            Scalar::UInt64(v) => v.hash(state),
+            Scalar::Utf8(v) => v.hash(state),
+        }
+    }
+}
+
+/// Macro used to get the value at the specified index of an array as a scalar value
+macro_rules! array_value_to_scalar {
+    ($ARRAY:expr, $INDEX:expr, $ARRAY_TYPE:ident, $SCALAR_TYPE:ident) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        if array.is_null($INDEX) {
+            Scalar::$SCALAR_TYPE(None)
+        } else {
+            Scalar::$SCALAR_TYPE(Some(array.value($INDEX).into()))
+        }
+    }};
+}
+
 /// Macro used to convert scalar values to array based on the scalar value type
 macro_rules! scalar_to_array {
     ($Data_Type:ident, $ARRAY_TYPE:ident, $VALUE:expr, $Size:expr) => {{
@@ -48,7 +107,39 @@ impl Scalar {
         }
     }
 
+    /// Whether the value is NULL, of any type
+    pub fn is_null(&self) -> bool {
+        matches!(
+            self,
+            Scalar::Null
+                | Scalar::Boolean(None)
+                | Scalar::Float64(None)
+                | Scalar::Int64(None)
+                | Scalar::UInt64(None)
+                | Scalar::Utf8(None)
+        )
+    }
+
+    /// Gets the value at index `i` of the array as a scalar value
+    pub fn try_from_array(array: &ArrayRef, i: usize) -> Result<Self> {
+        Ok(match array.data_type() {
+            DataType::Null => Scalar::Null,
+            DataType::Boolean => array_value_to_scalar!(array, i, BooleanArray, Boolean),
+            DataType::Float64 => array_value_to_scalar!(array, i, Float64Array, Float64),
+            DataType::Int64 => array_value_to_scalar!(array, i, Int64Array, Int64),
+            DataType::UInt64 => array_value_to_scalar!(array, i, UInt64Array, UInt64),
+            DataType::Utf8 => array_value_to_scalar!(array, i, StringArray, Utf8),
+            other => {
+                return Err(Error::IntervalError(format!(
+                    "Cannot convert a value of type {:?} to a scalar",
+                    other
+                )))
+            }
+        })
+    }
+
     /// Converts the scalar value to an Array, where the Array length is the size recorded in ColumnArray
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_array(self, size: usize) -> ArrayRef {
         match self {
             Scalar::Null => new_null_array(&DataType::Null, size),
@@ -57,20 +148,22 @@ impl Scalar {
             Scalar::Int64(v) => scalar_to_array!(Int64, Int64Array, v, size),
             Scalar::UInt64(v) => scalar_to_array!(UInt64, UInt64Array, v, size),
             Scalar::Utf8(v) => match v {
-                Some(str) => Arc::new(StringArray::from_iter_values(repeat(str).take(size))),
+                Some(str) => Arc::new(StringArray::from_iter_values(repeat_n(str, size))),
                 None => new_null_array(&DataType::Utf8, size),
             },
         }
     }
+}
 
-    pub fn to_string(&self) -> String {
+impl Display for Scalar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Scalar::Boolean(Some(val)) => val.to_string(),
-            Scalar::Int64(Some(val)) => val.to_string(),
-            Scalar::UInt64(Some(val)) => val.to_string(),
-            Scalar::Float64(Some(val)) => val.to_string(),
-            Scalar::Utf8(Some(val)) => val.to_string(),
-            _ => "null".to_string(),
+            Scalar::Boolean(Some(val)) => write!(f, "{}", val),
+            Scalar::Int64(Some(val)) => write!(f, "{}", val),
+            Scalar::UInt64(Some(val)) => write!(f, "{}", val),
+            Scalar::Float64(Some(val)) => write!(f, "{}", val),
+            Scalar::Utf8(Some(val)) => write!(f, "{}", val),
+            _ => write!(f, "null"),
         }
     }
 }
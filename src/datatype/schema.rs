@@ -1,4 +1,4 @@
-use crate::datatype::field::Field;
+use crate::datatype::field::{common_type, Field};
 use crate::error::{Error, Result};
 use arrow::datatypes;
 
@@ -23,9 +23,7 @@ impl Schema {
             schema
                 .fields()
                 .iter()
-                .map(|field| Field {
-                    field: field.clone(),
-                })
+                .map(|field| Field::from(field.clone()))
                 .collect(),
         )
     }
@@ -38,22 +36,94 @@ impl Schema {
         &self.fields[i]
     }
 
+    /// Finds the index of a column, `name` is either `relation.column` or a bare column name.
+    /// A bare name that matches columns of several relations is ambiguous.
     pub fn index_of(&self, name: &str) -> Result<usize> {
-        for (i, field) in self.fields.iter().enumerate() {
-            if field.name() == name {
-                return Ok(i);
-            }
+        let qualified_matches = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.qualified_name() == name)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        // A qualified name matches several fields when a relation is joined with itself
+        let matches = if qualified_matches.is_empty() {
+            self.fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| field.name() == name)
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        } else {
+            qualified_matches
+        };
+        match matches.len() {
+            0 => Err(Error::NoSuchField(name.to_string())),
+            1 => Ok(matches[0]),
+            _ => Err(Error::AmbiguousColumn(format!(
+                "Column reference {} is ambiguous, it could refer to {}",
+                name,
+                matches
+                    .iter()
+                    .map(|i| self.fields[*i].qualified_name())
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            ))),
         }
-        Err(Error::NoSuchField)
     }
 
     pub fn find_field_by_name(&self, name: &str) -> Result<Field> {
-        for field in self.fields.iter() {
-            if field.name() == name {
-                return Ok(field.clone());
-            }
+        Ok(self.fields[self.index_of(name)?].clone())
+    }
+
+    /// Creates a copy of the schema with all fields qualified by `relation`
+    pub fn with_relation(&self, relation: &str) -> Self {
+        Self::new(
+            self.fields
+                .iter()
+                .map(|field| field.with_relation(Some(relation.to_string())))
+                .collect(),
+        )
+    }
+
+    /// Returns the schema of the rows of both schemas combined by a set operation.
+    /// The schemas must have the same number of columns with compatible types, the names
+    /// are taken from this schema and a column is nullable if it is nullable in either schema.
+    pub fn union(&self, other: &Schema) -> Result<Self> {
+        if self.fields.len() != other.fields.len() {
+            return Err(Error::LogicalPlanError(format!(
+                "Set operations require the same number of columns, found {} and {}",
+                self.fields.len(),
+                other.fields.len()
+            )));
         }
-        Err(Error::NoSuchField)
+
+        let fields = self
+            .fields
+            .iter()
+            .zip(other.fields.iter())
+            .map(|(left, right)| {
+                let data_type =
+                    common_type(left.data_type(), right.data_type()).ok_or_else(|| {
+                        Error::LogicalPlanError(format!(
+                            "Column {} is {:?} on one side of a set operation but {:?} on the other",
+                            left.name(),
+                            left.data_type(),
+                            right.data_type()
+                        ))
+                    })?;
+                Ok(
+                    Field::new(
+                        left.name(),
+                        data_type,
+                        left.is_nullable() || right.is_nullable(),
+                    )
+                    .with_relation(left.relation().cloned()),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::new(fields))
     }
 
     pub fn join(&self, right: &Schema) -> Self {
@@ -1,16 +1,97 @@
+use crate::sql::tokenizer::Span;
 use arrow::error::ArrowError;
+use std::fmt::{self, Display, Formatter};
 use std::io;
 pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
-    NoSuchField,
+    /// A field that is not in a schema, with the name it was looked up by
+    NoSuchField(String),
     ArrowError(ArrowError),
     IOError(io::Error),
+    /// A table or view that is not in the catalog, with its name
     NoSuchTable(String),
     LogicalPlanError(String),
     PhysicalPlanError(String),
     IntervalError(String),
+    /// A column that is not in scope, with its name
     NoSuchColumn(String),
+    AmbiguousColumn(String),
+    ParserError(String),
+    /// A query that uses something unsupported, e.g. an operator on a type without a kernel for it
+    NotImplemented(String),
+    /// An error together with what was being done when it occurred, e.g. which file was read
+    Context {
+        context: String,
+        source: Box<Error>,
+    },
+    /// An error caused by the part of a SQL statement at `span`
+    Sql {
+        source: Box<Error>,
+        span: Span,
+    },
+}
+
+impl Error {
+    /// Wraps the error with a description of what was being done
+    pub fn context(self, context: impl Into<String>) -> Self {
+        Error::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Points the error at the part of a SQL statement that caused it,
+    /// an error that already points somewhere is returned unchanged
+    pub fn at(self, span: Option<Span>) -> Self {
+        match (self, span) {
+            (error, Some(span)) if !matches!(error, Error::Sql { .. }) => Error::Sql {
+                source: Box::new(error),
+                span,
+            },
+            (error, _) => error,
+        }
+    }
+
+    /// Returns the error without its context and SQL span
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } | Error::Sql { source, .. } => source.root(),
+            error => error,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoSuchField(name) => write!(f, "Field {} does not exist", name),
+            Error::ArrowError(e) => write!(f, "Arrow error: {}", e),
+            Error::IOError(e) => write!(f, "IO error: {}", e),
+            Error::NoSuchTable(name) => write!(f, "Table {} does not exist", name),
+            Error::NoSuchColumn(name) => write!(f, "Column {} does not exist", name),
+            Error::LogicalPlanError(message)
+            | Error::PhysicalPlanError(message)
+            | Error::IntervalError(message)
+            | Error::AmbiguousColumn(message)
+            | Error::ParserError(message) => write!(f, "{}", message),
+            Error::NotImplemented(message) => write!(f, "Not implemented: {}", message),
+            Error::Context { context, source } => write!(f, "{}: {}", context, source),
+            Error::Sql { source, span } => write!(f, "{} at {}", source, span),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ArrowError(e) => Some(e),
+            Error::IOError(e) => Some(e),
+            Error::Context { source, .. } | Error::Sql { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
 }
 
 impl From<ArrowError> for Error {
@@ -24,3 +105,50 @@ impl From<io::Error> for Error {
         Error::IOError(value)
     }
 }
+
+/// Adds context to the error of a result, e.g. `File::open(path).with_context(|| ...)`
+pub trait ResultExt<T> {
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|e| e.into().context(context()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::tokenizer::Location;
+    use std::error::Error as _;
+
+    #[test]
+    fn display_error_with_context_and_span() {
+        let err: Result<()> = Err(io::Error::new(io::ErrorKind::NotFound, "no such file"))
+            .with_context(|| "Cannot open CSV file missing.csv");
+        let err = err.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot open CSV file missing.csv: IO error: no such file"
+        );
+        assert!(matches!(err.root(), Error::IOError(_)));
+        assert!(err.source().is_some());
+
+        let err = Error::Sql {
+            source: Box::new(Error::NoSuchColumn("nme".to_string())),
+            span: Span {
+                start: Location { line: 1, column: 8 },
+                end: Location {
+                    line: 1,
+                    column: 10,
+                },
+            },
+        };
+        assert_eq!(
+            err.to_string(),
+            "Column nme does not exist at line 1, columns 8-10"
+        );
+        assert!(matches!(err.root(), Error::NoSuchColumn(name) if name == "nme"));
+    }
+}
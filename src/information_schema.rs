@@ -0,0 +1,155 @@
+use crate::datasource::memory_table::MemoryTable;
+use crate::datasource::table::TableRef;
+use crate::datatype::field::Field;
+use crate::datatype::schema::Schema;
+use crate::error::Result;
+use crate::logical_plan::logical_plan::LogicalPlan;
+use arrow::array::{ArrayRef, StringArray, UInt64Array};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The tables and views of the catalog
+pub const TABLES: &str = "information_schema.tables";
+/// The columns of every table and view of the catalog
+pub const COLUMNS: &str = "information_schema.columns";
+
+/// Generates the information_schema table named `name` from the tables and views of a catalog,
+/// returns None for any other name. The rows are a snapshot taken when the table is read.
+pub fn information_schema_table(
+    name: &str,
+    tables: &HashMap<String, TableRef>,
+    views: &HashMap<String, LogicalPlan>,
+) -> Result<Option<TableRef>> {
+    let relations = relations(tables, views);
+    match name {
+        TABLES => tables_table(&relations).map(Some),
+        COLUMNS => columns_table(&relations).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// The rows of `DESCRIBE`, one for each column of the schema
+pub fn describe_table(schema: &Schema) -> Result<TableRef> {
+    let mut columns = Columns::default();
+    columns.push("", schema);
+    let output_schema = Schema::new(vec![
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("data_type", DataType::Utf8, false),
+        Field::new("is_nullable", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(output_schema.clone().into()),
+        vec![
+            Arc::new(StringArray::from(columns.column_names)) as ArrayRef,
+            Arc::new(StringArray::from(columns.data_types)) as ArrayRef,
+            Arc::new(StringArray::from(columns.is_nullable)) as ArrayRef,
+        ],
+    )?;
+    MemoryTable::try_create_table(output_schema, vec![batch])
+}
+
+/// A table or view of the catalog
+struct Relation {
+    name: String,
+    table_type: &'static str,
+    /// The source type of a table, views have none
+    source_type: Option<String>,
+    schema: Schema,
+}
+
+/// The tables and views sorted by their name
+fn relations(
+    tables: &HashMap<String, TableRef>,
+    views: &HashMap<String, LogicalPlan>,
+) -> Vec<Relation> {
+    let mut relations: Vec<_> = tables
+        .iter()
+        .map(|(name, table)| Relation {
+            name: name.clone(),
+            table_type: "BASE TABLE",
+            source_type: Some(table.source_type()),
+            schema: table.schema().clone(),
+        })
+        .chain(views.iter().map(|(name, plan)| Relation {
+            name: name.clone(),
+            table_type: "VIEW",
+            source_type: None,
+            schema: plan.schema().clone(),
+        }))
+        .collect();
+    relations.sort_by(|a, b| a.name.cmp(&b.name));
+    relations
+}
+
+fn tables_table(relations: &[Relation]) -> Result<TableRef> {
+    let schema = Schema::new(vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("table_type", DataType::Utf8, false),
+        Field::new("source_type", DataType::Utf8, true),
+    ]);
+    let table_names = StringArray::from_iter_values(relations.iter().map(|r| &r.name));
+    let table_types = StringArray::from_iter_values(relations.iter().map(|r| r.table_type));
+    let source_types: StringArray = relations.iter().map(|r| r.source_type.clone()).collect();
+
+    let batch = RecordBatch::try_new(
+        Arc::new(schema.clone().into()),
+        vec![
+            Arc::new(table_names) as ArrayRef,
+            Arc::new(table_types) as ArrayRef,
+            Arc::new(source_types) as ArrayRef,
+        ],
+    )?;
+    MemoryTable::try_create_table(schema, vec![batch])
+}
+
+fn columns_table(relations: &[Relation]) -> Result<TableRef> {
+    let mut columns = Columns::default();
+    for relation in relations {
+        columns.push(&relation.name, &relation.schema);
+    }
+
+    let schema = Schema::new(vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("ordinal_position", DataType::UInt64, false),
+        Field::new("data_type", DataType::Utf8, false),
+        Field::new("is_nullable", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema.clone().into()),
+        vec![
+            Arc::new(StringArray::from(columns.table_names)) as ArrayRef,
+            Arc::new(StringArray::from(columns.column_names)) as ArrayRef,
+            Arc::new(UInt64Array::from(columns.ordinal_positions)) as ArrayRef,
+            Arc::new(StringArray::from(columns.data_types)) as ArrayRef,
+            Arc::new(StringArray::from(columns.is_nullable)) as ArrayRef,
+        ],
+    )?;
+    MemoryTable::try_create_table(schema, vec![batch])
+}
+
+/// The values of the columns of information_schema.columns
+#[derive(Default)]
+struct Columns {
+    table_names: Vec<String>,
+    column_names: Vec<String>,
+    ordinal_positions: Vec<u64>,
+    data_types: Vec<String>,
+    is_nullable: Vec<&'static str>,
+}
+
+impl Columns {
+    fn push(&mut self, table_name: &str, schema: &Schema) {
+        for (i, field) in schema.fields().iter().enumerate() {
+            self.table_names.push(table_name.to_string());
+            self.column_names.push(field.name().clone());
+            // Positions are numbered from 1 like in SQL
+            self.ordinal_positions.push(i as u64 + 1);
+            self.data_types.push(format!("{:?}", field.data_type()));
+            self.is_nullable
+                .push(if field.is_nullable() { "YES" } else { "NO" });
+        }
+    }
+}
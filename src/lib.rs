@@ -1,10 +1,16 @@
 #![allow(dead_code)]
+// Operators and expressions are created by `new` as the shared `Arc<dyn ...>` they are planned
+// with, and the plans are executed on a single thread
+#![allow(clippy::new_ret_no_self, clippy::arc_with_non_send_sync)]
 
 mod catalog;
 mod datasource;
 mod datatype;
 mod error;
+mod information_schema;
 mod logical_plan;
 mod physical_plan;
 pub mod planner;
+mod prepared_statement;
+mod sql;
 mod util;
@@ -1,11 +1,16 @@
-use crate::datatype::field::Field;
+use crate::datatype::field::{common_type, Field};
 use crate::datatype::schema::Schema;
 use crate::error::Error::LogicalPlanError;
 use crate::error::Result;
-use crate::logical_plan::logical_expr::{AggregateFuncExpr, LogicalExpr};
+use crate::logical_plan::logical_expr::{
+    find_aggregate_exprs, rebase_expr, AggregateFunc, AggregateFuncExpr, LogicalExpr, SortExpr,
+    WindowFuncExpr,
+};
 use crate::logical_plan::logical_plan::{
-    Aggregation, Join, JoinType, LogicalPlan, Projection, Selection,
+    Aggregation, Distinct, EmptyRelation, Except, Explain, Intersect, Join, JoinType, Limit,
+    LogicalPlan, Projection, RecursiveQuery, Selection, Sort, SubqueryAlias, Union, Values, Window,
 };
+use arrow::datatypes::DataType;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -21,6 +26,57 @@ impl DataFrame {
         Self { plan }
     }
 
+    /// Creates a DataFrame whose rows are given by expressions that do not read any columns.
+    /// The columns are named `column1`, `column2`, ... and have the common type of their values.
+    pub fn values(values: Vec<Vec<LogicalExpr>>) -> Result<Self> {
+        let empty = LogicalPlan::EmptyRelation(EmptyRelation {
+            schema: Schema::new(vec![]),
+        });
+        let width = values.first().map_or(0, |row| row.len());
+
+        let mut fields: Vec<Field> = vec![];
+        for row in &values {
+            if row.len() != width {
+                return Err(LogicalPlanError(format!(
+                    "All rows of VALUES must have the same number of columns, found {} and {}",
+                    width,
+                    row.len()
+                )));
+            }
+            for (i, expr) in row.iter().enumerate() {
+                let field = expr.to_field(&empty)?;
+                let nullable = field.is_nullable() || field.data_type() == &DataType::Null;
+                match fields.get_mut(i) {
+                    None => fields.push(Field::new(
+                        &format!("column{}", i + 1),
+                        field.data_type().clone(),
+                        nullable,
+                    )),
+                    Some(column) => {
+                        let data_type = common_type(column.data_type(), field.data_type())
+                            .ok_or_else(|| {
+                                LogicalPlanError(format!(
+                                    "{} of VALUES has values of types {:?} and {:?}",
+                                    column.name(),
+                                    column.data_type(),
+                                    field.data_type()
+                                ))
+                            })?;
+                        *column =
+                            Field::new(column.name(), data_type, column.is_nullable() || nullable);
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            plan: LogicalPlan::Values(Values {
+                values,
+                schema: Schema::new(fields),
+            }),
+        })
+    }
+
     pub fn project(self, exprs: Vec<LogicalExpr>) -> Result<Self> {
         let mut fields = vec![];
         for expr in &exprs {
@@ -39,6 +95,14 @@ impl DataFrame {
     }
 
     pub fn select(self, expr: LogicalExpr) -> Result<Self> {
+        let data_type = expr.to_field(&self.plan)?.data_type().clone();
+        if !matches!(data_type, DataType::Boolean | DataType::Null) {
+            return Err(LogicalPlanError(format!(
+                "The predicate {} is {:?}, but it must be Boolean",
+                expr, data_type
+            )));
+        }
+
         Ok(Self {
             plan: LogicalPlan::Selection(Selection {
                 input: Arc::new(self.plan),
@@ -49,27 +113,148 @@ impl DataFrame {
 
     pub fn aggregate(
         self,
-        group_expr: LogicalExpr,
+        group_expr: Vec<LogicalExpr>,
         aggr_expr: Vec<AggregateFuncExpr>,
     ) -> Result<Self> {
-        let mut fields = vec![group_expr.to_field(&self.plan)?];
-        fields.append(
-            &mut aggr_expr
-                .iter()
-                .map(|expr| expr.to_field(&self.plan).unwrap())
-                .collect::<Vec<Field>>(),
-        );
+        let grouping_sets = vec![(0..group_expr.len()).collect()];
+        self.aggregate_grouping_sets(group_expr, grouping_sets, aggr_expr)
+    }
+
+    /// Groups the rows by each of the grouping sets, which are indices into `group_expr`,
+    /// e.g. `ROLLUP(a, b)` is the group exprs `[a, b]` with the sets `[[0, 1], [0], []]`.
+    /// The group columns that are not in the set of a group are NULL.
+    pub fn aggregate_grouping_sets(
+        self,
+        group_expr: Vec<LogicalExpr>,
+        grouping_sets: Vec<Vec<usize>>,
+        aggr_expr: Vec<AggregateFuncExpr>,
+    ) -> Result<Self> {
+        if grouping_sets.is_empty() {
+            return Err(LogicalPlanError(
+                "An aggregation needs at least one grouping set".to_string(),
+            ));
+        }
+        if let Some(i) = grouping_sets
+            .iter()
+            .flatten()
+            .find(|i| **i >= group_expr.len())
+        {
+            return Err(LogicalPlanError(format!(
+                "Grouping set index {} is out of range for {} group exprs",
+                i,
+                group_expr.len()
+            )));
+        }
+
+        let mut fields = vec![];
+        for (i, expr) in group_expr.iter().enumerate() {
+            let field = expr.to_field(&self.plan)?;
+            if grouping_sets.iter().all(|set| set.contains(&i)) {
+                fields.push(field);
+            } else {
+                fields.push(
+                    Field::new(field.name(), field.data_type().clone(), true)
+                        .with_relation(field.relation().cloned()),
+                );
+            }
+        }
+        for expr in &aggr_expr {
+            if let Some(filter) = &expr.filter {
+                let data_type = filter.to_field(&self.plan)?.data_type().clone();
+                if !matches!(data_type, DataType::Boolean | DataType::Null) {
+                    return Err(LogicalPlanError(format!(
+                        "The filter {} of an aggregate is {:?}, but it must be Boolean",
+                        filter, data_type
+                    )));
+                }
+            }
+            if let AggregateFunc::GROUPING = expr.func {
+                let name = expr.expr.to_field(&self.plan)?.qualified_name();
+                let is_group_expr = fields
+                    .iter()
+                    .take(group_expr.len())
+                    .any(|field| field.qualified_name() == name);
+                if !is_group_expr {
+                    return Err(LogicalPlanError(format!(
+                        "The argument {} of GROUPING must be a GROUP BY expression",
+                        expr.expr
+                    )));
+                }
+            }
+            fields.push(expr.to_field(&self.plan)?);
+        }
         let schema = Schema::new(fields);
         Ok(Self {
             plan: LogicalPlan::Aggregation(Aggregation {
                 input: Arc::new(self.plan),
                 group_expr,
+                grouping_sets,
                 aggr_expr,
                 schema,
             }),
         })
     }
 
+    /// Filters the groups produced by `aggregate`. The predicate may contain aggregate
+    /// functions, aggregates that are not computed yet are added to the aggregation
+    /// and removed from the output again after filtering.
+    pub fn having(self, expr: LogicalExpr) -> Result<Self> {
+        let aggregation = match &self.plan {
+            LogicalPlan::Aggregation(aggregation) => aggregation.clone(),
+            _ => {
+                return Err(LogicalPlanError(
+                    "HAVING can only be applied to an aggregation".to_string(),
+                ))
+            }
+        };
+
+        let input = aggregation.input.as_ref();
+        let mut aggr_expr = aggregation.aggr_expr.clone();
+        find_aggregate_exprs(&expr, input, &mut aggr_expr)?;
+
+        let mut base_exprs = aggregation.group_expr.clone();
+        base_exprs.extend(
+            aggr_expr
+                .iter()
+                .cloned()
+                .map(LogicalExpr::AggregateFuncExpr),
+        );
+        let predicate = rebase_expr(&expr, &base_exprs, input)?;
+
+        if aggr_expr.len() == aggregation.aggr_expr.len() {
+            return self.select(predicate);
+        }
+
+        let output = aggregation
+            .schema
+            .fields()
+            .iter()
+            .map(|field| LogicalExpr::Column(field.qualified_name()))
+            .collect();
+
+        DataFrame::new(input.clone())
+            .aggregate_grouping_sets(aggregation.group_expr, aggregation.grouping_sets, aggr_expr)?
+            .select(predicate)?
+            .project(output)
+    }
+
+    /// Computes window functions for every row, their results are appended to the columns
+    /// of the input and named after the window expressions
+    pub fn window(self, window_expr: Vec<WindowFuncExpr>) -> Result<Self> {
+        let mut fields = vec![];
+        for expr in &window_expr {
+            fields.push(expr.to_field(&self.plan)?);
+        }
+        let schema = self.plan.schema().join(&Schema::new(fields));
+        Ok(Self {
+            plan: LogicalPlan::Window(Window {
+                input: Arc::new(self.plan),
+                window_expr,
+                schema,
+            }),
+        })
+    }
+
     pub fn join(
         self,
         right: &LogicalPlan,
@@ -85,10 +270,29 @@ impl DataFrame {
         let (left_keys, right_keys) = on;
         let on = left_keys
             .into_iter()
-            .zip(right_keys.into_iter())
-            .collect::<Vec<(String, String)>>();
+            .zip(right_keys)
+            .map(|(left, right)| (LogicalExpr::Column(left), LogicalExpr::Column(right)))
+            .collect::<Vec<_>>();
 
-        let join_schema = self.plan.schema().join(right.schema());
+        // The side that may not have a match is nullable in the output
+        let nullable = |schema: &Schema| {
+            Schema::new(
+                schema
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        Field::new(field.name(), field.data_type().clone(), true)
+                            .with_relation(field.relation().cloned())
+                    })
+                    .collect(),
+            )
+        };
+        let join_schema = match join_type {
+            JoinType::Inner => self.plan.schema().join(right.schema()),
+            JoinType::Left => self.plan.schema().join(&nullable(right.schema())),
+            JoinType::Right => nullable(self.plan.schema()).join(right.schema()),
+            JoinType::Semi | JoinType::Anti | JoinType::NullAwareAnti => self.plan.schema().clone(),
+        };
 
         Ok(Self {
             plan: LogicalPlan::Join(Join {
@@ -101,6 +305,156 @@ impl DataFrame {
         })
     }
 
+    pub fn sort(self, exprs: Vec<SortExpr>) -> Result<Self> {
+        // Makes sure every sort expression can be evaluated on the input
+        for sort_expr in &exprs {
+            sort_expr.expr.to_field(&self.plan)?;
+        }
+
+        Ok(Self {
+            plan: LogicalPlan::Sort(Sort {
+                input: Arc::new(self.plan),
+                exprs,
+            }),
+        })
+    }
+
+    /// Skips `skip` rows and then returns at most `fetch` rows, all remaining rows if `fetch` is None
+    pub fn limit(self, skip: usize, fetch: Option<usize>) -> Result<Self> {
+        Ok(Self {
+            plan: LogicalPlan::Limit(Limit {
+                input: Arc::new(self.plan),
+                skip,
+                fetch,
+            }),
+        })
+    }
+
+    /// Removes duplicate rows
+    pub fn distinct(self) -> Result<Self> {
+        Ok(Self {
+            plan: LogicalPlan::Distinct(Distinct {
+                input: Arc::new(self.plan),
+            }),
+        })
+    }
+
+    /// Creates a recursive query, the DataFrame is its static term. `recursive_term` reads the
+    /// rows of the previous iteration with a `WorkTableScan` named `name`.
+    pub fn recursive_query(
+        self,
+        name: &str,
+        recursive_term: LogicalPlan,
+        distinct: bool,
+        max_iterations: usize,
+    ) -> Result<Self> {
+        let static_fields = self.plan.schema().fields();
+        let recursive_fields = recursive_term.schema().fields();
+
+        if static_fields.len() != recursive_fields.len() {
+            return Err(LogicalPlanError(format!(
+                "The static term of {} has {} columns but the recursive term has {}",
+                name,
+                static_fields.len(),
+                recursive_fields.len()
+            )));
+        }
+        for (static_field, recursive_field) in static_fields.iter().zip(recursive_fields) {
+            if static_field.data_type() != recursive_field.data_type() {
+                return Err(LogicalPlanError(format!(
+                    "Column {} of {} is {:?} in the static term but {:?} in the recursive term",
+                    static_field.name(),
+                    name,
+                    static_field.data_type(),
+                    recursive_field.data_type()
+                )));
+            }
+        }
+
+        Ok(Self {
+            plan: LogicalPlan::RecursiveQuery(RecursiveQuery {
+                name: name.to_string(),
+                static_term: Arc::new(self.plan),
+                recursive_term: Arc::new(recursive_term),
+                distinct,
+                max_iterations,
+            }),
+        })
+    }
+
+    /// Returns the rows of both DataFrames, duplicate rows are removed unless `all` is set
+    pub fn union(self, other: DataFrame, all: bool) -> Result<Self> {
+        let schema = self.plan.schema().union(other.schema())?;
+        Ok(Self {
+            plan: LogicalPlan::Union(Union {
+                left: Arc::new(self.plan),
+                right: Arc::new(other.plan),
+                all,
+                schema,
+            }),
+        })
+    }
+
+    /// Returns the rows that are in both DataFrames. With `all`, a row that is `m` times
+    /// in this DataFrame and `n` times in `other` is returned `min(m, n)` times, otherwise once.
+    pub fn intersect(self, other: DataFrame, all: bool) -> Result<Self> {
+        let schema = self.plan.schema().union(other.schema())?;
+        Ok(Self {
+            plan: LogicalPlan::Intersect(Intersect {
+                left: Arc::new(self.plan),
+                right: Arc::new(other.plan),
+                all,
+                schema,
+            }),
+        })
+    }
+
+    /// Returns the rows of this DataFrame that are not in `other`. With `all`, a row that is
+    /// `m` times in this DataFrame and `n` times in `other` is returned `m - n` times,
+    /// otherwise duplicate rows are removed.
+    pub fn except(self, other: DataFrame, all: bool) -> Result<Self> {
+        let schema = self.plan.schema().union(other.schema())?;
+        Ok(Self {
+            plan: LogicalPlan::Except(Except {
+                left: Arc::new(self.plan),
+                right: Arc::new(other.plan),
+                all,
+                schema,
+            }),
+        })
+    }
+
+    /// Qualifies all columns with `alias`, they can then be referenced as `alias.column`
+    pub fn alias(self, alias: &str) -> Result<Self> {
+        let schema = self.plan.schema().with_relation(alias);
+        Ok(Self {
+            plan: LogicalPlan::SubqueryAlias(SubqueryAlias {
+                input: Arc::new(self.plan),
+                alias: alias.to_string(),
+                schema,
+            }),
+        })
+    }
+
+    /// Returns a DataFrame that lists the logical, optimized and physical plans of this one
+    /// instead of executing it. With `verbose`, the plan after each optimizer rule is listed too.
+    /// With `analyze` the plan is executed and its physical plan is shown with the metrics
+    /// of each node
+    pub fn explain(self, verbose: bool, analyze: bool) -> Result<Self> {
+        let schema = Schema::new(vec![
+            Field::new("plan_type", DataType::Utf8, false),
+            Field::new("plan", DataType::Utf8, false),
+        ]);
+        Ok(Self {
+            plan: LogicalPlan::Explain(Explain {
+                plan: Arc::new(self.plan),
+                verbose,
+                analyze,
+                schema,
+            }),
+        })
+    }
+
     pub fn schema(&self) -> &Schema {
         self.plan.schema()
     }
@@ -114,8 +468,13 @@ impl DataFrame {
 mod tests {
     use crate::catalog::Catalog;
     use crate::datatype::scalar::Scalar;
-    use crate::error::Result;
-    use crate::logical_plan::logical_expr::{BinaryExpr, LogicalExpr, Operator};
+    use crate::error::{Error, Result};
+    use crate::logical_plan::logical_expr::{
+        AggregateFunc, AggregateFuncExpr, BinaryExpr, LogicalExpr, Operator,
+    };
+    use crate::logical_plan::logical_plan::JoinType;
+    use crate::planner::QueryPlanner;
+    use arrow::util::pretty;
 
     #[test]
     fn create_logical_plan_by_df() -> Result<()> {
@@ -144,10 +503,87 @@ mod tests {
             \n        Scan:\
             \n          source_type: \"CSV file\"\
             \n          projection: None\
-            \n  schema: Schema { fields: [Field { field: Field { name: \"name\", data_type: Utf8, nullable: false, dict_id: 0, dict_is_ordered: false, metadata: None } }, Field { field: Field { name: \"score\", data_type: Float64, nullable: false, dict_id: 0, dict_is_ordered: false, metadata: None } }] }\n",
+            \n  schema: Schema { fields: [Field { field: Field { name: \"name\", data_type: Utf8, nullable: false, dict_id: 0, dict_is_ordered: false, metadata: None }, relation: None }, Field { field: Field { name: \"score\", data_type: Float64, nullable: false, dict_id: 0, dict_is_ordered: false, metadata: None }, relation: None }] }\n",
             format!("{}", df.plan)
         );
 
         Ok(())
     }
+
+    #[test]
+    fn filter_groups_with_having() -> Result<()> {
+        let mut catalog = Catalog::default();
+        catalog.add_csv_table("test", "data/test.csv")?;
+
+        let max_score = AggregateFuncExpr {
+            func: AggregateFunc::MAX,
+            expr: Box::new(LogicalExpr::Column("score".to_string())),
+            distinct: false,
+            filter: None,
+        };
+        let count_id = AggregateFuncExpr {
+            func: AggregateFunc::COUNT,
+            expr: Box::new(LogicalExpr::Column("id".to_string())),
+            distinct: false,
+            filter: None,
+        };
+
+        // COUNT(id) is only used by the predicate, so it is not part of the output
+        let df = catalog
+            .get_table_df("test")?
+            .aggregate(
+                vec![LogicalExpr::Column("age".to_string())],
+                vec![max_score],
+            )?
+            .having(LogicalExpr::BinaryExpr(BinaryExpr {
+                left: Box::new(LogicalExpr::AggregateFuncExpr(count_id)),
+                op: Operator::Gt,
+                right: Box::new(LogicalExpr::Literal(Scalar::UInt64(Some(1)))),
+            }))?;
+
+        let batches = QueryPlanner::create_physical_plan(df.plan())?.execute()?;
+
+        assert_eq!(
+            pretty::pretty_format_batches(&batches)?.to_string(),
+            "+-----+------------+\
+            \n| age | MAX(score) |\
+            \n+-----+------------+\
+            \n| 24  | 100        |\
+            \n| 18  | 99.99      |\
+            \n+-----+------------+"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_aliased_data_frames() -> Result<()> {
+        let mut catalog = Catalog::default();
+        catalog.add_csv_table("test", "data/test.csv")?;
+        catalog.add_csv_table("salary", "data/salary.csv")?;
+
+        let salary = catalog.get_table_df("salary")?.alias("s")?;
+        let df = catalog.get_table_df("test")?.alias("t")?.join(
+            salary.plan(),
+            JoinType::Inner,
+            (vec!["t.id".to_string()], vec!["s.id".to_string()]),
+        )?;
+
+        assert!(matches!(
+            df.clone()
+                .project(vec![LogicalExpr::Column("name".to_string())]),
+            Err(Error::AmbiguousColumn(_))
+        ));
+
+        let df = df.project(vec![
+            LogicalExpr::Column("s.name".to_string()),
+            LogicalExpr::Column("age".to_string()),
+        ])?;
+        assert_eq!(df.schema().field(0).qualified_name(), "s.name");
+
+        let batches = QueryPlanner::create_physical_plan(df.plan())?.execute()?;
+        assert_eq!(batches[0].num_columns(), 2);
+
+        Ok(())
+    }
 }
@@ -1,8 +1,12 @@
-use crate::datatype::field::Field;
+use crate::datatype::field::{common_type, Field};
 use crate::datatype::scalar::Scalar;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::logical_plan::logical_plan::LogicalPlan;
-use arrow::datatypes::{self, DataType};
+use crate::logical_plan::type_coercion::coerce_types;
+use arrow::compute::can_cast_types;
+use arrow::datatypes::DataType;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
 
 /// A logical expr is an abstract representation of a query condition or filter condition.
 /// It usually consists of logical operators (such as AND, OR, NOT) and comparison operations.
@@ -23,6 +27,36 @@ pub enum LogicalExpr {
     /// Aggregate function expressions perform an aggregate function
     /// such as MIN, MAX, COUNT, SUM, or AVG on an input expr.
     AggregateFuncExpr(AggregateFuncExpr),
+    /// A subquery that returns a single value.
+    ScalarSubquery(Subquery),
+    /// Checks whether the value of an expr is returned by a subquery.
+    InSubquery(InSubquery),
+    /// Checks whether a subquery returns any rows.
+    Exists(Exists),
+    /// A column of the outer query referenced by a correlated subquery.
+    OuterColumn(Field),
+    /// Conditional expression, returns the result of the first matching WHEN branch.
+    Case(Case),
+    /// Converts an expr to another data type, fails if a value cannot be converted.
+    Cast(Cast),
+    /// Converts an expr to another data type, values that cannot be converted become NULL.
+    TryCast(TryCast),
+    /// A parameter of a prepared statement, `$1` is the first parameter.
+    /// Its value is bound when the statement is executed.
+    Placeholder(usize),
+    /// `*` as the argument of COUNT, a value that is never NULL so every row is counted.
+    Wildcard,
+    /// Window function expressions compute a value for each row from the rows of its window,
+    /// such as ROW_NUMBER() or SUM(salary) OVER (PARTITION BY dept ORDER BY id).
+    WindowFuncExpr(WindowFuncExpr),
+    /// Matches a string against a pattern, such as name LIKE 'B%' or name ~ '^[A-Z]'.
+    PatternMatch(PatternMatch),
+    /// An operator with a single operand, such as NOT active, -score or name IS NULL.
+    UnaryExpr(UnaryExpr),
+    /// Checks whether the value of an expr is in a list, such as id IN (1, 3, 5).
+    InList(InList),
+    /// Checks whether the value of an expr is in a range, such as age BETWEEN 18 AND 24.
+    Between(Between),
 }
 
 impl LogicalExpr {
@@ -43,10 +77,210 @@ impl LogicalExpr {
             LogicalExpr::AggregateFuncExpr(aggregate_func_expr) => {
                 aggregate_func_expr.to_field(input)
             }
+            LogicalExpr::ScalarSubquery(subquery) => {
+                let schema = subquery.plan.schema();
+                if schema.fields().len() != 1 {
+                    return Err(Error::LogicalPlanError(format!(
+                        "A scalar subquery must return exactly one column, found {}",
+                        schema.fields().len()
+                    )));
+                }
+                let field = schema.field(0);
+                Ok(Field::new(field.name(), field.data_type().clone(), true))
+            }
+            LogicalExpr::InSubquery(in_subquery) => {
+                let field = in_subquery.expr.to_field(input)?;
+                let not = if in_subquery.negated { "NOT " } else { "" };
+                Ok(Field::new(
+                    &format!("{} {}IN (<subquery>)", field.name(), not),
+                    DataType::Boolean,
+                    true,
+                ))
+            }
+            LogicalExpr::Exists(exists) => {
+                let not = if exists.negated { "NOT " } else { "" };
+                Ok(Field::new(
+                    &format!("{}EXISTS (<subquery>)", not),
+                    DataType::Boolean,
+                    false,
+                ))
+            }
+            LogicalExpr::OuterColumn(field) => Ok(field.clone()),
+            LogicalExpr::Case(case) => case.to_field(input),
+            LogicalExpr::Cast(cast) => cast_field("CAST", &cast.expr, &cast.data_type, input),
+            LogicalExpr::TryCast(try_cast) => {
+                cast_field("TRY_CAST", &try_cast.expr, &try_cast.data_type, input)
+            }
+            // The type is only known from the context the placeholder is used in,
+            // NULL can be coerced to any other type
+            LogicalExpr::Placeholder(id) => {
+                Ok(Field::new(&format!("${}", id), DataType::Null, true))
+            }
+            LogicalExpr::Wildcard => Ok(Field::new("*", DataType::Boolean, false)),
+            LogicalExpr::WindowFuncExpr(window_expr) => window_expr.to_field(input),
+            LogicalExpr::PatternMatch(pattern_match) => pattern_match.to_field(input),
+            LogicalExpr::UnaryExpr(unary_expr) => unary_expr.to_field(input),
+            LogicalExpr::InList(in_list) => in_list.to_field(input),
+            LogicalExpr::Between(between) => between.to_field(input),
         }
     }
 }
 
+/// Formats the expr as SQL-like text, e.g. `age >= 24 AND MAX(score) > 99`
+impl Display for LogicalExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LogicalExpr::BinaryExpr(binary_expr) => {
+                let precedence = binary_expr.op.precedence();
+                fmt_operand(f, &binary_expr.left, precedence)?;
+                write!(f, " {} ", binary_expr.op)?;
+                // Operators are left associative, so an operand of the same precedence
+                // on the right side needs parentheses
+                fmt_operand(f, &binary_expr.right, precedence + 1)
+            }
+            LogicalExpr::Literal(Scalar::Utf8(Some(value))) => write!(f, "'{}'", value),
+            LogicalExpr::Literal(scalar) => write!(f, "{}", scalar),
+            LogicalExpr::Alias(alias) => write!(f, "{} AS {}", alias.expr, alias.name),
+            LogicalExpr::Column(column) => write!(f, "{}", column),
+            LogicalExpr::ScalarFuncExpr(scalar_func_expr) => {
+                write!(f, "{:?}(", scalar_func_expr.func)?;
+                for (i, expr) in scalar_func_expr.exprs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", expr)?;
+                }
+                write!(f, ")")
+            }
+            LogicalExpr::AggregateFuncExpr(aggr_expr) => {
+                write!(
+                    f,
+                    "{}",
+                    aggr_expr.format_with_arg(&aggr_expr.expr.to_string())
+                )
+            }
+            LogicalExpr::ScalarSubquery(_) => write!(f, "(<subquery>)"),
+            LogicalExpr::InSubquery(in_subquery) => {
+                let not = if in_subquery.negated { "NOT " } else { "" };
+                write!(f, "{} {}IN (<subquery>)", in_subquery.expr, not)
+            }
+            LogicalExpr::Exists(exists) => {
+                let not = if exists.negated { "NOT " } else { "" };
+                write!(f, "{}EXISTS (<subquery>)", not)
+            }
+            LogicalExpr::OuterColumn(field) => write!(f, "outer_ref({})", field.qualified_name()),
+            LogicalExpr::Case(case) => {
+                write!(f, "CASE")?;
+                if let Some(expr) = &case.expr {
+                    write!(f, " {}", expr)?;
+                }
+                for (when, then) in &case.when_then_expr {
+                    write!(f, " WHEN {} THEN {}", when, then)?;
+                }
+                if let Some(else_expr) = &case.else_expr {
+                    write!(f, " ELSE {}", else_expr)?;
+                }
+                write!(f, " END")
+            }
+            LogicalExpr::Cast(cast) => write!(f, "CAST({} AS {:?})", cast.expr, cast.data_type),
+            LogicalExpr::TryCast(try_cast) => {
+                write!(f, "TRY_CAST({} AS {:?})", try_cast.expr, try_cast.data_type)
+            }
+            LogicalExpr::Placeholder(id) => write!(f, "${}", id),
+            LogicalExpr::Wildcard => write!(f, "*"),
+            LogicalExpr::WindowFuncExpr(window_expr) => write!(f, "{}", window_expr),
+            LogicalExpr::PatternMatch(pattern_match) => {
+                write!(
+                    f,
+                    "{} {} {}",
+                    pattern_match.expr,
+                    pattern_match.operator(),
+                    pattern_match.pattern
+                )?;
+                if let Some(escape_char) = pattern_match.escape_char {
+                    write!(f, " ESCAPE '{}'", escape_char)?;
+                }
+                Ok(())
+            }
+            LogicalExpr::UnaryExpr(unary_expr) => match unary_expr.op {
+                UnaryOperator::Not => {
+                    write!(f, "NOT ")?;
+                    fmt_operand(f, &unary_expr.expr, UnaryOperator::NOT_PRECEDENCE)
+                }
+                UnaryOperator::Negative => {
+                    write!(f, "-")?;
+                    fmt_operand(f, &unary_expr.expr, u8::MAX)
+                }
+                _ => {
+                    // Comparisons bind less tightly than IS
+                    fmt_operand(f, &unary_expr.expr, Operator::Eq.precedence() + 1)?;
+                    write!(f, " {}", unary_expr.op)
+                }
+            },
+            LogicalExpr::InList(in_list) => {
+                let not = if in_list.negated { "NOT " } else { "" };
+                write!(f, "{} {}IN (", in_list.expr, not)?;
+                for (i, expr) in in_list.list.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", expr)?;
+                }
+                write!(f, ")")
+            }
+            LogicalExpr::Between(between) => {
+                let not = if between.negated { "NOT " } else { "" };
+                write!(
+                    f,
+                    "{} {}BETWEEN {} AND {}",
+                    between.expr, not, between.low, between.high
+                )
+            }
+        }
+    }
+}
+
+/// Writes an operand of a binary expression, in parentheses if it binds less tightly
+fn fmt_operand(f: &mut Formatter<'_>, expr: &LogicalExpr, precedence: u8) -> fmt::Result {
+    match expr {
+        LogicalExpr::BinaryExpr(binary_expr) if binary_expr.op.precedence() < precedence => {
+            write!(f, "({})", expr)
+        }
+        _ => write!(f, "{}", expr),
+    }
+}
+
+fn cast_field(
+    func: &str,
+    expr: &LogicalExpr,
+    data_type: &DataType,
+    input: &LogicalPlan,
+) -> Result<Field> {
+    let field = expr.to_field(input)?;
+    if !can_cast_types(field.data_type(), data_type) {
+        return Err(Error::LogicalPlanError(format!(
+            "Cannot cast {} from {:?} to {:?}",
+            field.name(),
+            field.data_type(),
+            data_type
+        )));
+    }
+
+    Ok(Field::new(
+        &format!("{}({} AS {:?})", func, expr_name(expr, input)?, data_type),
+        data_type.clone(),
+        true,
+    ))
+}
+
+/// The name of an expression inside another one, literals are shown by their value
+fn expr_name(expr: &LogicalExpr, input: &LogicalPlan) -> Result<String> {
+    match expr {
+        LogicalExpr::Literal(scalar) => Ok(scalar.to_string()),
+        _ => Ok(expr.to_field(input)?.name().clone()),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BinaryExpr {
     pub left: Box<LogicalExpr>,
@@ -66,6 +300,13 @@ impl BinaryExpr {
             _ => self.right.to_field(input)?.name().clone(),
         };
 
+        // Arithmetic results have the type the operands are coerced to
+        let operand_type = coerce_types(
+            left.data_type(),
+            &self.op,
+            self.right.to_field(input)?.data_type(),
+        )?;
+
         let (operator, data_type) = match self.op {
             Operator::Eq => ("=", DataType::Boolean),
             Operator::Neq => ("!=", DataType::Boolean),
@@ -75,22 +316,30 @@ impl BinaryExpr {
             Operator::GtEq => (">=", DataType::Boolean),
             Operator::And => ("and", DataType::Boolean),
             Operator::Or => ("or", DataType::Boolean),
-            Operator::Add => ("+", left.data_type().clone()),
-            Operator::Sub => ("-", left.data_type().clone()),
-            Operator::Mul => ("*", left.data_type().clone()),
-            Operator::Div => ("/", left.data_type().clone()),
-            Operator::Mod => ("%", left.data_type().clone()),
+            Operator::Add => ("+", operand_type.clone()),
+            Operator::Sub => ("-", operand_type.clone()),
+            Operator::Mul => ("*", operand_type.clone()),
+            Operator::Div => ("/", operand_type.clone()),
+            Operator::Mod => ("%", operand_type.clone()),
+            Operator::IsDistinctFrom => ("IS DISTINCT FROM", DataType::Boolean),
+            Operator::IsNotDistinctFrom => ("IS NOT DISTINCT FROM", DataType::Boolean),
         };
 
+        // NULLs are compared as values by IS [NOT] DISTINCT FROM, so the result is never NULL
+        let nullable = !matches!(
+            self.op,
+            Operator::IsDistinctFrom | Operator::IsNotDistinctFrom
+        );
+
         Ok(Field::new(
             &format!("{} {} {}", left_name, right_name, operator),
             data_type,
-            true,
+            nullable,
         ))
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Operator {
     /// Equality (`=`) comparison
     Eq,
@@ -118,6 +367,53 @@ pub enum Operator {
     Div,
     /// Modulus operator (`%`)
     Mod,
+    /// `IS DISTINCT FROM`, inequality that treats NULLs as equal to each other
+    IsDistinctFrom,
+    /// `IS NOT DISTINCT FROM`, equality that treats NULLs as equal to each other
+    IsNotDistinctFrom,
+}
+
+impl Operator {
+    /// How tightly the operator binds, the same as in the SQL parser
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Operator::Or => 5,
+            Operator::And => 10,
+            Operator::Eq
+            | Operator::Neq
+            | Operator::Gt
+            | Operator::GtEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::IsDistinctFrom
+            | Operator::IsNotDistinctFrom => 20,
+            Operator::Add | Operator::Sub => 30,
+            Operator::Mul | Operator::Div | Operator::Mod => 40,
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let operator = match self {
+            Operator::Eq => "=",
+            Operator::Neq => "!=",
+            Operator::Gt => ">",
+            Operator::GtEq => ">=",
+            Operator::Lt => "<",
+            Operator::LtEq => "<=",
+            Operator::And => "AND",
+            Operator::Or => "OR",
+            Operator::Add => "+",
+            Operator::Sub => "-",
+            Operator::Mul => "*",
+            Operator::Div => "/",
+            Operator::Mod => "%",
+            Operator::IsDistinctFrom => "IS DISTINCT FROM",
+            Operator::IsNotDistinctFrom => "IS NOT DISTINCT FROM",
+        };
+        write!(f, "{}", operator)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -130,9 +426,11 @@ pub struct Alias {
 /// Represents a series of operations on scalar values
 pub struct ScalarFuncExpr {
     pub func: ScalarFunc,
-    pub exprs: Vec<Box<LogicalExpr>>,
+    pub exprs: Vec<LogicalExpr>,
 }
 
+// The variants are spelled like the SQL functions, their Debug output is the function name
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Debug)]
 pub enum ScalarFunc {
     CONCAT,
@@ -160,33 +458,963 @@ impl ScalarFuncExpr {
 pub struct AggregateFuncExpr {
     pub func: AggregateFunc,
     pub expr: Box<LogicalExpr>,
+    /// `func(DISTINCT expr)`, each distinct value of a group is aggregated once
+    pub distinct: bool,
+    /// `func(expr) FILTER (WHERE filter)`, only the rows the filter is true for are aggregated
+    pub filter: Option<Box<LogicalExpr>>,
 }
 
 #[derive(Clone, Debug)]
 /// Represents a series of aggregation operations.
+#[allow(clippy::upper_case_acronyms)]
 pub enum AggregateFunc {
     SUM,
     MIN,
     MAX,
     AVG,
     COUNT,
+    /// 1 if the argument, a group expr, is rolled up in the grouping set of the row, else 0
+    GROUPING,
 }
 
 impl AggregateFuncExpr {
     pub fn to_field(&self, input: &LogicalPlan) -> Result<Field> {
         let field = self.expr.to_field(input)?;
 
-        let (name, data_type) = match self.func {
-            AggregateFunc::SUM => (format!("SUM({})", field.name()), field.data_type()),
-            AggregateFunc::MIN => (format!("Min({})", field.name()), field.data_type()),
-            AggregateFunc::MAX => (format!("Max({})", field.name()), field.data_type()),
-            AggregateFunc::AVG => (format!("AVG({})", field.name()), field.data_type()),
-            AggregateFunc::COUNT => (
-                format!("COUNT({})", field.name()),
-                &datatypes::DataType::Int64,
-            ),
+        let data_type = match self.func {
+            AggregateFunc::SUM | AggregateFunc::MIN | AggregateFunc::MAX => field.data_type(),
+            AggregateFunc::AVG => &DataType::Float64,
+            AggregateFunc::COUNT | AggregateFunc::GROUPING => &DataType::UInt64,
+        };
+
+        Ok(Field::new(
+            self.format_with_arg(field.name()).as_str(),
+            data_type.clone(),
+            true,
+        ))
+    }
+
+    /// Formats the aggregate with `arg` in place of its argument,
+    /// e.g. `COUNT(DISTINCT name) FILTER (WHERE age > 18)`
+    fn format_with_arg(&self, arg: &str) -> String {
+        let distinct = if self.distinct { "DISTINCT " } else { "" };
+        let mut name = format!("{:?}({}{})", self.func, distinct, arg);
+        if let Some(filter) = &self.filter {
+            name.push_str(&format!(" FILTER (WHERE {})", filter));
+        }
+        name
+    }
+}
+
+#[derive(Clone, Debug)]
+/// `func(args) OVER (PARTITION BY partition_by ORDER BY order_by window_frame)`
+pub struct WindowFuncExpr {
+    pub func: WindowFunc,
+    /// LAG and LEAD always have three arguments: the value, the offset and the default
+    pub args: Vec<LogicalExpr>,
+    pub partition_by: Vec<LogicalExpr>,
+    pub order_by: Vec<SortExpr>,
+    /// The rows of the partition an aggregate or FIRST_VALUE is computed from,
+    /// `None` for the default frame
+    pub window_frame: Option<WindowFrame>,
+}
+
+#[derive(Clone, Debug)]
+pub enum WindowFunc {
+    /// An aggregate function computed from the rows of the window frame
+    Aggregate(AggregateFunc),
+    /// The number of the row in its partition, starting from 1
+    RowNumber,
+    /// The row number of the first peer of the row, so there are gaps after peers
+    Rank,
+    /// The number of the peer group of the row, without gaps
+    DenseRank,
+    /// The value of the row that is `offset` rows before the row in its partition
+    Lag,
+    /// The value of the row that is `offset` rows after the row in its partition
+    Lead,
+    /// The value of the first row of the window frame
+    FirstValue,
+    /// The value of the last row of the window frame
+    LastValue,
+}
+
+impl Display for WindowFunc {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowFunc::Aggregate(func) => write!(f, "{:?}", func),
+            WindowFunc::RowNumber => write!(f, "ROW_NUMBER"),
+            WindowFunc::Rank => write!(f, "RANK"),
+            WindowFunc::DenseRank => write!(f, "DENSE_RANK"),
+            WindowFunc::Lag => write!(f, "LAG"),
+            WindowFunc::Lead => write!(f, "LEAD"),
+            WindowFunc::FirstValue => write!(f, "FIRST_VALUE"),
+            WindowFunc::LastValue => write!(f, "LAST_VALUE"),
+        }
+    }
+}
+
+impl WindowFunc {
+    /// Whether the result depends on the window frame rather than on the whole partition
+    pub fn uses_frame(&self) -> bool {
+        matches!(
+            self,
+            WindowFunc::Aggregate(_) | WindowFunc::FirstValue | WindowFunc::LastValue
+        )
+    }
+}
+
+impl WindowFuncExpr {
+    pub fn to_field(&self, input: &LogicalPlan) -> Result<Field> {
+        let (data_type, nullable) = match &self.func {
+            WindowFunc::Aggregate(func) => {
+                let field = AggregateFuncExpr {
+                    func: func.clone(),
+                    expr: Box::new(self.args[0].clone()),
+                    distinct: false,
+                    filter: None,
+                }
+                .to_field(input)?;
+                // COUNT of an empty frame is 0, other aggregates are NULL
+                let nullable = !matches!(func, AggregateFunc::COUNT);
+                (field.data_type().clone(), nullable)
+            }
+            WindowFunc::RowNumber | WindowFunc::Rank | WindowFunc::DenseRank => {
+                (DataType::UInt64, false)
+            }
+            WindowFunc::Lag | WindowFunc::Lead => {
+                let value_type = self.args[0].to_field(input)?.data_type().clone();
+                let default_type = self.args[2].to_field(input)?.data_type().clone();
+                let data_type = common_type(&value_type, &default_type).ok_or_else(|| {
+                    Error::LogicalPlanError(format!(
+                        "The default of {} is {:?}, but the value is {:?}",
+                        self.func, default_type, value_type
+                    ))
+                })?;
+                (data_type, true)
+            }
+            WindowFunc::FirstValue | WindowFunc::LastValue => {
+                (self.args[0].to_field(input)?.data_type().clone(), true)
+            }
+        };
+
+        Ok(Field::new(&self.to_string(), data_type, nullable))
+    }
+}
+
+impl Display for WindowFuncExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.func)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", arg)?;
+        }
+        write!(f, ") OVER (")?;
+
+        let mut clauses = vec![];
+        if !self.partition_by.is_empty() {
+            let exprs = self
+                .partition_by
+                .iter()
+                .map(|expr| expr.to_string())
+                .collect::<Vec<_>>();
+            clauses.push(format!("PARTITION BY {}", exprs.join(", ")));
+        }
+        if !self.order_by.is_empty() {
+            let exprs = self
+                .order_by
+                .iter()
+                .map(|sort_expr| {
+                    let order = if sort_expr.asc { "" } else { " DESC" };
+                    format!("{}{}", sort_expr.expr, order)
+                })
+                .collect::<Vec<_>>();
+            clauses.push(format!("ORDER BY {}", exprs.join(", ")));
+        }
+        if let Some(window_frame) = &self.window_frame {
+            clauses.push(window_frame.to_string());
+        }
+        write!(f, "{})", clauses.join(" "))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// `{ ROWS | RANGE } BETWEEN start_bound AND end_bound`
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start_bound: WindowFrameBound,
+    pub end_bound: WindowFrameBound,
+}
+
+impl WindowFrame {
+    /// The frame of a window without a frame clause. With ORDER BY it ends with the last
+    /// peer of the row, so aggregates are running totals, otherwise it is the whole partition.
+    pub fn default_frame(ordered: bool) -> Self {
+        if ordered {
+            WindowFrame {
+                units: WindowFrameUnits::Range,
+                start_bound: WindowFrameBound::UnboundedPreceding,
+                end_bound: WindowFrameBound::CurrentRow,
+            }
+        } else {
+            WindowFrame {
+                units: WindowFrameUnits::Rows,
+                start_bound: WindowFrameBound::UnboundedPreceding,
+                end_bound: WindowFrameBound::UnboundedFollowing,
+            }
+        }
+    }
+}
+
+impl Display for WindowFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} BETWEEN {} AND {}",
+            self.units, self.start_bound, self.end_bound
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowFrameUnits {
+    /// The bounds are counted in rows
+    Rows,
+    /// The bounds are peer groups, rows with equal ORDER BY values
+    Range,
+}
+
+impl Display for WindowFrameUnits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowFrameUnits::Rows => write!(f, "ROWS"),
+            WindowFrameUnits::Range => write!(f, "RANGE"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowFrameBound {
+    UnboundedPreceding,
+    /// `N PRECEDING`
+    Preceding(usize),
+    CurrentRow,
+    /// `N FOLLOWING`
+    Following(usize),
+    UnboundedFollowing,
+}
+
+impl Display for WindowFrameBound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowFrameBound::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+            WindowFrameBound::Preceding(n) => write!(f, "{} PRECEDING", n),
+            WindowFrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            WindowFrameBound::Following(n) => write!(f, "{} FOLLOWING", n),
+            WindowFrameBound::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// The logical plan of a subquery, it may reference columns of the outer query
+pub struct Subquery {
+    pub plan: Arc<LogicalPlan>,
+}
+
+#[derive(Clone, Debug)]
+/// `expr [NOT] IN (subquery)`
+pub struct InSubquery {
+    pub expr: Box<LogicalExpr>,
+    pub subquery: Subquery,
+    pub negated: bool,
+}
+
+#[derive(Clone, Debug)]
+/// `[NOT] EXISTS (subquery)`
+pub struct Exists {
+    pub subquery: Subquery,
+    pub negated: bool,
+}
+
+#[derive(Clone, Debug)]
+/// `CASE [expr] WHEN when THEN then ... [ELSE else_expr] END`
+pub struct Case {
+    /// The operand of the simple form, which is compared with each WHEN value.
+    /// `None` for the searched form, where each WHEN is a boolean condition.
+    pub expr: Option<Box<LogicalExpr>>,
+    pub when_then_expr: Vec<(Box<LogicalExpr>, Box<LogicalExpr>)>,
+    /// The result when no branch matches, NULL if it is not given
+    pub else_expr: Option<Box<LogicalExpr>>,
+}
+
+impl Case {
+    /// The result type is the common type of all THEN and ELSE expressions
+    pub fn to_field(&self, input: &LogicalPlan) -> Result<Field> {
+        let mut name = "CASE".to_string();
+        let operand = match &self.expr {
+            Some(expr) => {
+                name.push_str(&format!(" {}", expr_name(expr, input)?));
+                Some(expr.to_field(input)?)
+            }
+            None => None,
+        };
+
+        let mut data_type = DataType::Null;
+        for (when, then) in &self.when_then_expr {
+            let when_field = when.to_field(input)?;
+            match &operand {
+                Some(operand) => {
+                    if common_type(operand.data_type(), when_field.data_type()).is_none() {
+                        return Err(Error::LogicalPlanError(format!(
+                            "Cannot compare CASE operand of type {:?} with WHEN value of type {:?}",
+                            operand.data_type(),
+                            when_field.data_type()
+                        )));
+                    }
+                }
+                None => {
+                    if !matches!(when_field.data_type(), DataType::Boolean | DataType::Null) {
+                        return Err(Error::LogicalPlanError(format!(
+                            "WHEN condition must be Boolean, found {:?}",
+                            when_field.data_type()
+                        )));
+                    }
+                }
+            }
+
+            data_type = result_type(&data_type, then, input)?;
+            name.push_str(&format!(
+                " WHEN {} THEN {}",
+                expr_name(when, input)?,
+                expr_name(then, input)?
+            ));
+        }
+
+        if let Some(else_expr) = &self.else_expr {
+            data_type = result_type(&data_type, else_expr, input)?;
+            name.push_str(&format!(" ELSE {}", expr_name(else_expr, input)?));
+        }
+        name.push_str(" END");
+
+        Ok(Field::new(&name, data_type, true))
+    }
+}
+
+fn result_type(
+    data_type: &DataType,
+    result: &LogicalExpr,
+    input: &LogicalPlan,
+) -> Result<DataType> {
+    let result_type = result.to_field(input)?.data_type().clone();
+    common_type(data_type, &result_type).ok_or_else(|| {
+        Error::LogicalPlanError(format!(
+            "CASE results have incompatible types {:?} and {:?}",
+            data_type, result_type
+        ))
+    })
+}
+
+#[derive(Clone, Debug)]
+/// `CAST(expr AS data_type)`
+pub struct Cast {
+    pub expr: Box<LogicalExpr>,
+    pub data_type: DataType,
+}
+
+#[derive(Clone, Debug)]
+/// `expr [NOT] { LIKE | ILIKE | SIMILAR TO } pattern [ESCAPE 'c']` or `expr { ~ | !~ } pattern`
+pub struct PatternMatch {
+    pub kind: PatternKind,
+    pub negated: bool,
+    pub expr: Box<LogicalExpr>,
+    pub pattern: Box<LogicalExpr>,
+    /// The character that makes the next character of the pattern match literally,
+    /// e.g. `'100\%' ESCAPE '\'`. There is none unless the ESCAPE clause is given.
+    pub escape_char: Option<char>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The kind of pattern a string is matched against
+pub enum PatternKind {
+    /// `%` matches any characters and `_` a single character
+    Like,
+    /// LIKE ignoring case
+    ILike,
+    /// A SQL regular expression, LIKE with the alternatives and repetitions of regexes,
+    /// it has to match the whole string
+    SimilarTo,
+    /// A POSIX-style regular expression, it may match any part of the string
+    Regex,
+}
+
+impl PatternKind {
+    /// The operator as written in SQL, e.g. `NOT LIKE` or `!~`
+    pub fn operator(&self, negated: bool) -> &'static str {
+        match (self, negated) {
+            (PatternKind::Like, false) => "LIKE",
+            (PatternKind::Like, true) => "NOT LIKE",
+            (PatternKind::ILike, false) => "ILIKE",
+            (PatternKind::ILike, true) => "NOT ILIKE",
+            (PatternKind::SimilarTo, false) => "SIMILAR TO",
+            (PatternKind::SimilarTo, true) => "NOT SIMILAR TO",
+            (PatternKind::Regex, false) => "~",
+            (PatternKind::Regex, true) => "!~",
+        }
+    }
+}
+
+impl PatternMatch {
+    pub fn operator(&self) -> &'static str {
+        self.kind.operator(self.negated)
+    }
+
+    fn to_field(&self, input: &LogicalPlan) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        let pattern_name = match self.pattern.as_ref() {
+            LogicalExpr::Literal(scalar) => scalar.to_string(),
+            pattern => pattern.to_field(input)?.name().clone(),
         };
+        let pattern_type = self.pattern.to_field(input)?.data_type().clone();
+
+        for data_type in [field.data_type(), &pattern_type] {
+            if !matches!(data_type, DataType::Utf8 | DataType::Null) {
+                return Err(Error::LogicalPlanError(format!(
+                    "{} can only match Utf8 values with a Utf8 pattern, found {:?}",
+                    self.operator(),
+                    data_type
+                )));
+            }
+        }
+
+        Ok(Field::new(
+            &format!("{} {} {}", field.name(), self.operator(), pattern_name),
+            DataType::Boolean,
+            true,
+        ))
+    }
+}
+
+#[derive(Clone, Debug)]
+/// An operator applied to a single expr, e.g. `NOT expr` or `expr IS NULL`
+pub struct UnaryExpr {
+    pub op: UnaryOperator,
+    pub expr: Box<LogicalExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnaryOperator {
+    /// Logical negation (`NOT`), NULL stays NULL
+    Not,
+    /// Arithmetic negation (`-`)
+    Negative,
+    /// `IS NULL`
+    IsNull,
+    /// `IS NOT NULL`
+    IsNotNull,
+    /// `IS TRUE`, false for NULL
+    IsTrue,
+    /// `IS FALSE`, false for NULL
+    IsFalse,
+    /// `IS NOT TRUE`, true for NULL
+    IsNotTrue,
+    /// `IS NOT FALSE`, true for NULL
+    IsNotFalse,
+}
+
+impl UnaryOperator {
+    /// How tightly NOT binds, between AND and the comparisons as in the SQL parser
+    pub const NOT_PRECEDENCE: u8 = 15;
+}
+
+impl Display for UnaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let operator = match self {
+            UnaryOperator::Not => "NOT",
+            UnaryOperator::Negative => "-",
+            UnaryOperator::IsNull => "IS NULL",
+            UnaryOperator::IsNotNull => "IS NOT NULL",
+            UnaryOperator::IsTrue => "IS TRUE",
+            UnaryOperator::IsFalse => "IS FALSE",
+            UnaryOperator::IsNotTrue => "IS NOT TRUE",
+            UnaryOperator::IsNotFalse => "IS NOT FALSE",
+        };
+        write!(f, "{}", operator)
+    }
+}
+
+impl UnaryExpr {
+    fn to_field(&self, input: &LogicalPlan) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        let name = expr_name(&self.expr, input)?;
+        let data_type = field.data_type();
+
+        let check_type = |valid: bool, expected: &str| {
+            if valid {
+                Ok(())
+            } else {
+                Err(Error::LogicalPlanError(format!(
+                    "{} expects {} operand, found {} of type {:?}",
+                    self.op, expected, name, data_type
+                )))
+            }
+        };
+
+        match self.op {
+            // NOT and - keep NULLs, so the result is as nullable as the operand
+            UnaryOperator::Not => {
+                check_type(
+                    matches!(data_type, DataType::Boolean | DataType::Null),
+                    "a Boolean",
+                )?;
+                Ok(Field::new(
+                    &format!("NOT {}", name),
+                    DataType::Boolean,
+                    field.is_nullable(),
+                ))
+            }
+            UnaryOperator::Negative => {
+                check_type(
+                    matches!(
+                        data_type,
+                        DataType::Int64 | DataType::UInt64 | DataType::Float64 | DataType::Null
+                    ),
+                    "a numeric",
+                )?;
+                // Unsigned values are negated as signed ones
+                let data_type = match data_type {
+                    DataType::UInt64 | DataType::Null => DataType::Int64,
+                    data_type => data_type.clone(),
+                };
+                Ok(Field::new(
+                    &format!("-{}", name),
+                    data_type,
+                    field.is_nullable(),
+                ))
+            }
+            UnaryOperator::IsNull | UnaryOperator::IsNotNull => Ok(Field::new(
+                &format!("{} {}", name, self.op),
+                DataType::Boolean,
+                false,
+            )),
+            UnaryOperator::IsTrue
+            | UnaryOperator::IsFalse
+            | UnaryOperator::IsNotTrue
+            | UnaryOperator::IsNotFalse => {
+                check_type(
+                    matches!(data_type, DataType::Boolean | DataType::Null),
+                    "a Boolean",
+                )?;
+                Ok(Field::new(
+                    &format!("{} {}", name, self.op),
+                    DataType::Boolean,
+                    false,
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// `expr [NOT] IN (expr, ...)`
+pub struct InList {
+    pub expr: Box<LogicalExpr>,
+    pub list: Vec<LogicalExpr>,
+    pub negated: bool,
+}
+
+impl InList {
+    fn to_field(&self, input: &LogicalPlan) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        let mut data_type = field.data_type().clone();
+        let mut names = vec![];
+        for expr in &self.list {
+            let item = expr.to_field(input)?;
+            // Fails if the values cannot be compared
+            data_type = coerce_types(&data_type, &Operator::Eq, item.data_type())?;
+            names.push(expr_name(expr, input)?);
+        }
+
+        let not = if self.negated { "NOT " } else { "" };
+        Ok(Field::new(
+            &format!("{} {}IN ({})", field.name(), not, names.join(", ")),
+            DataType::Boolean,
+            true,
+        ))
+    }
+}
+
+#[derive(Clone, Debug)]
+/// `expr [NOT] BETWEEN low AND high`, the range includes both bounds
+pub struct Between {
+    pub expr: Box<LogicalExpr>,
+    pub negated: bool,
+    pub low: Box<LogicalExpr>,
+    pub high: Box<LogicalExpr>,
+}
+
+impl Between {
+    fn to_field(&self, input: &LogicalPlan) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        for bound in [&self.low, &self.high] {
+            // Fails if the values cannot be compared
+            coerce_types(
+                field.data_type(),
+                &Operator::GtEq,
+                bound.to_field(input)?.data_type(),
+            )?;
+        }
+
+        let not = if self.negated { "NOT " } else { "" };
+        Ok(Field::new(
+            &format!(
+                "{} {}BETWEEN {} AND {}",
+                field.name(),
+                not,
+                expr_name(&self.low, input)?,
+                expr_name(&self.high, input)?
+            ),
+            DataType::Boolean,
+            true,
+        ))
+    }
+}
 
-        Ok(Field::new(name.as_str(), data_type.clone(), true))
+#[derive(Clone, Debug)]
+/// `TRY_CAST(expr AS data_type)`
+pub struct TryCast {
+    pub expr: Box<LogicalExpr>,
+    pub data_type: DataType,
+}
+
+#[derive(Clone, Debug)]
+/// Represents an expression to sort by together with the sort direction
+pub struct SortExpr {
+    pub expr: LogicalExpr,
+    /// Sorts in ascending order if true, otherwise in descending order
+    pub asc: bool,
+    /// Whether NULLs are placed before all other values
+    pub nulls_first: bool,
+}
+
+/// Collects the distinct aggregate expressions used in `expr`
+pub fn find_aggregate_exprs(
+    expr: &LogicalExpr,
+    input: &LogicalPlan,
+    aggr_exprs: &mut Vec<AggregateFuncExpr>,
+) -> Result<()> {
+    match expr {
+        LogicalExpr::AggregateFuncExpr(aggr_expr) => {
+            let name = aggr_expr.to_field(input)?.name().clone();
+            for existing in aggr_exprs.iter() {
+                if existing.to_field(input)?.name() == &name {
+                    return Ok(());
+                }
+            }
+            aggr_exprs.push(aggr_expr.clone());
+            Ok(())
+        }
+        LogicalExpr::BinaryExpr(binary_expr) => {
+            find_aggregate_exprs(&binary_expr.left, input, aggr_exprs)?;
+            find_aggregate_exprs(&binary_expr.right, input, aggr_exprs)
+        }
+        LogicalExpr::Alias(alias) => find_aggregate_exprs(&alias.expr, input, aggr_exprs),
+        LogicalExpr::InSubquery(in_subquery) => {
+            find_aggregate_exprs(&in_subquery.expr, input, aggr_exprs)
+        }
+        LogicalExpr::Case(case) => {
+            if let Some(expr) = &case.expr {
+                find_aggregate_exprs(expr, input, aggr_exprs)?;
+            }
+            for (when, then) in &case.when_then_expr {
+                find_aggregate_exprs(when, input, aggr_exprs)?;
+                find_aggregate_exprs(then, input, aggr_exprs)?;
+            }
+            if let Some(else_expr) = &case.else_expr {
+                find_aggregate_exprs(else_expr, input, aggr_exprs)?;
+            }
+            Ok(())
+        }
+        LogicalExpr::Cast(cast) => find_aggregate_exprs(&cast.expr, input, aggr_exprs),
+        LogicalExpr::TryCast(try_cast) => find_aggregate_exprs(&try_cast.expr, input, aggr_exprs),
+        LogicalExpr::PatternMatch(pattern_match) => {
+            find_aggregate_exprs(&pattern_match.expr, input, aggr_exprs)?;
+            find_aggregate_exprs(&pattern_match.pattern, input, aggr_exprs)
+        }
+        LogicalExpr::UnaryExpr(unary_expr) => {
+            find_aggregate_exprs(&unary_expr.expr, input, aggr_exprs)
+        }
+        LogicalExpr::InList(in_list) => {
+            find_aggregate_exprs(&in_list.expr, input, aggr_exprs)?;
+            for expr in &in_list.list {
+                find_aggregate_exprs(expr, input, aggr_exprs)?;
+            }
+            Ok(())
+        }
+        LogicalExpr::Between(between) => {
+            find_aggregate_exprs(&between.expr, input, aggr_exprs)?;
+            find_aggregate_exprs(&between.low, input, aggr_exprs)?;
+            find_aggregate_exprs(&between.high, input, aggr_exprs)
+        }
+        // A window function may be computed from the results of an aggregation
+        LogicalExpr::WindowFuncExpr(window_expr) => {
+            let sort_exprs = window_expr.order_by.iter().map(|sort_expr| &sort_expr.expr);
+            for expr in window_expr
+                .args
+                .iter()
+                .chain(&window_expr.partition_by)
+                .chain(sort_exprs)
+            {
+                find_aggregate_exprs(expr, input, aggr_exprs)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Collects the distinct window function expressions used in `expr`
+pub fn find_window_exprs(expr: &LogicalExpr, window_exprs: &mut Vec<WindowFuncExpr>) {
+    match expr {
+        LogicalExpr::WindowFuncExpr(window_expr) => {
+            let name = window_expr.to_string();
+            if !window_exprs
+                .iter()
+                .any(|existing| existing.to_string() == name)
+            {
+                window_exprs.push(window_expr.clone());
+            }
+        }
+        LogicalExpr::BinaryExpr(binary_expr) => {
+            find_window_exprs(&binary_expr.left, window_exprs);
+            find_window_exprs(&binary_expr.right, window_exprs);
+        }
+        LogicalExpr::Alias(alias) => find_window_exprs(&alias.expr, window_exprs),
+        LogicalExpr::AggregateFuncExpr(aggr_expr) => {
+            find_window_exprs(&aggr_expr.expr, window_exprs);
+            if let Some(filter) = &aggr_expr.filter {
+                find_window_exprs(filter, window_exprs);
+            }
+        }
+        LogicalExpr::InSubquery(in_subquery) => find_window_exprs(&in_subquery.expr, window_exprs),
+        LogicalExpr::Case(case) => {
+            if let Some(expr) = &case.expr {
+                find_window_exprs(expr, window_exprs);
+            }
+            for (when, then) in &case.when_then_expr {
+                find_window_exprs(when, window_exprs);
+                find_window_exprs(then, window_exprs);
+            }
+            if let Some(else_expr) = &case.else_expr {
+                find_window_exprs(else_expr, window_exprs);
+            }
+        }
+        LogicalExpr::Cast(cast) => find_window_exprs(&cast.expr, window_exprs),
+        LogicalExpr::TryCast(try_cast) => find_window_exprs(&try_cast.expr, window_exprs),
+        LogicalExpr::PatternMatch(pattern_match) => {
+            find_window_exprs(&pattern_match.expr, window_exprs);
+            find_window_exprs(&pattern_match.pattern, window_exprs);
+        }
+        LogicalExpr::UnaryExpr(unary_expr) => find_window_exprs(&unary_expr.expr, window_exprs),
+        LogicalExpr::InList(in_list) => {
+            find_window_exprs(&in_list.expr, window_exprs);
+            for expr in &in_list.list {
+                find_window_exprs(expr, window_exprs);
+            }
+        }
+        LogicalExpr::Between(between) => {
+            find_window_exprs(&between.expr, window_exprs);
+            find_window_exprs(&between.low, window_exprs);
+            find_window_exprs(&between.high, window_exprs);
+        }
+        _ => {}
+    }
+}
+
+/// Replaces the window function expressions of `expr` with references to the columns
+/// holding their results, which a window plan appends to its input
+pub fn rebase_window_expr(expr: &LogicalExpr) -> LogicalExpr {
+    let rebase = |expr: &LogicalExpr| Box::new(rebase_window_expr(expr));
+    match expr {
+        LogicalExpr::WindowFuncExpr(window_expr) => LogicalExpr::Column(window_expr.to_string()),
+        LogicalExpr::BinaryExpr(binary_expr) => LogicalExpr::BinaryExpr(BinaryExpr {
+            left: rebase(&binary_expr.left),
+            op: binary_expr.op.clone(),
+            right: rebase(&binary_expr.right),
+        }),
+        LogicalExpr::Alias(alias) => LogicalExpr::Alias(Alias {
+            name: alias.name.clone(),
+            expr: rebase(&alias.expr),
+        }),
+        LogicalExpr::InSubquery(in_subquery) => LogicalExpr::InSubquery(InSubquery {
+            expr: rebase(&in_subquery.expr),
+            subquery: in_subquery.subquery.clone(),
+            negated: in_subquery.negated,
+        }),
+        LogicalExpr::Case(case) => LogicalExpr::Case(Case {
+            expr: case.expr.as_deref().map(rebase),
+            when_then_expr: case
+                .when_then_expr
+                .iter()
+                .map(|(when, then)| (rebase(when), rebase(then)))
+                .collect(),
+            else_expr: case.else_expr.as_deref().map(rebase),
+        }),
+        LogicalExpr::Cast(cast) => LogicalExpr::Cast(Cast {
+            expr: rebase(&cast.expr),
+            data_type: cast.data_type.clone(),
+        }),
+        LogicalExpr::TryCast(try_cast) => LogicalExpr::TryCast(TryCast {
+            expr: rebase(&try_cast.expr),
+            data_type: try_cast.data_type.clone(),
+        }),
+        LogicalExpr::PatternMatch(pattern_match) => LogicalExpr::PatternMatch(PatternMatch {
+            expr: rebase(&pattern_match.expr),
+            pattern: rebase(&pattern_match.pattern),
+            ..pattern_match.clone()
+        }),
+        LogicalExpr::UnaryExpr(unary_expr) => LogicalExpr::UnaryExpr(UnaryExpr {
+            op: unary_expr.op.clone(),
+            expr: rebase(&unary_expr.expr),
+        }),
+        LogicalExpr::InList(in_list) => LogicalExpr::InList(InList {
+            expr: rebase(&in_list.expr),
+            list: in_list.list.iter().map(rebase_window_expr).collect(),
+            negated: in_list.negated,
+        }),
+        LogicalExpr::Between(between) => LogicalExpr::Between(Between {
+            expr: rebase(&between.expr),
+            negated: between.negated,
+            low: rebase(&between.low),
+            high: rebase(&between.high),
+        }),
+        _ => expr.clone(),
+    }
+}
+
+/// Replaces the sub-expressions of `expr` that are computed by `base_exprs`
+/// with references to the columns holding their results
+pub fn rebase_expr(
+    expr: &LogicalExpr,
+    base_exprs: &[LogicalExpr],
+    input: &LogicalPlan,
+) -> Result<LogicalExpr> {
+    match expr {
+        LogicalExpr::Alias(alias) => {
+            return Ok(LogicalExpr::Alias(Alias {
+                name: alias.name.clone(),
+                expr: Box::new(rebase_expr(&alias.expr, base_exprs, input)?),
+            }))
+        }
+        LogicalExpr::InSubquery(in_subquery) => {
+            return Ok(LogicalExpr::InSubquery(InSubquery {
+                expr: Box::new(rebase_expr(&in_subquery.expr, base_exprs, input)?),
+                subquery: in_subquery.subquery.clone(),
+                negated: in_subquery.negated,
+            }))
+        }
+        LogicalExpr::Literal(_)
+        | LogicalExpr::Placeholder(_)
+        | LogicalExpr::Wildcard
+        | LogicalExpr::ScalarSubquery(_)
+        | LogicalExpr::Exists(_)
+        | LogicalExpr::OuterColumn(_) => return Ok(expr.clone()),
+        _ => {}
+    }
+
+    let name = expr.to_field(input)?.qualified_name();
+    for base_expr in base_exprs {
+        if base_expr.to_field(input)?.qualified_name() == name {
+            return Ok(LogicalExpr::Column(name));
+        }
+    }
+
+    match expr {
+        LogicalExpr::BinaryExpr(binary_expr) => Ok(LogicalExpr::BinaryExpr(BinaryExpr {
+            left: Box::new(rebase_expr(&binary_expr.left, base_exprs, input)?),
+            op: binary_expr.op.clone(),
+            right: Box::new(rebase_expr(&binary_expr.right, base_exprs, input)?),
+        })),
+        LogicalExpr::Case(case) => {
+            let rebase = |expr: &LogicalExpr| rebase_expr(expr, base_exprs, input).map(Box::new);
+            Ok(LogicalExpr::Case(Case {
+                expr: case.expr.as_deref().map(rebase).transpose()?,
+                when_then_expr: case
+                    .when_then_expr
+                    .iter()
+                    .map(|(when, then)| Ok((rebase(when)?, rebase(then)?)))
+                    .collect::<Result<Vec<_>>>()?,
+                else_expr: case.else_expr.as_deref().map(rebase).transpose()?,
+            }))
+        }
+        LogicalExpr::Cast(cast) => Ok(LogicalExpr::Cast(Cast {
+            expr: Box::new(rebase_expr(&cast.expr, base_exprs, input)?),
+            data_type: cast.data_type.clone(),
+        })),
+        LogicalExpr::TryCast(try_cast) => Ok(LogicalExpr::TryCast(TryCast {
+            expr: Box::new(rebase_expr(&try_cast.expr, base_exprs, input)?),
+            data_type: try_cast.data_type.clone(),
+        })),
+        LogicalExpr::PatternMatch(pattern_match) => Ok(LogicalExpr::PatternMatch(PatternMatch {
+            expr: Box::new(rebase_expr(&pattern_match.expr, base_exprs, input)?),
+            pattern: Box::new(rebase_expr(&pattern_match.pattern, base_exprs, input)?),
+            ..pattern_match.clone()
+        })),
+        LogicalExpr::UnaryExpr(unary_expr) => Ok(LogicalExpr::UnaryExpr(UnaryExpr {
+            op: unary_expr.op.clone(),
+            expr: Box::new(rebase_expr(&unary_expr.expr, base_exprs, input)?),
+        })),
+        LogicalExpr::InList(in_list) => Ok(LogicalExpr::InList(InList {
+            expr: Box::new(rebase_expr(&in_list.expr, base_exprs, input)?),
+            list: in_list
+                .list
+                .iter()
+                .map(|expr| rebase_expr(expr, base_exprs, input))
+                .collect::<Result<Vec<_>>>()?,
+            negated: in_list.negated,
+        })),
+        LogicalExpr::Between(between) => {
+            let rebase = |expr: &LogicalExpr| rebase_expr(expr, base_exprs, input).map(Box::new);
+            Ok(LogicalExpr::Between(Between {
+                expr: rebase(&between.expr)?,
+                negated: between.negated,
+                low: rebase(&between.low)?,
+                high: rebase(&between.high)?,
+            }))
+        }
+        LogicalExpr::WindowFuncExpr(window_expr) => {
+            let rebase = |expr: &LogicalExpr| rebase_expr(expr, base_exprs, input);
+            Ok(LogicalExpr::WindowFuncExpr(WindowFuncExpr {
+                func: window_expr.func.clone(),
+                args: window_expr
+                    .args
+                    .iter()
+                    .map(rebase)
+                    .collect::<Result<Vec<_>>>()?,
+                partition_by: window_expr
+                    .partition_by
+                    .iter()
+                    .map(rebase)
+                    .collect::<Result<Vec<_>>>()?,
+                order_by: window_expr
+                    .order_by
+                    .iter()
+                    .map(|sort_expr| {
+                        Ok(SortExpr {
+                            expr: rebase(&sort_expr.expr)?,
+                            ..sort_expr.clone()
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                window_frame: window_expr.window_frame.clone(),
+            }))
+        }
+        LogicalExpr::Column(column) => Err(Error::LogicalPlanError(format!(
+            "Column {} must appear in the GROUP BY clause or be used in an aggregate function",
+            column
+        ))),
+        _ => Ok(expr.clone()),
     }
 }
@@ -1,5 +1,6 @@
 use crate::datasource::table::TableRef;
 use crate::datatype::schema::Schema;
+use crate::error::Error;
 use core::fmt::{Debug, Result};
 use std::{
     fmt::{Display, Formatter},
@@ -7,7 +8,7 @@ use std::{
     vec,
 };
 
-use super::logical_expr::{AggregateFuncExpr, LogicalExpr};
+use super::logical_expr::{AggregateFuncExpr, LogicalExpr, SortExpr, WindowFuncExpr};
 
 /// A logic plan is an intermediate representation generated during a query.
 /// It is used to express how to execute a query to satisfy the conditions in a logical expr.
@@ -28,8 +29,51 @@ pub enum LogicalPlan {
     /// Aggregate logical plan calculates aggregates of underlying data
     /// such as calculating minimum, maximum, averages, and sums of data.
     Aggregation(Aggregation),
+    /// The Window logical plan computes window functions for every row of its input
+    /// and appends their results as new columns, the rows themselves are unchanged.
+    /// This is represented by function calls with an OVER clause in SQL.
+    Window(Window),
     /// Join two logical plans on one or more join columns
     Join(Join),
+    /// The Sort logical plan orders the rows of its input by a list of sort expressions.
+    /// This is represented by the ORDER BY clause in SQL.
+    Sort(Sort),
+    /// The Limit logical plan skips some rows of its input and then returns at most `fetch` rows.
+    /// This is represented by the LIMIT and OFFSET clauses in SQL.
+    Limit(Limit),
+    /// The Distinct logical plan removes duplicate rows from its input.
+    /// This is represented by SELECT DISTINCT in SQL.
+    Distinct(Distinct),
+    /// The SubqueryAlias logical plan qualifies all fields of its input with an alias,
+    /// so that they can be referenced as `alias.column`.
+    /// This is represented by a table alias or a derived table in the FROM clause.
+    SubqueryAlias(SubqueryAlias),
+    /// The RecursiveQuery logical plan evaluates a recursive common table expression.
+    /// The static term is evaluated once, then the recursive term is evaluated repeatedly
+    /// on the rows produced by the previous iteration until no new rows are produced.
+    /// This is represented by WITH RECURSIVE in SQL.
+    RecursiveQuery(RecursiveQuery),
+    /// The WorkTableScan logical plan reads the rows produced by the previous iteration
+    /// of the recursive query with the same name. It is a leaf node like Scan.
+    WorkTableScan(WorkTableScan),
+    /// The Union logical plan returns the rows of both inputs.
+    /// This is represented by UNION [ALL] in SQL.
+    Union(Union),
+    /// The Intersect logical plan returns the rows of the left input that are also in the right input.
+    /// This is represented by INTERSECT [ALL] in SQL.
+    Intersect(Intersect),
+    /// The Except logical plan returns the rows of the left input that are not in the right input.
+    /// This is represented by EXCEPT [ALL] in SQL.
+    Except(Except),
+    /// The Explain logical plan returns the plans created for its input instead of executing it,
+    /// one row per plan. This is represented by EXPLAIN in SQL.
+    Explain(Explain),
+    /// The EmptyRelation logical plan produces no rows, it is the result of a statement
+    /// that is executed when it is planned, like CREATE EXTERNAL TABLE.
+    EmptyRelation(EmptyRelation),
+    /// The Values logical plan produces rows whose values are given by expressions.
+    /// It is a leaf node like Scan. This is represented by VALUES in SQL.
+    Values(Values),
 }
 
 impl LogicalPlan {
@@ -39,7 +83,20 @@ impl LogicalPlan {
             LogicalPlan::Projection(Projection { schema, .. }) => schema,
             LogicalPlan::Selection(Selection { input, .. }) => input.schema(),
             LogicalPlan::Aggregation(Aggregation { schema, .. }) => schema,
+            LogicalPlan::Window(Window { schema, .. }) => schema,
             LogicalPlan::Join(Join { schema, .. }) => schema,
+            LogicalPlan::Sort(Sort { input, .. }) => input.schema(),
+            LogicalPlan::Limit(Limit { input, .. }) => input.schema(),
+            LogicalPlan::Distinct(Distinct { input }) => input.schema(),
+            LogicalPlan::SubqueryAlias(SubqueryAlias { schema, .. }) => schema,
+            LogicalPlan::RecursiveQuery(RecursiveQuery { static_term, .. }) => static_term.schema(),
+            LogicalPlan::WorkTableScan(WorkTableScan { schema, .. }) => schema,
+            LogicalPlan::Union(Union { schema, .. }) => schema,
+            LogicalPlan::Intersect(Intersect { schema, .. }) => schema,
+            LogicalPlan::Except(Except { schema, .. }) => schema,
+            LogicalPlan::Explain(Explain { schema, .. }) => schema,
+            LogicalPlan::EmptyRelation(EmptyRelation { schema }) => schema,
+            LogicalPlan::Values(Values { schema, .. }) => schema,
         }
     }
 
@@ -49,9 +106,270 @@ impl LogicalPlan {
             LogicalPlan::Projection(Projection { input, .. }) => vec![input.clone()],
             LogicalPlan::Selection(Selection { input, .. }) => vec![input.clone()],
             LogicalPlan::Aggregation(Aggregation { input, .. }) => vec![input.clone()],
+            LogicalPlan::Window(Window { input, .. }) => vec![input.clone()],
             LogicalPlan::Join(Join { left, right, .. }) => vec![left.clone(), right.clone()],
+            LogicalPlan::Sort(Sort { input, .. }) => vec![input.clone()],
+            LogicalPlan::Limit(Limit { input, .. }) => vec![input.clone()],
+            LogicalPlan::Distinct(Distinct { input }) => vec![input.clone()],
+            LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => vec![input.clone()],
+            LogicalPlan::RecursiveQuery(RecursiveQuery {
+                static_term,
+                recursive_term,
+                ..
+            }) => vec![static_term.clone(), recursive_term.clone()],
+            LogicalPlan::WorkTableScan(_)
+            | LogicalPlan::EmptyRelation(_)
+            | LogicalPlan::Values(_) => vec![],
+            LogicalPlan::Union(Union { left, right, .. }) => vec![left.clone(), right.clone()],
+            LogicalPlan::Intersect(Intersect { left, right, .. }) => {
+                vec![left.clone(), right.clone()]
+            }
+            LogicalPlan::Except(Except { left, right, .. }) => vec![left.clone(), right.clone()],
+            LogicalPlan::Explain(Explain { plan, .. }) => vec![plan.clone()],
         }
     }
+
+    /// Returns a copy of the plan with its children replaced,
+    /// `children` must be in the same order as returned by `children`
+    pub fn with_new_children(
+        &self,
+        children: Vec<Arc<LogicalPlan>>,
+    ) -> crate::error::Result<LogicalPlan> {
+        if children.len() != self.children().len() {
+            return Err(Error::LogicalPlanError(format!(
+                "Expected {} children, got {}",
+                self.children().len(),
+                children.len()
+            )));
+        }
+
+        let child = |i: usize| children[i].clone();
+        let plan = match self {
+            LogicalPlan::Scan(_)
+            | LogicalPlan::WorkTableScan(_)
+            | LogicalPlan::EmptyRelation(_)
+            | LogicalPlan::Values(_) => self.clone(),
+            LogicalPlan::Projection(projection) => LogicalPlan::Projection(Projection {
+                input: child(0),
+                ..projection.clone()
+            }),
+            LogicalPlan::Selection(selection) => LogicalPlan::Selection(Selection {
+                input: child(0),
+                ..selection.clone()
+            }),
+            LogicalPlan::Aggregation(aggregation) => LogicalPlan::Aggregation(Aggregation {
+                input: child(0),
+                ..aggregation.clone()
+            }),
+            LogicalPlan::Window(window) => LogicalPlan::Window(Window {
+                input: child(0),
+                ..window.clone()
+            }),
+            LogicalPlan::Join(join) => LogicalPlan::Join(Join {
+                left: child(0),
+                right: child(1),
+                ..join.clone()
+            }),
+            LogicalPlan::Sort(sort) => LogicalPlan::Sort(Sort {
+                input: child(0),
+                ..sort.clone()
+            }),
+            LogicalPlan::Limit(limit) => LogicalPlan::Limit(Limit {
+                input: child(0),
+                ..limit.clone()
+            }),
+            LogicalPlan::Distinct(_) => LogicalPlan::Distinct(Distinct { input: child(0) }),
+            LogicalPlan::SubqueryAlias(subquery_alias) => {
+                LogicalPlan::SubqueryAlias(SubqueryAlias {
+                    input: child(0),
+                    ..subquery_alias.clone()
+                })
+            }
+            LogicalPlan::RecursiveQuery(recursive_query) => {
+                LogicalPlan::RecursiveQuery(RecursiveQuery {
+                    static_term: child(0),
+                    recursive_term: child(1),
+                    ..recursive_query.clone()
+                })
+            }
+            LogicalPlan::Union(union) => LogicalPlan::Union(Union {
+                left: child(0),
+                right: child(1),
+                ..union.clone()
+            }),
+            LogicalPlan::Intersect(intersect) => LogicalPlan::Intersect(Intersect {
+                left: child(0),
+                right: child(1),
+                ..intersect.clone()
+            }),
+            LogicalPlan::Except(except) => LogicalPlan::Except(Except {
+                left: child(0),
+                right: child(1),
+                ..except.clone()
+            }),
+            LogicalPlan::Explain(explain) => LogicalPlan::Explain(Explain {
+                plan: child(0),
+                ..explain.clone()
+            }),
+        };
+        Ok(plan)
+    }
+
+    /// Returns a compact representation of the plan with one node per line,
+    /// the children of a node are indented below it, e.g.
+    ///
+    /// ```text
+    /// Projection: name
+    ///   Selection: age >= 24
+    ///     Scan: CSV file [id, name, age, score]
+    /// ```
+    pub fn display_indent(&self) -> impl Display + '_ {
+        IndentDisplay(self)
+    }
+}
+
+struct IndentDisplay<'a>(&'a LogicalPlan);
+
+impl Display for IndentDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        fmt_indent(self.0, f, 0)
+    }
+}
+
+fn fmt_indent(plan: &LogicalPlan, f: &mut Formatter<'_>, depth: usize) -> Result {
+    if depth > 0 {
+        writeln!(f)?;
+    }
+    write!(f, "{}", "  ".repeat(depth))?;
+    fmt_node(plan, f)?;
+
+    for child in plan.children() {
+        fmt_indent(&child, f, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// Writes a single line describing the node without its children
+fn fmt_node(plan: &LogicalPlan, f: &mut Formatter<'_>) -> Result {
+    match plan {
+        LogicalPlan::Scan(Scan {
+            data_source,
+            projection,
+        }) => {
+            let fields = data_source.schema().fields();
+            let names = match projection {
+                Some(projection) => projection
+                    .iter()
+                    .map(|i| fields[*i].name().clone())
+                    .collect::<Vec<_>>(),
+                None => fields.iter().map(|field| field.name().clone()).collect(),
+            };
+            write!(
+                f,
+                "Scan: {} [{}]",
+                data_source.source_type(),
+                names.join(", ")
+            )
+        }
+        LogicalPlan::Projection(Projection { exprs, .. }) => {
+            write!(f, "Projection: {}", join_exprs(exprs))
+        }
+        LogicalPlan::Selection(Selection { expr, .. }) => write!(f, "Selection: {}", expr),
+        LogicalPlan::Aggregation(aggregation) => {
+            let aggr_expr = aggregation
+                .aggr_expr
+                .iter()
+                .cloned()
+                .map(LogicalExpr::AggregateFuncExpr)
+                .collect::<Vec<_>>();
+            if aggregation.has_grouping_sets() {
+                write!(
+                    f,
+                    "Aggregate: grouping_sets=[{}], aggr_expr=[{}]",
+                    aggregation.format_grouping_sets(),
+                    join_exprs(&aggr_expr)
+                )
+            } else {
+                write!(
+                    f,
+                    "Aggregate: group_expr=[{}], aggr_expr=[{}]",
+                    join_exprs(&aggregation.group_expr),
+                    join_exprs(&aggr_expr)
+                )
+            }
+        }
+        LogicalPlan::Window(Window { window_expr, .. }) => {
+            let window_expr = window_expr
+                .iter()
+                .cloned()
+                .map(LogicalExpr::WindowFuncExpr)
+                .collect::<Vec<_>>();
+            write!(f, "Window: window_expr=[{}]", join_exprs(&window_expr))
+        }
+        LogicalPlan::Join(Join { on, join_type, .. }) => {
+            let on = on
+                .iter()
+                .map(|(left, right)| format!("{} = {}", left, right))
+                .collect::<Vec<_>>();
+            write!(f, "Join: type={:?}, on=[{}]", join_type, on.join(", "))
+        }
+        LogicalPlan::Sort(Sort { exprs, .. }) => {
+            let exprs = exprs
+                .iter()
+                .map(|sort_expr| {
+                    format!(
+                        "{} {} NULLS {}",
+                        sort_expr.expr,
+                        if sort_expr.asc { "ASC" } else { "DESC" },
+                        if sort_expr.nulls_first {
+                            "FIRST"
+                        } else {
+                            "LAST"
+                        }
+                    )
+                })
+                .collect::<Vec<_>>();
+            write!(f, "Sort: {}", exprs.join(", "))
+        }
+        LogicalPlan::Limit(Limit { skip, fetch, .. }) => match fetch {
+            Some(fetch) => write!(f, "Limit: skip={}, fetch={}", skip, fetch),
+            None => write!(f, "Limit: skip={}, fetch=None", skip),
+        },
+        LogicalPlan::Distinct(_) => write!(f, "Distinct"),
+        LogicalPlan::SubqueryAlias(SubqueryAlias { alias, .. }) => {
+            write!(f, "SubqueryAlias: {}", alias)
+        }
+        LogicalPlan::RecursiveQuery(RecursiveQuery { name, distinct, .. }) => {
+            write!(f, "RecursiveQuery: name={}, distinct={}", name, distinct)
+        }
+        LogicalPlan::WorkTableScan(WorkTableScan { name, .. }) => {
+            write!(f, "WorkTableScan: {}", name)
+        }
+        LogicalPlan::Union(Union { all, .. }) => write!(f, "Union: all={}", all),
+        LogicalPlan::Intersect(Intersect { all, .. }) => write!(f, "Intersect: all={}", all),
+        LogicalPlan::Except(Except { all, .. }) => write!(f, "Except: all={}", all),
+        LogicalPlan::Explain(Explain {
+            verbose, analyze, ..
+        }) => {
+            write!(f, "Explain: verbose={}, analyze={}", verbose, analyze)
+        }
+        LogicalPlan::EmptyRelation(_) => write!(f, "EmptyRelation"),
+        LogicalPlan::Values(Values { values, .. }) => {
+            let rows = values
+                .iter()
+                .map(|row| format!("({})", join_exprs(row)))
+                .collect::<Vec<_>>();
+            write!(f, "Values: {}", rows.join(", "))
+        }
+    }
+}
+
+fn join_exprs(exprs: &[LogicalExpr]) -> String {
+    exprs
+        .iter()
+        .map(|expr| expr.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl Display for LogicalPlan {
@@ -88,25 +406,172 @@ pub struct Selection {
 #[derive(Debug, Clone)]
 pub struct Aggregation {
     pub input: Arc<LogicalPlan>,
-    pub group_expr: LogicalExpr,
+    pub group_expr: Vec<LogicalExpr>,
+    /// The sets of group exprs the rows are grouped by, as indices into `group_expr`.
+    /// `GROUP BY a, b` has the single set `[0, 1]`, the group columns that are not in the set
+    /// of a row are NULL, e.g. `ROLLUP(a, b)` has the sets `[0, 1]`, `[0]` and `[]`.
+    pub grouping_sets: Vec<Vec<usize>>,
     pub aggr_expr: Vec<AggregateFuncExpr>,
     pub schema: Schema,
 }
 
+impl Aggregation {
+    /// Whether the rows are grouped by anything else than all group exprs
+    pub fn has_grouping_sets(&self) -> bool {
+        self.grouping_sets.len() != 1
+            || !self.grouping_sets[0]
+                .iter()
+                .copied()
+                .eq(0..self.group_expr.len())
+    }
+
+    /// The grouping sets formatted with their group exprs, e.g. `(age, name), (age), ()`
+    pub fn format_grouping_sets(&self) -> String {
+        self.grouping_sets
+            .iter()
+            .map(|set| {
+                let exprs = set
+                    .iter()
+                    .map(|i| self.group_expr[*i].clone())
+                    .collect::<Vec<_>>();
+                format!("({})", join_exprs(&exprs))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub input: Arc<LogicalPlan>,
+    pub window_expr: Vec<WindowFuncExpr>,
+    /// The fields of the input followed by one field for each window expr
+    pub schema: Schema,
+}
+
 #[derive(Debug, Clone)]
 pub struct Join {
     pub left: Arc<LogicalPlan>,
-    pub on: Vec<(String, String)>,
+    /// Pairs of keys that are equal in the joined rows, the left key is evaluated on the
+    /// left input and the right key on the right input
+    pub on: Vec<(LogicalExpr, LogicalExpr)>,
     pub right: Arc<LogicalPlan>,
     pub join_type: JoinType,
     pub schema: Schema,
 }
 
+#[derive(Debug, Clone)]
+pub struct Sort {
+    pub input: Arc<LogicalPlan>,
+    pub exprs: Vec<SortExpr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Limit {
+    pub input: Arc<LogicalPlan>,
+    /// Number of rows to skip before fetching
+    pub skip: usize,
+    /// Maximum number of rows to fetch, `None` means fetching all rows
+    pub fetch: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Distinct {
+    pub input: Arc<LogicalPlan>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubqueryAlias {
+    pub input: Arc<LogicalPlan>,
+    pub alias: String,
+    /// The schema of the input with all fields qualified by the alias
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecursiveQuery {
+    /// Name of the common table expression, the recursive term refers to it by this name
+    pub name: String,
+    pub static_term: Arc<LogicalPlan>,
+    pub recursive_term: Arc<LogicalPlan>,
+    /// Whether rows that were already produced are removed (UNION rather than UNION ALL)
+    pub distinct: bool,
+    /// Evaluation fails if the recursion does not end within this number of iterations
+    pub max_iterations: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkTableScan {
+    /// Name of the recursive query whose rows are read
+    pub name: String,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone)]
+pub struct Union {
+    pub left: Arc<LogicalPlan>,
+    pub right: Arc<LogicalPlan>,
+    /// Whether duplicate rows are kept (UNION ALL rather than UNION)
+    pub all: bool,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone)]
+pub struct Intersect {
+    pub left: Arc<LogicalPlan>,
+    pub right: Arc<LogicalPlan>,
+    /// Whether a row is returned as many times as it is in both inputs,
+    /// rather than once (INTERSECT ALL rather than INTERSECT)
+    pub all: bool,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone)]
+pub struct Except {
+    pub left: Arc<LogicalPlan>,
+    pub right: Arc<LogicalPlan>,
+    /// Whether each right row only removes one matching left row,
+    /// rather than all of them (EXCEPT ALL rather than EXCEPT)
+    pub all: bool,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone)]
+pub struct Explain {
+    /// The plan that is explained, it is only executed by `EXPLAIN ANALYZE`
+    pub plan: Arc<LogicalPlan>,
+    /// Whether the plan after each optimizer rule is listed as well
+    pub verbose: bool,
+    /// Whether the plan is executed and shown with its metrics
+    pub analyze: bool,
+    /// `plan_type` and `plan` columns
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmptyRelation {
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone)]
+pub struct Values {
+    /// The rows, each one has an expression for every column of the schema
+    pub values: Vec<Vec<LogicalExpr>>,
+    pub schema: Schema,
+}
+
 #[derive(Debug, Clone)]
 pub enum JoinType {
     Inner,
     Left,
     Right,
+    /// Returns the left rows that have at least one match, only left columns are returned
+    Semi,
+    /// Returns the left rows that have no match, only left columns are returned
+    Anti,
+    /// Like `Anti`, but a NULL in the first join key removes the left row,
+    /// which matches the semantics of `NOT IN`
+    NullAwareAnti,
 }
 
 /// Implement more friendly output for logical plan
@@ -153,12 +618,15 @@ fn do_pretty_print(plan: &LogicalPlan, f: &mut Formatter<'_>, depth: usize) -> R
             writeln!(f, "input:")?;
             do_pretty_print(input.as_ref(), f, depth + 2)
         }
-        LogicalPlan::Aggregation(Aggregation {
-            input,
-            group_expr,
-            aggr_expr,
-            schema,
-        }) => {
+        LogicalPlan::Aggregation(
+            aggregation @ Aggregation {
+                input,
+                group_expr,
+                grouping_sets,
+                aggr_expr,
+                schema,
+            },
+        ) => {
             writeln!(f, "Aggregate:")?;
 
             write!(f, "{}", "  ".repeat(depth + 1))?;
@@ -168,12 +636,34 @@ fn do_pretty_print(plan: &LogicalPlan, f: &mut Formatter<'_>, depth: usize) -> R
             write!(f, "{}", "  ".repeat(depth + 1))?;
             writeln!(f, "group_expr: {:?}", group_expr)?;
 
+            if aggregation.has_grouping_sets() {
+                write!(f, "{}", "  ".repeat(depth + 1))?;
+                writeln!(f, "grouping_sets: {:?}", grouping_sets)?;
+            }
+
             write!(f, "{}", "  ".repeat(depth + 1))?;
             writeln!(f, "aggr_expr: {:?}", aggr_expr)?;
 
             write!(f, "{}", "  ".repeat(depth + 1))?;
             writeln!(f, "schema: {:?}", schema)
         }
+        LogicalPlan::Window(Window {
+            input,
+            window_expr,
+            schema,
+        }) => {
+            writeln!(f, "Window:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "input:")?;
+            do_pretty_print(input.as_ref(), f, depth + 2)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "window_expr: {:?}", window_expr)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "schema: {:?}", schema)
+        }
         LogicalPlan::Join(Join {
             left,
             right,
@@ -200,9 +690,146 @@ fn do_pretty_print(plan: &LogicalPlan, f: &mut Formatter<'_>, depth: usize) -> R
             write!(f, "{}", "  ".repeat(depth + 1))?;
             writeln!(f, "schema: {:?}", schema)
         }
+        LogicalPlan::Sort(Sort { input, exprs }) => {
+            writeln!(f, "Sort:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "exprs: {:?}", exprs)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "input:")?;
+            do_pretty_print(input.as_ref(), f, depth + 2)
+        }
+        LogicalPlan::Limit(Limit { input, skip, fetch }) => {
+            writeln!(f, "Limit:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "skip: {}", skip)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "fetch: {:?}", fetch)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "input:")?;
+            do_pretty_print(input.as_ref(), f, depth + 2)
+        }
+        LogicalPlan::Distinct(Distinct { input }) => {
+            writeln!(f, "Distinct:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "input:")?;
+            do_pretty_print(input.as_ref(), f, depth + 2)
+        }
+        LogicalPlan::RecursiveQuery(RecursiveQuery {
+            name,
+            static_term,
+            recursive_term,
+            distinct,
+            max_iterations,
+        }) => {
+            writeln!(f, "RecursiveQuery:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "name: {}", name)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "distinct: {}", distinct)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "max_iterations: {}", max_iterations)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "static_term:")?;
+            do_pretty_print(static_term.as_ref(), f, depth + 2)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "recursive_term:")?;
+            do_pretty_print(recursive_term.as_ref(), f, depth + 2)
+        }
+        LogicalPlan::WorkTableScan(WorkTableScan { name, .. }) => {
+            writeln!(f, "WorkTableScan:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "name: {}", name)
+        }
+        LogicalPlan::Union(Union {
+            left, right, all, ..
+        }) => {
+            writeln!(f, "Union:")?;
+            print_set_operation(f, depth, left, right, *all)
+        }
+        LogicalPlan::Intersect(Intersect {
+            left, right, all, ..
+        }) => {
+            writeln!(f, "Intersect:")?;
+            print_set_operation(f, depth, left, right, *all)
+        }
+        LogicalPlan::Except(Except {
+            left, right, all, ..
+        }) => {
+            writeln!(f, "Except:")?;
+            print_set_operation(f, depth, left, right, *all)
+        }
+        LogicalPlan::Explain(Explain {
+            plan,
+            verbose,
+            analyze,
+            ..
+        }) => {
+            writeln!(f, "Explain:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "verbose: {}", verbose)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "analyze: {}", analyze)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "plan:")?;
+            do_pretty_print(plan.as_ref(), f, depth + 2)
+        }
+        LogicalPlan::EmptyRelation(_) => writeln!(f, "EmptyRelation"),
+        LogicalPlan::Values(Values { values, schema }) => {
+            writeln!(f, "Values:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "values: {:?}", values)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "schema: {:?}", schema)
+        }
+        LogicalPlan::SubqueryAlias(SubqueryAlias { input, alias, .. }) => {
+            writeln!(f, "SubqueryAlias:")?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "alias: {}", alias)?;
+
+            write!(f, "{}", "  ".repeat(depth + 1))?;
+            writeln!(f, "input:")?;
+            do_pretty_print(input.as_ref(), f, depth + 2)
+        }
     }
 }
 
+fn print_set_operation(
+    f: &mut Formatter<'_>,
+    depth: usize,
+    left: &LogicalPlan,
+    right: &LogicalPlan,
+    all: bool,
+) -> Result {
+    write!(f, "{}", "  ".repeat(depth + 1))?;
+    writeln!(f, "all: {}", all)?;
+
+    write!(f, "{}", "  ".repeat(depth + 1))?;
+    writeln!(f, "left:")?;
+    do_pretty_print(left, f, depth + 2)?;
+
+    write!(f, "{}", "  ".repeat(depth + 1))?;
+    writeln!(f, "right:")?;
+    do_pretty_print(right, f, depth + 2)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::datasource::csv_table::CSVTable;
@@ -269,10 +896,13 @@ mod tests {
 
         let aggregate = LogicalPlan::Aggregation(Aggregation {
             input: scan.clone(),
-            group_expr: LogicalExpr::Column("age".to_string()),
+            group_expr: vec![LogicalExpr::Column("age".to_string())],
+            grouping_sets: vec![vec![0]],
             aggr_expr: vec![AggregateFuncExpr {
                 func: AggregateFunc::MAX,
                 expr: Box::new(LogicalExpr::Column("age".to_string())),
+                distinct: false,
+                filter: None,
             }],
             schema: schema.clone(),
         });
@@ -284,7 +914,7 @@ mod tests {
             \n      source_type: \"CSV file\"\
             \n      projection: None\
             \n  group_expr: [Column(\"age\")]\
-            \n  aggr_expr: [AggregateFuncExpr { func: MAX, expr: Column(\"age\") }]\
+            \n  aggr_expr: [AggregateFuncExpr { func: MAX, expr: Column(\"age\"), distinct: false, filter: None }]\
             \n  schema: Schema { fields: [] }\n",
             format!("{}", aggregate)
         );
@@ -1,3 +1,7 @@
 pub mod data_frame;
 pub mod logical_expr;
+#[allow(clippy::module_inception)]
 pub mod logical_plan;
+pub mod optimizer;
+pub mod subquery;
+pub mod type_coercion;
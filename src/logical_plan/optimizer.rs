@@ -0,0 +1,244 @@
+//! The optimizer rewrites a logical plan into an equivalent plan that can be executed.
+//! Each rule is applied to the whole plan, in the order the rules are registered.
+
+use crate::error::Result;
+use crate::logical_plan::logical_expr::{AggregateFuncExpr, SortExpr};
+use crate::logical_plan::logical_plan::{
+    Aggregation, Join, LogicalPlan, Projection, Selection, Sort, Values, Window,
+};
+use crate::logical_plan::subquery::{contains_subquery, rewrite_projection, rewrite_selection};
+use crate::logical_plan::type_coercion::{coerce_expr, coerce_join_keys, coerce_window_expr};
+use std::sync::Arc;
+
+pub trait OptimizerRule {
+    /// The name shown by `EXPLAIN VERBOSE`
+    fn name(&self) -> &str;
+
+    fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan>;
+}
+
+pub struct Optimizer {
+    rules: Vec<Box<dyn OptimizerRule>>,
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self {
+            rules: vec![Box::new(DecorrelateSubqueries), Box::new(TypeCoercion)],
+        }
+    }
+}
+
+impl Optimizer {
+    pub fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        self.optimize_with_observer(plan, |_, _| {})
+    }
+
+    /// Optimizes the plan, `observer` is called with the plan after each rule
+    pub fn optimize_with_observer(
+        &self,
+        plan: &LogicalPlan,
+        mut observer: impl FnMut(&LogicalPlan, &dyn OptimizerRule),
+    ) -> Result<LogicalPlan> {
+        let mut plan = plan.clone();
+        for rule in &self.rules {
+            plan = rule.optimize(&plan)?;
+            observer(&plan, rule.as_ref());
+        }
+        Ok(plan)
+    }
+}
+
+/// Applies `rewrite` to each node of the plan, the children of a node are rewritten
+/// after the node itself. The plan of an EXPLAIN is left as it is.
+fn rewrite_plan(
+    plan: &LogicalPlan,
+    rewrite: &impl Fn(&LogicalPlan) -> Result<LogicalPlan>,
+) -> Result<LogicalPlan> {
+    if let LogicalPlan::Explain(_) = plan {
+        return Ok(plan.clone());
+    }
+
+    let plan = rewrite(plan)?;
+    let children = plan
+        .children()
+        .iter()
+        .map(|child| Ok(Arc::new(rewrite_plan(child, rewrite)?)))
+        .collect::<Result<Vec<_>>>()?;
+    plan.with_new_children(children)
+}
+
+/// Rewrites the subquery expressions into joins, see `subquery`
+pub struct DecorrelateSubqueries;
+
+impl DecorrelateSubqueries {
+    fn rewrite(plan: &LogicalPlan) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::Selection(selection) if contains_subquery(&selection.expr) => {
+                DecorrelateSubqueries::rewrite(&rewrite_selection(selection)?)
+            }
+            LogicalPlan::Projection(projection)
+                if projection.exprs.iter().any(contains_subquery) =>
+            {
+                DecorrelateSubqueries::rewrite(&rewrite_projection(projection)?)
+            }
+            _ => Ok(plan.clone()),
+        }
+    }
+}
+
+impl OptimizerRule for DecorrelateSubqueries {
+    fn name(&self) -> &str {
+        "decorrelate_subqueries"
+    }
+
+    fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        rewrite_plan(plan, &DecorrelateSubqueries::rewrite)
+    }
+}
+
+/// Inserts the casts needed to evaluate the expressions, see `type_coercion`.
+/// The output schema of each node stays the same.
+pub struct TypeCoercion;
+
+impl TypeCoercion {
+    fn rewrite(plan: &LogicalPlan) -> Result<LogicalPlan> {
+        let plan = match plan {
+            LogicalPlan::Projection(projection) => LogicalPlan::Projection(Projection {
+                exprs: projection
+                    .exprs
+                    .iter()
+                    .map(|expr| coerce_expr(expr, &projection.input))
+                    .collect::<Result<Vec<_>>>()?,
+                ..projection.clone()
+            }),
+            LogicalPlan::Selection(selection) => LogicalPlan::Selection(Selection {
+                expr: coerce_expr(&selection.expr, &selection.input)?,
+                ..selection.clone()
+            }),
+            LogicalPlan::Aggregation(aggregation) => LogicalPlan::Aggregation(Aggregation {
+                group_expr: aggregation
+                    .group_expr
+                    .iter()
+                    .map(|expr| coerce_expr(expr, &aggregation.input))
+                    .collect::<Result<Vec<_>>>()?,
+                aggr_expr: aggregation
+                    .aggr_expr
+                    .iter()
+                    .map(|aggr_expr| {
+                        let filter = match &aggr_expr.filter {
+                            Some(filter) => {
+                                Some(Box::new(coerce_expr(filter, &aggregation.input)?))
+                            }
+                            None => None,
+                        };
+                        Ok(AggregateFuncExpr {
+                            func: aggr_expr.func.clone(),
+                            expr: Box::new(coerce_expr(&aggr_expr.expr, &aggregation.input)?),
+                            distinct: aggr_expr.distinct,
+                            filter,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                ..aggregation.clone()
+            }),
+            LogicalPlan::Window(window) => LogicalPlan::Window(Window {
+                window_expr: window
+                    .window_expr
+                    .iter()
+                    .map(|window_expr| coerce_window_expr(window_expr, &window.input))
+                    .collect::<Result<Vec<_>>>()?,
+                ..window.clone()
+            }),
+            LogicalPlan::Sort(sort) => LogicalPlan::Sort(Sort {
+                exprs: sort
+                    .exprs
+                    .iter()
+                    .map(|sort_expr| {
+                        Ok(SortExpr {
+                            expr: coerce_expr(&sort_expr.expr, &sort.input)?,
+                            ..sort_expr.clone()
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                ..sort.clone()
+            }),
+            LogicalPlan::Join(join) => LogicalPlan::Join(Join {
+                on: join
+                    .on
+                    .iter()
+                    .map(|keys| coerce_join_keys(keys, &join.left, &join.right))
+                    .collect::<Result<Vec<_>>>()?,
+                ..join.clone()
+            }),
+            // The values do not refer to any columns
+            LogicalPlan::Values(values) => LogicalPlan::Values(Values {
+                values: values
+                    .values
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|expr| coerce_expr(expr, plan))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                ..values.clone()
+            }),
+            _ => plan.clone(),
+        };
+        Ok(plan)
+    }
+}
+
+impl OptimizerRule for TypeCoercion {
+    fn name(&self) -> &str {
+        "type_coercion"
+    }
+
+    fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        rewrite_plan(plan, &TypeCoercion::rewrite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    #[test]
+    fn optimize_plan() -> Result<()> {
+        let mut catalog = Catalog::default();
+        catalog.add_csv_table("test", "data/test.csv")?;
+        catalog.add_csv_table("salary", "data/salary.csv")?;
+
+        let plan = catalog
+            .sql("SELECT name FROM test WHERE age + score > 100 AND id IN (SELECT id FROM salary)")?
+            .plan;
+        assert_eq!(
+            plan.display_indent().to_string(),
+            "Projection: name\
+           \n  Selection: age + score > 100 AND id IN (<subquery>)\
+           \n    SubqueryAlias: test\
+           \n      Scan: CSV file [id, name, age, score]"
+        );
+
+        let mut rules = vec![];
+        let optimized = Optimizer::default()
+            .optimize_with_observer(&plan, |_, rule| rules.push(rule.name().to_string()))?;
+        assert_eq!(rules, vec!["decorrelate_subqueries", "type_coercion"]);
+        assert_eq!(
+            optimized.display_indent().to_string(),
+            "Projection: name\
+           \n  Selection: CAST(age AS Float64) + score > CAST(100 AS Float64)\
+           \n    Join: type=Semi, on=[id = __subquery_1.__value]\
+           \n      SubqueryAlias: test\
+           \n        Scan: CSV file [id, name, age, score]\
+           \n      SubqueryAlias: __subquery_1\
+           \n        Projection: id AS __value\
+           \n          SubqueryAlias: salary\
+           \n            Scan: CSV file [id, name, salary, awards]"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,510 @@
+//! Subquery expressions cannot be evaluated by the physical operators directly,
+//! so the plans using them are rewritten into joins with the subqueries:
+//!
+//! * `[NOT] EXISTS (subquery)` becomes a semi (anti) join
+//! * `expr [NOT] IN (subquery)` becomes a semi (null-aware anti) join on `expr`
+//! * a scalar subquery becomes a left join with its result, grouped by the correlated columns.
+//!   A COUNT is 0 instead of NULL for the outer rows that have no match.
+//!
+//! Correlated subqueries are decorrelated by turning the equality predicates that reference
+//! the outer query into join keys. The joined columns are removed again, so the rewritten
+//! plan has the same schema as the original one.
+
+use crate::datatype::field::Field;
+use crate::datatype::scalar::Scalar;
+use crate::error::{Error, Result};
+use crate::logical_plan::data_frame::DataFrame;
+use crate::logical_plan::logical_expr::{
+    AggregateFunc, Alias, BinaryExpr, Case, Exists, InSubquery, LogicalExpr, Operator, Subquery,
+    UnaryExpr, UnaryOperator,
+};
+use crate::logical_plan::logical_plan::{JoinType, LogicalPlan, Projection, Selection};
+
+/// Whether the expr contains a subquery
+pub fn contains_subquery(expr: &LogicalExpr) -> bool {
+    match expr {
+        LogicalExpr::ScalarSubquery(_) | LogicalExpr::InSubquery(_) | LogicalExpr::Exists(_) => {
+            true
+        }
+        LogicalExpr::BinaryExpr(binary_expr) => {
+            contains_subquery(&binary_expr.left) || contains_subquery(&binary_expr.right)
+        }
+        LogicalExpr::Alias(alias) => contains_subquery(&alias.expr),
+        LogicalExpr::AggregateFuncExpr(aggr_expr) => {
+            contains_subquery(&aggr_expr.expr)
+                || aggr_expr.filter.as_deref().is_some_and(contains_subquery)
+        }
+        LogicalExpr::UnaryExpr(unary_expr) => contains_subquery(&unary_expr.expr),
+        LogicalExpr::InList(in_list) => {
+            contains_subquery(&in_list.expr) || in_list.list.iter().any(contains_subquery)
+        }
+        LogicalExpr::Between(between) => {
+            contains_subquery(&between.expr)
+                || contains_subquery(&between.low)
+                || contains_subquery(&between.high)
+        }
+        _ => false,
+    }
+}
+
+/// Checks that the subquery of a subquery expression can be decorrelated, so that
+/// an unsupported subquery fails when the query is planned instead of when it is executed
+pub fn check_subquery(expr: LogicalExpr) -> Result<LogicalExpr> {
+    match &expr {
+        LogicalExpr::ScalarSubquery(subquery) => {
+            decorrelate_scalar(subquery)?;
+        }
+        LogicalExpr::InSubquery(in_subquery) => {
+            decorrelate_relation(&in_subquery.subquery, true)?;
+        }
+        LogicalExpr::Exists(exists) => {
+            decorrelate_relation(&exists.subquery, false)?;
+        }
+        _ => {}
+    }
+    Ok(expr)
+}
+
+/// Rewrites a selection whose predicate contains subqueries.
+/// IN and EXISTS are only supported as operands of the top-level AND conditions.
+pub fn rewrite_selection(selection: &Selection) -> Result<LogicalPlan> {
+    let input = selection.input.as_ref();
+    let mut rewriter = SubqueryRewriter {
+        df: DataFrame::new(input.clone()),
+        count: 0,
+    };
+
+    let mut conjuncts = vec![];
+    split_conjunction(&selection.expr, &mut conjuncts);
+
+    let mut predicates = vec![];
+    for conjunct in conjuncts {
+        match conjunct {
+            LogicalExpr::Exists(exists) => rewriter.join_exists(exists)?,
+            LogicalExpr::InSubquery(in_subquery) => rewriter.join_in_subquery(in_subquery)?,
+            expr => predicates.push(rewriter.rewrite_scalar_subqueries(expr)?),
+        }
+    }
+
+    let mut df = rewriter.df;
+    if let Some(predicate) = predicates.into_iter().reduce(|left, right| {
+        LogicalExpr::BinaryExpr(BinaryExpr {
+            left: Box::new(left),
+            op: Operator::And,
+            right: Box::new(right),
+        })
+    }) {
+        df = df.select(predicate)?;
+    }
+
+    restore_columns(df, input)
+}
+
+/// Rewrites a projection that contains scalar subqueries
+pub fn rewrite_projection(projection: &Projection) -> Result<LogicalPlan> {
+    let mut rewriter = SubqueryRewriter {
+        df: DataFrame::new(projection.input.as_ref().clone()),
+        count: 0,
+    };
+
+    let mut exprs = vec![];
+    for (expr, field) in projection.exprs.iter().zip(projection.schema.fields()) {
+        if !contains_subquery(expr) {
+            exprs.push(expr.clone());
+            continue;
+        }
+        // Keeps the name of the original expression
+        match rewriter.rewrite_scalar_subqueries(expr)? {
+            expr @ LogicalExpr::Alias(_) => exprs.push(expr),
+            expr => exprs.push(alias(&expr, field.name())),
+        }
+    }
+
+    Ok(rewriter.df.project(exprs)?.plan)
+}
+
+struct SubqueryRewriter {
+    /// The outer query joined with the subqueries rewritten so far
+    df: DataFrame,
+    /// Number of subqueries rewritten so far, used to name the joined subqueries
+    count: usize,
+}
+
+impl SubqueryRewriter {
+    fn next_alias(&mut self) -> String {
+        self.count += 1;
+        format!("__subquery_{}", self.count)
+    }
+
+    fn join_exists(&mut self, exists: &Exists) -> Result<()> {
+        let alias = self.next_alias();
+        let (right, outer_keys) = decorrelate_relation(&exists.subquery, false)?;
+        let right = DataFrame::new(right).alias(&alias)?;
+
+        let right_keys = (0..outer_keys.len())
+            .map(|i| format!("{}.__key_{}", alias, i))
+            .collect();
+        let join_type = if exists.negated {
+            JoinType::Anti
+        } else {
+            JoinType::Semi
+        };
+
+        self.df = self
+            .df
+            .clone()
+            .join(right.plan(), join_type, (outer_keys, right_keys))?;
+        Ok(())
+    }
+
+    fn join_in_subquery(&mut self, in_subquery: &InSubquery) -> Result<()> {
+        let alias = self.next_alias();
+        let (right, outer_keys) = decorrelate_relation(&in_subquery.subquery, true)?;
+        let right = DataFrame::new(right).alias(&alias)?;
+
+        // The compared value has to be a column of the left side to be used as a join key
+        let value = match in_subquery.expr.as_ref() {
+            LogicalExpr::Column(name) => name.clone(),
+            expr => {
+                let name = format!("{}_value", alias);
+                let mut exprs = self
+                    .df
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|field| LogicalExpr::Column(field.qualified_name()))
+                    .collect::<Vec<_>>();
+                exprs.push(LogicalExpr::Alias(Alias {
+                    name: name.clone(),
+                    expr: Box::new(expr.clone()),
+                }));
+                self.df = self.df.clone().project(exprs)?;
+                name
+            }
+        };
+
+        let mut left_keys = vec![value];
+        left_keys.extend(outer_keys);
+        let mut right_keys = vec![format!("{}.__value", alias)];
+        right_keys.extend((0..left_keys.len() - 1).map(|i| format!("{}.__key_{}", alias, i)));
+
+        let join_type = if in_subquery.negated {
+            JoinType::NullAwareAnti
+        } else {
+            JoinType::Semi
+        };
+
+        self.df = self
+            .df
+            .clone()
+            .join(right.plan(), join_type, (left_keys, right_keys))?;
+        Ok(())
+    }
+
+    /// Left joins every scalar subquery of the expr and replaces it with the joined column
+    fn rewrite_scalar_subqueries(&mut self, expr: &LogicalExpr) -> Result<LogicalExpr> {
+        match expr {
+            LogicalExpr::ScalarSubquery(subquery) => {
+                let alias = self.next_alias();
+                let (right, outer_keys, empty_value) = decorrelate_scalar(subquery)?;
+                let right = DataFrame::new(right).alias(&alias)?;
+
+                let right_keys = (0..outer_keys.len())
+                    .map(|i| format!("{}.__key_{}", alias, i))
+                    .collect();
+
+                self.df =
+                    self.df
+                        .clone()
+                        .join(right.plan(), JoinType::Left, (outer_keys, right_keys))?;
+
+                let value = LogicalExpr::Column(format!("{}.__value", alias));
+                Ok(match empty_value {
+                    // The outer rows without a match get NULL from the join
+                    Some(empty_value) => LogicalExpr::Case(Case {
+                        expr: None,
+                        when_then_expr: vec![(
+                            Box::new(LogicalExpr::UnaryExpr(UnaryExpr {
+                                op: UnaryOperator::IsNull,
+                                expr: Box::new(value.clone()),
+                            })),
+                            Box::new(LogicalExpr::Literal(empty_value)),
+                        )],
+                        else_expr: Some(Box::new(value)),
+                    }),
+                    None => value,
+                })
+            }
+            LogicalExpr::BinaryExpr(binary_expr) => Ok(LogicalExpr::BinaryExpr(BinaryExpr {
+                left: Box::new(self.rewrite_scalar_subqueries(&binary_expr.left)?),
+                op: binary_expr.op.clone(),
+                right: Box::new(self.rewrite_scalar_subqueries(&binary_expr.right)?),
+            })),
+            LogicalExpr::Alias(alias) => Ok(LogicalExpr::Alias(Alias {
+                name: alias.name.clone(),
+                expr: Box::new(self.rewrite_scalar_subqueries(&alias.expr)?),
+            })),
+            LogicalExpr::InSubquery(_) | LogicalExpr::Exists(_) => Err(Error::LogicalPlanError(
+                "IN and EXISTS subqueries are only supported as AND conditions of WHERE or HAVING"
+                    .to_string(),
+            )),
+            expr => Ok(expr.clone()),
+        }
+    }
+}
+
+/// Prepares the subquery of IN or EXISTS to be joined. The result has a `__value` column
+/// with the value of the select list if `with_value` is true, and a `__key_i` column for each
+/// correlated column. The returned keys are the outer columns to be joined with them.
+fn decorrelate_relation(
+    subquery: &Subquery,
+    with_value: bool,
+) -> Result<(LogicalPlan, Vec<String>)> {
+    let plan = match subquery.plan.as_ref() {
+        // Removing duplicates does not change which rows have a match
+        LogicalPlan::Distinct(distinct) => distinct.input.as_ref(),
+        plan => plan,
+    };
+
+    let (input, select_exprs, correlated) = match plan {
+        LogicalPlan::Projection(projection) => {
+            let (input, correlated) = pull_up_correlated(&projection.input)?;
+            (input, projection.exprs.clone(), correlated)
+        }
+        plan => (
+            plan.clone(),
+            plan.schema()
+                .fields()
+                .iter()
+                .map(|field| LogicalExpr::Column(field.qualified_name()))
+                .collect(),
+            vec![],
+        ),
+    };
+
+    let mut exprs = vec![];
+    if with_value {
+        if select_exprs.len() != 1 {
+            return Err(Error::LogicalPlanError(format!(
+                "The subquery of IN must return exactly one column, found {}",
+                select_exprs.len()
+            )));
+        }
+        exprs.push(alias(&select_exprs[0], "__value"));
+    }
+
+    let mut outer_keys = vec![];
+    for (i, (inner, outer)) in correlated.iter().enumerate() {
+        exprs.push(alias(inner, &format!("__key_{}", i)));
+        outer_keys.push(outer.qualified_name());
+    }
+
+    // An uncorrelated EXISTS only needs to know whether the subquery returns rows
+    if exprs.is_empty() {
+        return Ok((input, outer_keys));
+    }
+
+    Ok((DataFrame::new(input).project(exprs)?.plan, outer_keys))
+}
+
+/// Prepares a scalar subquery to be joined. Only a single aggregate without GROUP BY is
+/// supported, so the subquery returns exactly one row for each value of the correlated columns.
+/// The result has a `__value` column and a `__key_i` column for each correlated column.
+/// The returned value is the result of the subquery for the outer rows without matching
+/// inner rows, if it is not NULL.
+fn decorrelate_scalar(subquery: &Subquery) -> Result<(LogicalPlan, Vec<String>, Option<Scalar>)> {
+    let unsupported = || {
+        Err(Error::LogicalPlanError(
+            "A scalar subquery must compute a single aggregate without GROUP BY, \
+            e.g. (SELECT AVG(salary) FROM salary)"
+                .to_string(),
+        ))
+    };
+
+    let projection = match subquery.plan.as_ref() {
+        LogicalPlan::Projection(projection) if projection.exprs.len() == 1 => projection,
+        _ => return unsupported(),
+    };
+
+    // HAVING adds a selection on top of the aggregation
+    let (aggregation, having) = match projection.input.as_ref() {
+        LogicalPlan::Aggregation(aggregation) => (aggregation, None),
+        LogicalPlan::Selection(selection) => match selection.input.as_ref() {
+            LogicalPlan::Aggregation(aggregation) => (aggregation, Some(selection.expr.clone())),
+            _ => return unsupported(),
+        },
+        _ => return unsupported(),
+    };
+    if !aggregation.group_expr.is_empty() || aggregation.has_grouping_sets() {
+        return unsupported();
+    }
+
+    let (input, correlated) = pull_up_correlated(&aggregation.input)?;
+
+    // A COUNT of no rows is 0, but the grouped subquery has no group for the outer rows
+    // without matching inner rows
+    let mut empty_value = None;
+    if !correlated.is_empty() && having.is_none() {
+        let value_name = projection.exprs[0]
+            .to_field(&projection.input)?
+            .qualified_name();
+        for aggr_expr in &aggregation.aggr_expr {
+            if !matches!(aggr_expr.func, AggregateFunc::COUNT) {
+                continue;
+            }
+            let name = aggr_expr.to_field(&aggregation.input)?.qualified_name();
+            if name != value_name {
+                return Err(Error::NotImplemented(format!(
+                    "A correlated scalar subquery can only use COUNT as its whole result, found {}",
+                    projection.exprs[0]
+                )));
+            }
+            empty_value = Some(Scalar::UInt64(Some(0)));
+        }
+    }
+
+    // The aggregates are computed for each value of the correlated columns
+    let group_expr = correlated
+        .iter()
+        .map(|(inner, _)| inner.clone())
+        .collect::<Vec<_>>();
+    let mut exprs = vec![alias(&projection.exprs[0], "__value")];
+    let mut outer_keys = vec![];
+    for (i, (inner, outer)) in correlated.iter().enumerate() {
+        let name = inner.to_field(&input)?.qualified_name();
+        exprs.push(alias(&LogicalExpr::Column(name), &format!("__key_{}", i)));
+        outer_keys.push(outer.qualified_name());
+    }
+
+    let mut df = DataFrame::new(input).aggregate(group_expr, aggregation.aggr_expr.clone())?;
+    if let Some(having) = having {
+        df = df.select(having)?;
+    }
+
+    Ok((df.project(exprs)?.plan, outer_keys, empty_value))
+}
+
+/// Removes the correlated predicates from the selections at the top of the plan.
+/// Each of them has to be an equality between an expression of the subquery and
+/// an outer column, they are returned as pairs of the two sides.
+fn pull_up_correlated(plan: &LogicalPlan) -> Result<(LogicalPlan, Vec<(LogicalExpr, Field)>)> {
+    let selection = match plan {
+        LogicalPlan::Selection(selection) => selection,
+        plan => return Ok((plan.clone(), vec![])),
+    };
+
+    let (input, mut correlated) = pull_up_correlated(&selection.input)?;
+
+    let mut conjuncts = vec![];
+    split_conjunction(&selection.expr, &mut conjuncts);
+
+    let mut predicates = vec![];
+    for conjunct in conjuncts {
+        if !contains_outer_column(conjunct) {
+            predicates.push(conjunct.clone());
+            continue;
+        }
+
+        match conjunct {
+            LogicalExpr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::Eq,
+                right,
+            }) => match (left.as_ref(), right.as_ref()) {
+                (LogicalExpr::OuterColumn(outer), inner)
+                | (inner, LogicalExpr::OuterColumn(outer))
+                    if !contains_outer_column(inner) =>
+                {
+                    correlated.push((inner.clone(), outer.clone()))
+                }
+                _ => return unsupported_correlation(conjunct),
+            },
+            _ => return unsupported_correlation(conjunct),
+        }
+    }
+
+    let mut df = DataFrame::new(input);
+    if let Some(predicate) = predicates.into_iter().reduce(|left, right| {
+        LogicalExpr::BinaryExpr(BinaryExpr {
+            left: Box::new(left),
+            op: Operator::And,
+            right: Box::new(right),
+        })
+    }) {
+        df = df.select(predicate)?;
+    }
+
+    Ok((df.plan, correlated))
+}
+
+fn unsupported_correlation<T>(expr: &LogicalExpr) -> Result<T> {
+    Err(Error::LogicalPlanError(format!(
+        "Correlated predicates must be equalities with an outer column, found {}",
+        expr
+    )))
+}
+
+fn contains_outer_column(expr: &LogicalExpr) -> bool {
+    match expr {
+        LogicalExpr::OuterColumn(_) => true,
+        LogicalExpr::BinaryExpr(binary_expr) => {
+            contains_outer_column(&binary_expr.left) || contains_outer_column(&binary_expr.right)
+        }
+        LogicalExpr::Alias(alias) => contains_outer_column(&alias.expr),
+        LogicalExpr::AggregateFuncExpr(aggr_expr) => {
+            contains_outer_column(&aggr_expr.expr)
+                || aggr_expr
+                    .filter
+                    .as_deref()
+                    .is_some_and(contains_outer_column)
+        }
+        LogicalExpr::InSubquery(in_subquery) => contains_outer_column(&in_subquery.expr),
+        LogicalExpr::UnaryExpr(unary_expr) => contains_outer_column(&unary_expr.expr),
+        LogicalExpr::InList(in_list) => {
+            contains_outer_column(&in_list.expr) || in_list.list.iter().any(contains_outer_column)
+        }
+        LogicalExpr::Between(between) => {
+            contains_outer_column(&between.expr)
+                || contains_outer_column(&between.low)
+                || contains_outer_column(&between.high)
+        }
+        _ => false,
+    }
+}
+
+/// Projects the plan back to the columns of `original`
+fn restore_columns(df: DataFrame, original: &LogicalPlan) -> Result<LogicalPlan> {
+    if df.schema().fields().len() == original.schema().fields().len() {
+        return Ok(df.plan);
+    }
+
+    let exprs = original
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| LogicalExpr::Column(field.qualified_name()))
+        .collect();
+    Ok(df.project(exprs)?.plan)
+}
+
+fn alias(expr: &LogicalExpr, name: &str) -> LogicalExpr {
+    LogicalExpr::Alias(Alias {
+        name: name.to_string(),
+        expr: Box::new(expr.clone()),
+    })
+}
+
+/// Flattens `a AND b AND c` into `[a, b, c]`
+fn split_conjunction<'e>(expr: &'e LogicalExpr, exprs: &mut Vec<&'e LogicalExpr>) {
+    match expr {
+        LogicalExpr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => {
+            split_conjunction(left, exprs);
+            split_conjunction(right, exprs);
+        }
+        _ => exprs.push(expr),
+    }
+}
@@ -0,0 +1,325 @@
+//! Implicit type coercion. The operands of a binary expression must have the same type
+//! when it is evaluated, so the coercion pass casts the operands to a common type first.
+//! Numbers are widened (Int64 and UInt64 to Float64, UInt64 to Int64) and strings
+//! compared with dates are parsed as dates.
+
+use crate::datatype::field::common_type;
+use crate::datatype::scalar::Scalar;
+use crate::error::{Error, Result};
+use crate::logical_plan::logical_expr::{
+    AggregateFuncExpr, Alias, Between, BinaryExpr, Case, Cast, InList, InSubquery, LogicalExpr,
+    Operator, PatternMatch, SortExpr, TryCast, UnaryExpr, UnaryOperator, WindowFuncExpr,
+};
+use crate::logical_plan::logical_plan::LogicalPlan;
+use arrow::array::new_null_array;
+use arrow::compute::{cast_with_options, CastOptions};
+use arrow::datatypes::DataType;
+
+/// Returns the type both operands of `left op right` are cast to before evaluating it
+pub fn coerce_types(left: &DataType, op: &Operator, right: &DataType) -> Result<DataType> {
+    let data_type = match op {
+        Operator::Eq
+        | Operator::Neq
+        | Operator::Gt
+        | Operator::GtEq
+        | Operator::Lt
+        | Operator::LtEq
+        | Operator::IsDistinctFrom
+        | Operator::IsNotDistinctFrom => comparison_coercion(left, right),
+        Operator::And | Operator::Or => match (left, right) {
+            (DataType::Boolean | DataType::Null, DataType::Boolean | DataType::Null) => {
+                Some(DataType::Boolean)
+            }
+            _ => None,
+        },
+        Operator::Add | Operator::Sub | Operator::Mul | Operator::Div | Operator::Mod => {
+            match common_type(left, right) {
+                Some(DataType::Null) => Some(DataType::Int64),
+                Some(data_type) if is_numeric(&data_type) => Some(data_type),
+                _ => None,
+            }
+        }
+    };
+
+    data_type.ok_or_else(|| {
+        Error::LogicalPlanError(format!(
+            "Cannot apply {:?} to {:?} and {:?}",
+            op, left, right
+        ))
+    })
+}
+
+fn comparison_coercion(left: &DataType, right: &DataType) -> Option<DataType> {
+    match (left, right) {
+        (DataType::Utf8, date @ (DataType::Date32 | DataType::Date64))
+        | (date @ (DataType::Date32 | DataType::Date64), DataType::Utf8) => Some(date.clone()),
+        _ => common_type(left, right),
+    }
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int64 | DataType::UInt64 | DataType::Float64
+    )
+}
+
+/// Casts `expr` to `data_type` unless it already has that type
+fn cast_if_needed(expr: LogicalExpr, from: &DataType, data_type: &DataType) -> LogicalExpr {
+    if from == data_type {
+        expr
+    } else {
+        LogicalExpr::Cast(Cast {
+            expr: Box::new(expr),
+            data_type: data_type.clone(),
+        })
+    }
+}
+
+/// Casts a literal when the plan is built, so that e.g. the values of an IN list
+/// stay literals. None if the literal cannot be converted here.
+fn cast_literal(scalar: &Scalar, data_type: &DataType) -> Option<Scalar> {
+    let array = match scalar {
+        Scalar::Null => new_null_array(data_type, 1),
+        scalar => cast_with_options(
+            &scalar.clone().to_array(1),
+            data_type,
+            &CastOptions { safe: false },
+        )
+        .ok()?,
+    };
+    Scalar::try_from_array(&array, 0).ok()
+}
+
+/// Inserts the casts required to evaluate `expr` on the output of `input`
+pub fn coerce_expr(expr: &LogicalExpr, input: &LogicalPlan) -> Result<LogicalExpr> {
+    let coerce = |expr: &LogicalExpr| coerce_expr(expr, input).map(Box::new);
+
+    match expr {
+        LogicalExpr::BinaryExpr(binary_expr) => {
+            let left_type = binary_expr.left.to_field(input)?.data_type().clone();
+            let right_type = binary_expr.right.to_field(input)?.data_type().clone();
+            let data_type = coerce_types(&left_type, &binary_expr.op, &right_type)?;
+
+            Ok(LogicalExpr::BinaryExpr(BinaryExpr {
+                left: Box::new(cast_if_needed(
+                    coerce_expr(&binary_expr.left, input)?,
+                    &left_type,
+                    &data_type,
+                )),
+                op: binary_expr.op.clone(),
+                right: Box::new(cast_if_needed(
+                    coerce_expr(&binary_expr.right, input)?,
+                    &right_type,
+                    &data_type,
+                )),
+            }))
+        }
+        LogicalExpr::Alias(alias) => Ok(LogicalExpr::Alias(Alias {
+            name: alias.name.clone(),
+            expr: coerce(&alias.expr)?,
+        })),
+        LogicalExpr::AggregateFuncExpr(aggr_expr) => {
+            Ok(LogicalExpr::AggregateFuncExpr(AggregateFuncExpr {
+                func: aggr_expr.func.clone(),
+                expr: coerce(&aggr_expr.expr)?,
+                distinct: aggr_expr.distinct,
+                filter: aggr_expr.filter.as_deref().map(coerce).transpose()?,
+            }))
+        }
+        LogicalExpr::InSubquery(in_subquery) => Ok(LogicalExpr::InSubquery(InSubquery {
+            expr: coerce(&in_subquery.expr)?,
+            subquery: in_subquery.subquery.clone(),
+            negated: in_subquery.negated,
+        })),
+        LogicalExpr::Case(case) => Ok(LogicalExpr::Case(Case {
+            expr: case.expr.as_deref().map(coerce).transpose()?,
+            when_then_expr: case
+                .when_then_expr
+                .iter()
+                .map(|(when, then)| Ok((coerce(when)?, coerce(then)?)))
+                .collect::<Result<Vec<_>>>()?,
+            else_expr: case.else_expr.as_deref().map(coerce).transpose()?,
+        })),
+        LogicalExpr::Cast(cast) => Ok(LogicalExpr::Cast(Cast {
+            expr: coerce(&cast.expr)?,
+            data_type: cast.data_type.clone(),
+        })),
+        LogicalExpr::TryCast(try_cast) => Ok(LogicalExpr::TryCast(TryCast {
+            expr: coerce(&try_cast.expr)?,
+            data_type: try_cast.data_type.clone(),
+        })),
+        LogicalExpr::WindowFuncExpr(window_expr) => Ok(LogicalExpr::WindowFuncExpr(
+            coerce_window_expr(window_expr, input)?,
+        )),
+        LogicalExpr::PatternMatch(pattern_match) => Ok(LogicalExpr::PatternMatch(PatternMatch {
+            expr: coerce(&pattern_match.expr)?,
+            pattern: coerce(&pattern_match.pattern)?,
+            ..pattern_match.clone()
+        })),
+        LogicalExpr::UnaryExpr(unary_expr) => {
+            let expr = coerce_expr(&unary_expr.expr, input)?;
+            let expr = match unary_expr.op {
+                // Unsigned values are negated as signed ones
+                UnaryOperator::Negative => {
+                    let data_type = unary_expr.expr.to_field(input)?.data_type().clone();
+                    match data_type {
+                        DataType::UInt64 => cast_if_needed(expr, &data_type, &DataType::Int64),
+                        _ => expr,
+                    }
+                }
+                _ => expr,
+            };
+            Ok(LogicalExpr::UnaryExpr(UnaryExpr {
+                op: unary_expr.op.clone(),
+                expr: Box::new(expr),
+            }))
+        }
+        LogicalExpr::InList(in_list) => {
+            let mut data_type = in_list.expr.to_field(input)?.data_type().clone();
+            for expr in &in_list.list {
+                let item_type = expr.to_field(input)?.data_type().clone();
+                data_type = coerce_types(&data_type, &Operator::Eq, &item_type)?;
+            }
+
+            let coerce_to = |expr: &LogicalExpr| coerce_operand(expr, &data_type, input);
+            Ok(LogicalExpr::InList(InList {
+                expr: Box::new(coerce_to(&in_list.expr)?),
+                list: in_list
+                    .list
+                    .iter()
+                    .map(coerce_to)
+                    .collect::<Result<Vec<_>>>()?,
+                negated: in_list.negated,
+            }))
+        }
+        LogicalExpr::Between(between) => {
+            let mut data_type = between.expr.to_field(input)?.data_type().clone();
+            for bound in [&between.low, &between.high] {
+                let bound_type = bound.to_field(input)?.data_type().clone();
+                data_type = coerce_types(&data_type, &Operator::GtEq, &bound_type)?;
+            }
+
+            let coerce_to =
+                |expr: &LogicalExpr| coerce_operand(expr, &data_type, input).map(Box::new);
+            Ok(LogicalExpr::Between(Between {
+                expr: coerce_to(&between.expr)?,
+                negated: between.negated,
+                low: coerce_to(&between.low)?,
+                high: coerce_to(&between.high)?,
+            }))
+        }
+        _ => Ok(expr.clone()),
+    }
+}
+
+/// Coerces an operand of a comparison and casts it to the type it is compared as
+fn coerce_operand(
+    expr: &LogicalExpr,
+    data_type: &DataType,
+    input: &LogicalPlan,
+) -> Result<LogicalExpr> {
+    let from = expr.to_field(input)?.data_type().clone();
+    if let LogicalExpr::Literal(scalar) = expr {
+        if let Some(scalar) = cast_literal(scalar, data_type) {
+            return Ok(LogicalExpr::Literal(scalar));
+        }
+    }
+    Ok(cast_if_needed(coerce_expr(expr, input)?, &from, data_type))
+}
+
+/// Casts a pair of join keys to the type they are compared as,
+/// each key is evaluated on the output of its side of the join
+pub fn coerce_join_keys(
+    (left, right): &(LogicalExpr, LogicalExpr),
+    left_input: &LogicalPlan,
+    right_input: &LogicalPlan,
+) -> Result<(LogicalExpr, LogicalExpr)> {
+    let left_type = left.to_field(left_input)?.data_type().clone();
+    let right_type = right.to_field(right_input)?.data_type().clone();
+    let data_type = coerce_types(&left_type, &Operator::Eq, &right_type)?;
+    Ok((
+        cast_if_needed(coerce_expr(left, left_input)?, &left_type, &data_type),
+        cast_if_needed(coerce_expr(right, right_input)?, &right_type, &data_type),
+    ))
+}
+
+/// Inserts the casts required to evaluate the arguments, partition and order of a window expr
+pub fn coerce_window_expr(
+    window_expr: &WindowFuncExpr,
+    input: &LogicalPlan,
+) -> Result<WindowFuncExpr> {
+    let coerce = |expr: &LogicalExpr| coerce_expr(expr, input);
+    Ok(WindowFuncExpr {
+        func: window_expr.func.clone(),
+        args: window_expr
+            .args
+            .iter()
+            .map(coerce)
+            .collect::<Result<Vec<_>>>()?,
+        partition_by: window_expr
+            .partition_by
+            .iter()
+            .map(coerce)
+            .collect::<Result<Vec<_>>>()?,
+        order_by: window_expr
+            .order_by
+            .iter()
+            .map(|sort_expr| {
+                Ok(SortExpr {
+                    expr: coerce(&sort_expr.expr)?,
+                    ..sort_expr.clone()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        window_frame: window_expr.window_frame.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::Catalog;
+    use crate::datatype::scalar::Scalar;
+
+    #[test]
+    fn coerce_binary_expr() -> Result<()> {
+        let mut catalog = Catalog::default();
+        catalog.add_csv_table("test", "data/test.csv")?;
+        let plan = catalog.get_table_df("test")?.plan;
+
+        // age + score
+        let expr = LogicalExpr::BinaryExpr(BinaryExpr {
+            left: Box::new(LogicalExpr::Column("age".to_string())),
+            op: Operator::Add,
+            right: Box::new(LogicalExpr::Column("score".to_string())),
+        });
+        assert_eq!(expr.to_field(&plan)?.data_type(), &DataType::Float64);
+        assert_eq!(
+            format!("{:?}", coerce_expr(&expr, &plan)?),
+            "BinaryExpr(BinaryExpr { left: Cast(Cast { expr: Column(\"age\"), data_type: Float64 }), op: Add, right: Column(\"score\") })"
+        );
+
+        assert_eq!(
+            coerce_types(&DataType::UInt64, &Operator::Gt, &DataType::Int64)?,
+            DataType::Int64
+        );
+        assert_eq!(
+            coerce_types(&DataType::Utf8, &Operator::Eq, &DataType::Date32)?,
+            DataType::Date32
+        );
+
+        // name > 1
+        let expr = LogicalExpr::BinaryExpr(BinaryExpr {
+            left: Box::new(LogicalExpr::Column("name".to_string())),
+            op: Operator::Gt,
+            right: Box::new(LogicalExpr::Literal(Scalar::Int64(Some(1)))),
+        });
+        assert!(matches!(
+            coerce_expr(&expr, &plan),
+            Err(Error::LogicalPlanError(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -10,7 +10,8 @@ use crate::{
     physical_plan::expr::{column::ColumnExpr, PhysicalExpr},
 };
 
-use super::{AggrOperator, AggrOperatorRef};
+use super::{unsupported_type, AggrOperator, AggrOperatorRef};
+use std::fmt::{self, Display, Formatter};
 
 pub struct Avg {
     count: i64,
@@ -19,19 +20,19 @@ pub struct Avg {
 }
 
 impl Avg {
-    pub fn new(data_type: DataType, column: ColumnExpr) -> AggrOperatorRef {
+    pub fn new(data_type: DataType, column: ColumnExpr) -> Result<AggrOperatorRef> {
         let scalar_value = match data_type {
             DataType::Int64 => Scalar::Int64(Some(0i64)),
             DataType::UInt64 => Scalar::UInt64(Some(0u64)),
             DataType::Float64 => Scalar::Float64(Some(0f64)),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("AVG", &data_type)),
         };
 
-        Box::new(Self {
+        Ok(Box::new(Self {
             count: 0,
             sum: scalar_value,
             column,
-        })
+        }))
     }
 }
 
@@ -73,13 +74,19 @@ macro_rules! avg {
     };
 }
 
+impl Display for Avg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "AVG({})", self.column)
+    }
+}
+
 impl AggrOperator for Avg {
     fn to_field(&self, schema: &Schema) -> Result<Field> {
         let field = schema.field(self.column.index);
         Ok(Field::new(
             format!("AVG({})", field.name()).as_str(),
             DataType::Float64,
-            false,
+            true,
         ))
     }
 
@@ -90,7 +97,7 @@ impl AggrOperator for Avg {
             DataType::Int64 => update_batch!(column, Int64Type, Int64, self),
             DataType::UInt64 => update_batch!(column, UInt64Type, UInt64, self),
             DataType::Float64 => update_batch!(column, Float64Type, Float64, self),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("AVG", data_type)),
         }
 
         Ok(())
@@ -103,29 +110,34 @@ impl AggrOperator for Avg {
             DataType::Int64 => update!(column, Int64Type, Int64, self, i),
             DataType::UInt64 => update!(column, UInt64Type, UInt64, self, i),
             DataType::Float64 => update!(column, Float64Type, Float64, self, i),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("AVG", data_type)),
         }
 
         Ok(())
     }
 
     fn evaluate(&self) -> Result<Scalar> {
+        // The average of no values is NULL
+        if self.count == 0 {
+            return Ok(Scalar::Float64(None));
+        }
         let avg = match self.sum {
             Scalar::Int64(Some(sum)) => avg!(self.count, sum),
             Scalar::UInt64(Some(sum)) => avg!(self.count, sum),
             Scalar::Float64(Some(sum)) => avg!(self.count, sum),
-            _ => unimplemented!(),
+            ref scalar => return Err(unsupported_type("AVG", scalar.to_field().data_type())),
         };
 
         Ok(Scalar::Float64(Some(avg)))
     }
 
     fn clear(&mut self) -> Result<()> {
+        self.count = 0;
         match self.sum {
             Scalar::Int64(_) => self.sum = Scalar::Int64(Some(0i64)),
             Scalar::UInt64(_) => self.sum = Scalar::UInt64(Some(0u64)),
             Scalar::Float64(_) => self.sum = Scalar::Float64(Some(0f64)),
-            _ => unimplemented!(),
+            ref scalar => return Err(unsupported_type("AVG", scalar.to_field().data_type())),
         }
 
         Ok(())
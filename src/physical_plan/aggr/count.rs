@@ -6,38 +6,73 @@ use crate::physical_plan::expr::column::ColumnExpr;
 use crate::physical_plan::expr::PhysicalExpr;
 use arrow::datatypes::DataType;
 use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
 
 pub struct Count {
     count: usize,
-    column: ColumnExpr,
+    /// The column whose non-NULL values are counted, all rows are counted without one
+    column: Option<ColumnExpr>,
 }
 
 impl Count {
     pub fn new(column: ColumnExpr) -> AggrOperatorRef {
-        Box::new(Self { count: 0, column })
+        Box::new(Self {
+            count: 0,
+            column: Some(column),
+        })
+    }
+
+    /// Creates the operator of `COUNT(*)`, which counts the rows
+    pub fn rows() -> AggrOperatorRef {
+        Box::new(Self {
+            count: 0,
+            column: None,
+        })
+    }
+}
+
+impl Display for Count {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(f, "COUNT({})", column),
+            None => write!(f, "COUNT(*)"),
+        }
     }
 }
 
 impl AggrOperator for Count {
     fn to_field(&self, schema: &Schema) -> Result<Field> {
-        let field = schema.field(self.column.index);
+        let name = match &self.column {
+            Some(column) => schema.field(column.index).name().clone(),
+            None => "*".to_string(),
+        };
         Ok(Field::new(
-            format!("COUNT({})", field.name()).as_str(),
+            format!("COUNT({})", name).as_str(),
             DataType::UInt64,
             false,
         ))
     }
 
     fn update_batch(&mut self, record_batch: &RecordBatch) -> Result<()> {
-        let column = self.column.evaluate(record_batch)?.to_array();
-        self.count += column.len() - column.null_count();
+        match &self.column {
+            Some(column) => {
+                let column = column.evaluate(record_batch)?.to_array();
+                self.count += column.len() - column.null_count();
+            }
+            None => self.count += record_batch.num_rows(),
+        }
         Ok(())
     }
 
     fn update(&mut self, record_batch: &RecordBatch, i: usize) -> Result<()> {
-        let column = self.column.evaluate(record_batch)?.to_array();
-        if !column.is_null(i) {
-            self.count += 1;
+        match &self.column {
+            Some(column) => {
+                let column = column.evaluate(record_batch)?.to_array();
+                if !column.is_null(i) {
+                    self.count += 1;
+                }
+            }
+            None => self.count += 1,
         }
         Ok(())
     }
@@ -0,0 +1,95 @@
+use super::{AggrOperator, AggrOperatorRef};
+use crate::datatype::scalar::Scalar;
+use crate::datatype::{field::Field, schema::Schema};
+use crate::error::Result;
+use crate::logical_plan::logical_expr::AggregateFunc;
+use crate::physical_plan::expr::column::ColumnExpr;
+use crate::physical_plan::expr::PhysicalExpr;
+use arrow::array::{Array, ArrayRef};
+use arrow::record_batch::RecordBatch;
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+
+/// `func(DISTINCT column)`, passes each distinct value of a group to the inner operator once
+pub struct Distinct {
+    func: AggregateFunc,
+    column: ColumnExpr,
+    /// The values of the current group, cleared together with the inner operator
+    seen: HashSet<Scalar>,
+    inner: AggrOperatorRef,
+}
+
+impl Distinct {
+    pub fn new(func: AggregateFunc, column: ColumnExpr, inner: AggrOperatorRef) -> AggrOperatorRef {
+        Box::new(Self {
+            func,
+            column,
+            seen: HashSet::new(),
+            inner,
+        })
+    }
+
+    fn update_row(
+        &mut self,
+        record_batch: &RecordBatch,
+        column: &ArrayRef,
+        i: usize,
+    ) -> Result<()> {
+        // NULLs are ignored by all aggregates, so they do not need to be remembered
+        if column.is_null(i) {
+            return Ok(());
+        }
+        if self.seen.insert(Scalar::try_from_array(column, i)?) {
+            self.inner.update(record_batch, i)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Distinct {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}(DISTINCT {})", self.func, self.column)
+    }
+}
+
+impl AggrOperator for Distinct {
+    fn to_field(&self, schema: &Schema) -> Result<Field> {
+        let field = self.inner.to_field(schema)?;
+        let name = format!(
+            "{:?}(DISTINCT {})",
+            self.func,
+            schema.field(self.column.index).name()
+        );
+        Ok(Field::new(
+            name.as_str(),
+            field.data_type().clone(),
+            field.is_nullable(),
+        ))
+    }
+
+    fn update_batch(&mut self, record_batch: &RecordBatch) -> Result<()> {
+        let column = self.column.evaluate(record_batch)?.to_array();
+        for i in 0..record_batch.num_rows() {
+            self.update_row(record_batch, &column, i)?;
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, record_batch: &RecordBatch, i: usize) -> Result<()> {
+        let column = self.column.evaluate(record_batch)?.to_array();
+        self.update_row(record_batch, &column, i)
+    }
+
+    fn evaluate(&self) -> Result<Scalar> {
+        self.inner.evaluate()
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.seen.clear();
+        self.inner.clear()
+    }
+
+    fn set_grouping_set(&mut self, grouping_set: &[usize]) {
+        self.inner.set_grouping_set(grouping_set);
+    }
+}
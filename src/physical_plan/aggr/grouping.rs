@@ -0,0 +1,62 @@
+use super::{AggrOperator, AggrOperatorRef};
+use crate::datatype::scalar::Scalar;
+use crate::datatype::{field::Field, schema::Schema};
+use crate::error::Result;
+use crate::physical_plan::expr::PhysicalExprRef;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
+
+/// `GROUPING(expr)`, 1 if the group expr is rolled up in the grouping set of the group, else 0
+pub struct Grouping {
+    /// The index of the group expr in the group exprs of the aggregation
+    group_index: usize,
+    group_expr: PhysicalExprRef,
+    rolled_up: bool,
+}
+
+impl Grouping {
+    pub fn new(group_index: usize, group_expr: PhysicalExprRef) -> AggrOperatorRef {
+        Box::new(Self {
+            group_index,
+            group_expr,
+            rolled_up: false,
+        })
+    }
+}
+
+impl Display for Grouping {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "GROUPING({})", self.group_expr)
+    }
+}
+
+impl AggrOperator for Grouping {
+    fn to_field(&self, _schema: &Schema) -> Result<Field> {
+        Ok(Field::new(
+            self.to_string().as_str(),
+            DataType::UInt64,
+            false,
+        ))
+    }
+
+    fn update_batch(&mut self, _record_batch: &RecordBatch) -> Result<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, _record_batch: &RecordBatch, _i: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Scalar> {
+        Ok(Scalar::UInt64(Some(self.rolled_up as u64)))
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_grouping_set(&mut self, grouping_set: &[usize]) {
+        self.rolled_up = !grouping_set.contains(&self.group_index);
+    }
+}
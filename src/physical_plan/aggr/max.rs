@@ -2,14 +2,16 @@ use crate::datatype::field::Field;
 use crate::datatype::scalar::Scalar;
 use crate::datatype::schema::Schema;
 use crate::error::Result;
-use crate::physical_plan::aggr::{AggrOperator, AggrOperatorRef};
+use crate::physical_plan::aggr::{unsupported_type, AggrOperator, AggrOperatorRef};
 use crate::physical_plan::expr::column::ColumnExpr;
 use crate::physical_plan::expr::PhysicalExpr;
 use arrow::array::{Array, PrimitiveArray};
 use arrow::datatypes::{DataType, Float64Type, Int64Type, UInt64Type};
 use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
 
 pub struct Max {
+    /// The maximum of the values, NULL until a value is aggregated
     max: Scalar,
     column: ColumnExpr,
 }
@@ -21,8 +23,8 @@ macro_rules! update_batch {
             .downcast_ref::<PrimitiveArray<$DT>>()
             .unwrap();
         for val in column.into_iter().flatten() {
-            if let Scalar::$SCALARTYPE(Some(cur_max)) = $SELF.max {
-                if val > cur_max {
+            if let Scalar::$SCALARTYPE(cur_max) = $SELF.max {
+                if cur_max.map_or(true, |cur_max| val > cur_max) {
                     $SELF.max = Scalar::$SCALARTYPE(Some(val))
                 }
             }
@@ -37,9 +39,9 @@ macro_rules! update {
             .downcast_ref::<PrimitiveArray<$DT>>()
             .unwrap();
         if !column.is_null($IDX) {
-            if let Scalar::$SCALARTYPE(Some(cur_max)) = $SELF.max {
+            if let Scalar::$SCALARTYPE(cur_max) = $SELF.max {
                 let val = column.value($IDX);
-                if val > cur_max {
+                if cur_max.map_or(true, |cur_max| val > cur_max) {
                     $SELF.max = Scalar::$SCALARTYPE(Some(val))
                 }
             }
@@ -48,17 +50,23 @@ macro_rules! update {
 }
 
 impl Max {
-    pub fn new(data_type: DataType, column: ColumnExpr) -> AggrOperatorRef {
+    pub fn new(data_type: DataType, column: ColumnExpr) -> Result<AggrOperatorRef> {
         let scalar_value = match data_type {
-            DataType::Int64 => Scalar::Int64(Some(i64::MIN)),
-            DataType::UInt64 => Scalar::UInt64(Some(u64::MIN)),
-            DataType::Float64 => Scalar::Float64(Some(f64::MIN)),
-            _ => unimplemented!(),
+            DataType::Int64 => Scalar::Int64(None),
+            DataType::UInt64 => Scalar::UInt64(None),
+            DataType::Float64 => Scalar::Float64(None),
+            data_type => return Err(unsupported_type("MAX", &data_type)),
         };
-        Box::new(Self {
+        Ok(Box::new(Self {
             max: scalar_value,
             column,
-        })
+        }))
+    }
+}
+
+impl Display for Max {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "MAX({})", self.column)
     }
 }
 
@@ -68,7 +76,7 @@ impl AggrOperator for Max {
         Ok(Field::new(
             format!("MAX({})", field.name()).as_str(),
             field.data_type().clone(),
-            false,
+            true,
         ))
     }
 
@@ -79,7 +87,7 @@ impl AggrOperator for Max {
             DataType::Int64 => update_batch!(column, Int64Type, Int64, self),
             DataType::UInt64 => update_batch!(column, UInt64Type, UInt64, self),
             DataType::Float64 => update_batch!(column, Float64Type, Float64, self),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("MAX", data_type)),
         }
 
         Ok(())
@@ -92,7 +100,7 @@ impl AggrOperator for Max {
             DataType::Int64 => update!(column, Int64Type, Int64, self, i),
             DataType::UInt64 => update!(column, UInt64Type, UInt64, self, i),
             DataType::Float64 => update!(column, Float64Type, Float64, self, i),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("MAX", data_type)),
         }
 
         Ok(())
@@ -104,10 +112,10 @@ impl AggrOperator for Max {
 
     fn clear(&mut self) -> Result<()> {
         match self.max {
-            Scalar::Int64(_) => self.max = Scalar::Int64(Some(i64::MIN)),
-            Scalar::UInt64(_) => self.max = Scalar::UInt64(Some(u64::MIN)),
-            Scalar::Float64(_) => self.max = Scalar::Float64(Some(f64::MIN)),
-            _ => unimplemented!(),
+            Scalar::Int64(_) => self.max = Scalar::Int64(None),
+            Scalar::UInt64(_) => self.max = Scalar::UInt64(None),
+            Scalar::Float64(_) => self.max = Scalar::Float64(None),
+            ref scalar => return Err(unsupported_type("MAX", scalar.to_field().data_type())),
         }
         Ok(())
     }
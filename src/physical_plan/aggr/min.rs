@@ -1,4 +1,4 @@
-use super::{AggrOperator, AggrOperatorRef};
+use super::{unsupported_type, AggrOperator, AggrOperatorRef};
 use crate::{
     datatype::{field::Field, scalar::Scalar, schema::Schema},
     error::Result,
@@ -9,24 +9,26 @@ use arrow::{
     datatypes::{DataType, Float64Type, Int64Type, UInt64Type},
     record_batch::RecordBatch,
 };
+use std::fmt::{self, Display, Formatter};
 
 pub struct Min {
+    /// The minimum of the values, NULL until a value is aggregated
     min: Scalar,
     column: ColumnExpr,
 }
 
 impl Min {
-    pub fn new(data_type: DataType, column: ColumnExpr) -> AggrOperatorRef {
+    pub fn new(data_type: DataType, column: ColumnExpr) -> Result<AggrOperatorRef> {
         let scalar_value = match data_type {
-            DataType::Int64 => Scalar::Int64(Some(i64::MAX)),
-            DataType::UInt64 => Scalar::UInt64(Some(u64::MAX)),
-            DataType::Float64 => Scalar::Float64(Some(f64::MAX)),
-            _ => unimplemented!(),
+            DataType::Int64 => Scalar::Int64(None),
+            DataType::UInt64 => Scalar::UInt64(None),
+            DataType::Float64 => Scalar::Float64(None),
+            data_type => return Err(unsupported_type("MIN", &data_type)),
         };
-        Box::new(Self {
+        Ok(Box::new(Self {
             min: scalar_value,
             column,
-        })
+        }))
     }
 }
 
@@ -37,8 +39,8 @@ macro_rules! update_batch {
             .downcast_ref::<PrimitiveArray<$DT>>()
             .unwrap();
         for val in column.into_iter().flatten() {
-            if let Scalar::$SCALARTYPE(Some(cur_min)) = $SELF.min {
-                if val < cur_min {
+            if let Scalar::$SCALARTYPE(cur_min) = $SELF.min {
+                if cur_min.map_or(true, |cur_min| val < cur_min) {
                     $SELF.min = Scalar::$SCALARTYPE(Some(val))
                 }
             }
@@ -53,9 +55,9 @@ macro_rules! update {
             .downcast_ref::<PrimitiveArray<$DT>>()
             .unwrap();
         if !column.is_null($IDX) {
-            if let Scalar::$SCALARTYPE(Some(cur_min)) = $SELF.min {
+            if let Scalar::$SCALARTYPE(cur_min) = $SELF.min {
                 let val = column.value($IDX);
-                if val < cur_min {
+                if cur_min.map_or(true, |cur_min| val < cur_min) {
                     $SELF.min = Scalar::$SCALARTYPE(Some(val))
                 }
             }
@@ -63,13 +65,19 @@ macro_rules! update {
     }};
 }
 
+impl Display for Min {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "MIN({})", self.column)
+    }
+}
+
 impl AggrOperator for Min {
     fn to_field(&self, schema: &Schema) -> Result<Field> {
         let field = schema.field(self.column.index);
         Ok(Field::new(
             format!("MIN({})", field.name()).as_str(),
             field.data_type().clone(),
-            false,
+            true,
         ))
     }
 
@@ -80,7 +88,7 @@ impl AggrOperator for Min {
             DataType::Int64 => update_batch!(column, Int64Type, Int64, self),
             DataType::UInt64 => update_batch!(column, UInt64Type, UInt64, self),
             DataType::Float64 => update_batch!(column, Float64Type, Float64, self),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("MIN", data_type)),
         }
 
         Ok(())
@@ -93,7 +101,7 @@ impl AggrOperator for Min {
             DataType::Int64 => update!(column, Int64Type, Int64, self, i),
             DataType::UInt64 => update!(column, UInt64Type, UInt64, self, i),
             DataType::Float64 => update!(column, Float64Type, Float64, self, i),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("MIN", data_type)),
         }
 
         Ok(())
@@ -105,10 +113,10 @@ impl AggrOperator for Min {
 
     fn clear(&mut self) -> Result<()> {
         match self.min {
-            Scalar::Int64(_) => self.min = Scalar::Int64(Some(i64::MAX)),
-            Scalar::UInt64(_) => self.min = Scalar::UInt64(Some(u64::MAX)),
-            Scalar::Float64(_) => self.min = Scalar::Float64(Some(f64::MAX)),
-            _ => unimplemented!(),
+            Scalar::Int64(_) => self.min = Scalar::Int64(None),
+            Scalar::UInt64(_) => self.min = Scalar::UInt64(None),
+            Scalar::Float64(_) => self.min = Scalar::Float64(None),
+            ref scalar => return Err(unsupported_type("MIN", scalar.to_field().data_type())),
         }
 
         Ok(())
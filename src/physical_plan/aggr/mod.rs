@@ -1,5 +1,6 @@
-use arrow::array::{BooleanArray, PrimitiveArray, StringArray};
-use arrow::datatypes::{DataType, Int64Type, UInt64Type};
+use arrow::array::{new_null_array, Array, ArrayRef, BooleanArray};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::DataType;
 use arrow::record_batch::RecordBatch;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -7,19 +8,25 @@ use std::vec;
 
 use crate::datatype::scalar::Scalar;
 use crate::datatype::{field::Field, schema::Schema};
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::logical_plan::logical_expr::AggregateFunc;
 use crate::util::concat_batches;
 
-use super::expr::PhysicalExprRef;
+use super::expr::column::ColumnExpr;
+use super::expr::{format_exprs, PhysicalExprRef};
+use super::metrics::{batch_memory_size, ExecutionMetrics, MetricsSnapshot};
 use super::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use std::fmt::{self, Display, Formatter};
 
 pub mod avg;
 pub mod count;
+pub mod distinct;
+pub mod grouping;
 pub mod max;
 pub mod min;
 pub mod sum;
 
-pub trait AggrOperator {
+pub trait AggrOperator: Display {
     fn to_field(&self, schema: &Schema) -> Result<Field>;
 
     fn update_batch(&mut self, record_batch: &RecordBatch) -> Result<()>;
@@ -29,105 +36,199 @@ pub trait AggrOperator {
     fn evaluate(&self) -> Result<Scalar>;
 
     fn clear(&mut self) -> Result<()>;
+
+    /// Tells the operator the grouping set, as indices into the group exprs,
+    /// of the group it aggregates next
+    fn set_grouping_set(&mut self, _grouping_set: &[usize]) {}
 }
 
 pub type AggrOperatorRef = Box<dyn AggrOperator>;
 
+/// Creates the operator computing `func` of `column`, whose values are of type `data_type`.
+/// A `distinct` operator aggregates each distinct value of a group only once.
+pub fn create_aggr_operator(
+    func: &AggregateFunc,
+    distinct: bool,
+    data_type: DataType,
+    column: ColumnExpr,
+) -> Result<AggrOperatorRef> {
+    let aggr_op = match func {
+        AggregateFunc::SUM => sum::Sum::new(data_type, column.clone())?,
+        AggregateFunc::MIN => min::Min::new(data_type, column.clone())?,
+        AggregateFunc::MAX => max::Max::new(data_type, column.clone())?,
+        AggregateFunc::AVG => avg::Avg::new(data_type, column.clone())?,
+        AggregateFunc::COUNT => count::Count::new(column.clone()),
+        AggregateFunc::GROUPING => {
+            return Err(Error::PhysicalPlanError(
+                "GROUPING can only be used in an aggregation with GROUP BY".to_string(),
+            ))
+        }
+    };
+
+    if distinct {
+        Ok(distinct::Distinct::new(func.clone(), column, aggr_op))
+    } else {
+        Ok(aggr_op)
+    }
+}
+
+/// The error of an aggregate function applied to a type it does not support
+fn unsupported_type(func: &str, data_type: &DataType) -> Error {
+    Error::NotImplemented(format!("{} of type {:?}", func, data_type))
+}
+
 pub struct Aggregation {
     input: PhysicalPlanRef,
-    group_expr: Option<PhysicalExprRef>,
+    group_expr: Vec<PhysicalExprRef>,
+    /// The sets of group exprs the rows are grouped by, as indices into `group_expr`
+    grouping_sets: Vec<Vec<usize>>,
     aggr_expr: Mutex<Vec<AggrOperatorRef>>,
+    /// The FILTER of each aggregate, only the rows it is true for are aggregated
+    aggr_filter: Vec<Option<PhysicalExprRef>>,
+    schema: Schema,
+    metrics: ExecutionMetrics,
 }
 
 impl Aggregation {
     pub fn new(
         input: PhysicalPlanRef,
-        group_expr: Option<PhysicalExprRef>,
+        group_expr: Vec<PhysicalExprRef>,
+        aggr_expr: Vec<AggrOperatorRef>,
+        schema: Schema,
+    ) -> PhysicalPlanRef {
+        let grouping_sets = vec![(0..group_expr.len()).collect()];
+        let aggr_filter = vec![None; aggr_expr.len()];
+        Self::new_with_grouping_sets(
+            input,
+            group_expr,
+            grouping_sets,
+            aggr_expr,
+            aggr_filter,
+            schema,
+        )
+    }
+
+    /// Creates an aggregation that groups the rows by each of the grouping sets in a single
+    /// pass over the input, the group columns that are not in the set of a group are NULL.
+    /// Each aggregate may have a filter, which is evaluated on the input.
+    pub fn new_with_grouping_sets(
+        input: PhysicalPlanRef,
+        group_expr: Vec<PhysicalExprRef>,
+        grouping_sets: Vec<Vec<usize>>,
         aggr_expr: Vec<AggrOperatorRef>,
+        aggr_filter: Vec<Option<PhysicalExprRef>>,
+        schema: Schema,
     ) -> PhysicalPlanRef {
         Arc::new(Self {
             input,
             group_expr,
+            grouping_sets,
             aggr_expr: Mutex::new(aggr_expr),
+            aggr_filter,
+            schema,
+            metrics: ExecutionMetrics::default(),
         })
     }
 }
 
-macro_rules! group_by_datatype {
-    ($COLUMN: expr, $GROUP_BY_DT: ty, $AGGR_OPS: expr, $BATCH: expr, $SCHEMA: expr, $SCALAR_TYPE: ident) => {{
-        let mut group_idxs = HashMap::<$GROUP_BY_DT, Vec<usize>>::new();
-
-        for (idx, val) in $COLUMN.iter().enumerate() {
-            if let Some(val) = val {
-                if let Some(idxs) = group_idxs.get_mut(&val) {
-                    idxs.push(idx);
-                } else {
-                    group_idxs.insert(val, vec![idx]);
-                }
-            }
+impl Display for Aggregation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let aggr_ops = self.aggr_expr.lock().map_err(|_| fmt::Error)?;
+        let aggr_expr = aggr_ops
+            .iter()
+            .zip(&self.aggr_filter)
+            .map(|(aggr_op, filter)| match filter {
+                Some(filter) => format!("{} FILTER (WHERE {})", aggr_op, filter),
+                None => aggr_op.to_string(),
+            })
+            .collect::<Vec<_>>();
+        if self.has_grouping_sets() {
+            let grouping_sets = self
+                .grouping_sets
+                .iter()
+                .map(|set| {
+                    let exprs = set
+                        .iter()
+                        .map(|i| self.group_expr[*i].clone())
+                        .collect::<Vec<_>>();
+                    format!("({})", format_exprs(&exprs))
+                })
+                .collect::<Vec<_>>();
+            write!(
+                f,
+                "Aggregation: grouping_sets=[{}], aggr_expr=[{}]",
+                grouping_sets.join(", "),
+                aggr_expr.join(", ")
+            )
+        } else {
+            write!(
+                f,
+                "Aggregation: group_expr=[{}], aggr_expr=[{}]",
+                format_exprs(&self.group_expr),
+                aggr_expr.join(", ")
+            )
         }
+    }
+}
 
-        let mut batches = vec![];
-
-        for (val, group_idx) in group_idxs.into_iter() {
-            for idx in group_idx.iter() {
-                for aggr_op in $AGGR_OPS.iter_mut() {
-                    aggr_op.update(&$BATCH, *idx)?;
-                }
-            }
-
-            let mut arrays = vec![];
-
-            arrays.push(Scalar::$SCALAR_TYPE(Some(val)).to_array(1));
-
-            for aggr_op in $AGGR_OPS.iter() {
-                let array = aggr_op.evaluate()?.to_array(1);
-                arrays.push(array);
-            }
+impl PhysicalPlan for Aggregation {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
 
-            for aggr_op in $AGGR_OPS.iter_mut() {
-                aggr_op.clear()?;
-            }
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let input = self.input.execute()?;
+        let batch = self.metrics.record(|| self.aggregate(&input))?;
 
-            let batch = RecordBatch::try_new($SCHEMA, arrays)?;
-            batches.push(batch);
-        }
+        Ok(vec![batch])
+    }
 
-        let batch = concat_batches(&$SCHEMA, batches.as_slice())?;
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.input.clone()])
+    }
 
-        Ok(vec![batch])
-    }};
+    fn metrics(&self) -> Option<MetricsSnapshot> {
+        Some(self.metrics.snapshot())
+    }
 }
 
-impl PhysicalPlan for Aggregation {
-    fn schema(&self) -> &Schema {
-        &self.input.schema()
+impl Aggregation {
+    fn has_grouping_sets(&self) -> bool {
+        self.grouping_sets.len() != 1
+            || !self.grouping_sets[0]
+                .iter()
+                .copied()
+                .eq(0..self.group_expr.len())
     }
 
-    fn execute(&self) -> Result<Vec<RecordBatch>> {
-        let mut fields = vec![];
-
-        let batches = self.input.execute()?;
-        let batch = concat_batches(&Arc::new(self.schema().clone().into()), batches.as_slice())?;
+    /// Aggregates the batches of the input, which are held in memory together
+    fn aggregate(&self, input: &[RecordBatch]) -> Result<RecordBatch> {
+        let schema = Arc::new(self.schema.clone().into());
 
-        if let Some(group_expr) = &self.group_expr {
-            fields.push(group_expr.to_field(&batch)?);
-        }
+        let batch = concat_batches(&Arc::new(self.input.schema().clone().into()), input)?;
+        self.metrics.record_memory(batch_memory_size(&batch));
 
-        // Generates Schema based on aggregation operations
+        // Since `self.aggr_expr` is wrapped in a Mutex,
+        // we can obtain a mutable reference of `self.aggr_expr` after locking it
         let mut aggr_ops = self.aggr_expr.lock().unwrap();
-        for aggr_op in aggr_ops.iter() {
-            fields.push(aggr_op.to_field(self.schema())?)
-        }
-
-        let schema = Schema::new(fields);
 
-        if self.group_expr.is_none() {
-            // Since `self.aggr_expr` is wrapped in a Mutex,
-            // we can obtain a mutable reference of `self.aggr_expr` after locking it
-            for batch in &batches {
-                for aggr_op in aggr_ops.iter_mut() {
-                    aggr_op.update_batch(batch)?;
+        let masks = self
+            .aggr_filter
+            .iter()
+            .map(|filter| {
+                filter
+                    .as_ref()
+                    .map(|filter| filter_mask(filter, &batch))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if self.group_expr.is_empty() && self.grouping_sets.len() == 1 {
+            for (aggr_op, mask) in aggr_ops.iter_mut().zip(&masks) {
+                aggr_op.clear()?;
+                match mask {
+                    Some(mask) => aggr_op.update_batch(&filter_record_batch(&batch, mask)?)?,
+                    None => aggr_op.update_batch(&batch)?,
                 }
             }
 
@@ -139,114 +240,119 @@ impl PhysicalPlan for Aggregation {
                 arrays.push(aggr_op.evaluate()?.to_array(1));
             }
 
-            let record_batch = RecordBatch::try_new(Arc::new(schema.clone().into()), arrays)?;
+            let record_batch = RecordBatch::try_new(schema, arrays)?;
 
-            Ok(vec![record_batch])
-        } else {
-            if let Some(group_expr) = &self.group_expr {
-                let column = group_expr.evaluate(&batch)?.to_array();
-
-                match column.data_type() {
-                    DataType::Int64 => {
-                        let column = column
-                            .as_any()
-                            .downcast_ref::<PrimitiveArray<Int64Type>>()
-                            .unwrap();
-                        return group_by_datatype!(
-                            column,
-                            i64,
-                            aggr_ops,
-                            batch,
-                            Arc::new(schema.clone().into()),
-                            Int64
-                        );
-                    }
-                    DataType::UInt64 => {
-                        let column = column
-                            .as_any()
-                            .downcast_ref::<PrimitiveArray<UInt64Type>>()
-                            .unwrap();
-                        return group_by_datatype!(
-                            column,
-                            u64,
-                            aggr_ops,
-                            batch,
-                            Arc::new(schema.clone().into()),
-                            UInt64
-                        );
-                    }
-                    DataType::Boolean => {
-                        let column = column.as_any().downcast_ref::<BooleanArray>().unwrap();
-                        return group_by_datatype!(
-                            column,
-                            bool,
-                            aggr_ops,
-                            batch,
-                            Arc::new(schema.clone().into()),
-                            Boolean
-                        );
+            return Ok(record_batch);
+        }
+
+        let group_columns = self
+            .group_expr
+            .iter()
+            .map(|expr| Ok(expr.evaluate(&batch)?.to_array()))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+
+        // Groups are kept in the order in which their keys first appear,
+        // so the output order does not depend on the iteration order of the hash map.
+        // Every row belongs to one group of each grouping set, so the key of a group
+        // is the index of its set together with the values of the group exprs in the set.
+        let mut group_keys = Vec::<(usize, Vec<Scalar>)>::new();
+        let mut group_idxs = Vec::<Vec<usize>>::new();
+        let mut key_to_group = HashMap::<(usize, Vec<Scalar>), usize>::new();
+
+        for idx in 0..batch.num_rows() {
+            let row = group_columns
+                .iter()
+                .map(|column| Scalar::try_from_array(column, idx))
+                .collect::<Result<Vec<_>>>()?;
+
+            for (set_idx, grouping_set) in self.grouping_sets.iter().enumerate() {
+                let key = (
+                    set_idx,
+                    grouping_set.iter().map(|i| row[*i].clone()).collect(),
+                );
+
+                match key_to_group.get(&key) {
+                    Some(group) => group_idxs[*group].push(idx),
+                    None => {
+                        key_to_group.insert(key.clone(), group_keys.len());
+                        group_keys.push(key);
+                        group_idxs.push(vec![idx]);
                     }
-                    DataType::Utf8 => {
-                        let mut group_idxs = HashMap::<&str, Vec<usize>>::new();
-                        let column = column.as_any().downcast_ref::<StringArray>().unwrap();
-                        for (idx, val) in column.iter().enumerate() {
-                            if let Some(val) = val {
-                                if let Some(idxs) = group_idxs.get_mut(&val) {
-                                    idxs.push(idx);
-                                } else {
-                                    group_idxs.insert(val, vec![idx]);
-                                }
-                            }
-                        }
-
-                        let mut batches = vec![];
-
-                        for (val, group_idx) in group_idxs.into_iter() {
-                            for idx in group_idx.iter() {
-                                for aggr_op in aggr_ops.iter_mut() {
-                                    aggr_op.update(&batch, *idx)?;
-                                }
-                            }
-
-                            let mut arrays = vec![];
-
-                            arrays.push(Scalar::Utf8(Some(val.to_string())).to_array(1));
-
-                            for aggr_op in aggr_ops.iter() {
-                                let array = aggr_op.evaluate()?.to_array(1);
-                                arrays.push(array);
-                            }
-
-                            for aggr_op in aggr_ops.iter_mut() {
-                                aggr_op.clear()?;
-                            }
-
-                            let batch =
-                                RecordBatch::try_new(Arc::new(schema.clone().into()), arrays)?;
-                            batches.push(batch);
-                        }
-
-                        let batch =
-                            concat_batches(&Arc::new(schema.clone().into()), batches.as_slice())?;
-
-                        return Ok(vec![batch]);
+                }
+            }
+        }
+
+        // An empty grouping set aggregates all rows, so it has a group even without rows
+        for (set_idx, grouping_set) in self.grouping_sets.iter().enumerate() {
+            if grouping_set.is_empty() && !key_to_group.contains_key(&(set_idx, vec![])) {
+                group_keys.push((set_idx, vec![]));
+                group_idxs.push(vec![]);
+            }
+        }
+
+        let mut groups = group_keys.into_iter().zip(group_idxs).collect::<Vec<_>>();
+        groups.sort_by_key(|((set_idx, _), _)| *set_idx);
+
+        let mut batches = vec![];
+
+        for ((set_idx, key), group_idx) in groups {
+            let grouping_set = &self.grouping_sets[set_idx];
+            for aggr_op in aggr_ops.iter_mut() {
+                aggr_op.clear()?;
+                aggr_op.set_grouping_set(grouping_set);
+            }
+
+            for idx in group_idx.iter() {
+                for (aggr_op, mask) in aggr_ops.iter_mut().zip(&masks) {
+                    let selected = match mask {
+                        Some(mask) => mask.is_valid(*idx) && mask.value(*idx),
+                        None => true,
+                    };
+                    if selected {
+                        aggr_op.update(&batch, *idx)?;
                     }
-                    _ => unimplemented!(),
                 }
             }
 
-            Ok(vec![])
+            let mut arrays = (0..self.group_expr.len())
+                .map(|i| match grouping_set.iter().position(|j| *j == i) {
+                    Some(pos) => key[pos].clone().to_array(1),
+                    None => new_null_array(self.schema.field(i).data_type(), 1),
+                })
+                .collect::<Vec<_>>();
+
+            for aggr_op in aggr_ops.iter() {
+                arrays.push(aggr_op.evaluate()?.to_array(1));
+            }
+
+            batches.push(RecordBatch::try_new(schema.clone(), arrays)?);
         }
+
+        concat_batches(&schema, batches.as_slice())
     }
+}
 
-    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
-        Ok(vec![self.input.clone()])
+/// Evaluates the filter of an aggregate, a NULL filter selects no rows
+fn filter_mask(filter: &PhysicalExprRef, batch: &RecordBatch) -> Result<BooleanArray> {
+    let mask = filter.evaluate(batch)?.to_array();
+    if mask.data_type() == &DataType::Null {
+        return Ok(BooleanArray::from(vec![None; mask.len()]));
+    }
+    if mask.data_type() != &DataType::Boolean {
+        return Err(Error::PhysicalPlanError(format!(
+            "The filter {} is {:?}, but an aggregate filter needs a Boolean",
+            filter,
+            mask.data_type()
+        )));
     }
+    Ok(BooleanArray::from(mask.data().clone()))
 }
 
 #[cfg(test)]
 mod tests {
 
+    use arrow::array::UInt64Array;
+    use arrow::datatypes::DataType;
     use arrow::util::pretty;
 
     use crate::{
@@ -276,22 +382,38 @@ mod tests {
         let column = ColumnExpr::new(3);
         let column = column.as_any().downcast_ref::<ColumnExpr>().unwrap();
 
-        let max = Max::new(DataType::Float64, column.clone());
-        let min = Min::new(DataType::Float64, column.clone());
+        let max = Max::new(DataType::Float64, column.clone())?;
+        let min = Min::new(DataType::Float64, column.clone())?;
         let count = Count::new(column.clone());
-        let avg = Avg::new(DataType::Float64, column.clone());
-        let sum = Sum::new(DataType::Float64, column.clone());
+        let avg = Avg::new(DataType::Float64, column.clone())?;
+        let sum = Sum::new(DataType::Float64, column.clone())?;
+
+        let schema = Schema::new(vec![
+            Field::new("age <= 24", DataType::Boolean, true),
+            Field::new("MAX(score)", DataType::Float64, true),
+            Field::new("MIN(score)", DataType::Float64, true),
+            Field::new("COUNT(score)", DataType::UInt64, true),
+            Field::new("AVG(score)", DataType::Float64, true),
+            Field::new("SUM(score)", DataType::Float64, true),
+        ]);
 
         let aggregation = Aggregation::new(
             scan,
-            Some(group_expr),
+            vec![group_expr],
             vec![max, min, count, avg, sum],
+            schema,
         );
 
         let batch = aggregation.execute()?;
 
         pretty::print_batches(&batch)?;
 
+        assert_eq!(batch.len(), 1);
+        assert_eq!(
+            batch[0].column(3),
+            &(Arc::new(UInt64Array::from(vec![4, 1])) as ArrayRef)
+        );
+
         Ok(())
     }
 }
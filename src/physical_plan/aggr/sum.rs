@@ -2,31 +2,33 @@ use crate::datatype::field::Field;
 use crate::datatype::scalar::Scalar;
 use crate::datatype::schema::Schema;
 use crate::error::Result;
-use crate::physical_plan::aggr::{AggrOperator, AggrOperatorRef};
+use crate::physical_plan::aggr::{unsupported_type, AggrOperator, AggrOperatorRef};
 use crate::physical_plan::expr::column::ColumnExpr;
 use crate::physical_plan::expr::PhysicalExpr;
 use arrow::array::{Array, PrimitiveArray};
 use arrow::datatypes::{DataType, Float64Type, Int64Type, UInt64Type};
 use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
 
 pub struct Sum {
+    /// The sum of the values, NULL until a value is aggregated
     sum: Scalar,
     column: ColumnExpr,
 }
 
 impl Sum {
-    pub fn new(data_type: DataType, column: ColumnExpr) -> AggrOperatorRef {
+    pub fn new(data_type: DataType, column: ColumnExpr) -> Result<AggrOperatorRef> {
         let scalar_value = match data_type {
-            DataType::Int64 => Scalar::Int64(Some(0i64)),
-            DataType::UInt64 => Scalar::UInt64(Some(0u64)),
-            DataType::Float64 => Scalar::Float64(Some(0f64)),
-            _ => unimplemented!(),
+            DataType::Int64 => Scalar::Int64(None),
+            DataType::UInt64 => Scalar::UInt64(None),
+            DataType::Float64 => Scalar::Float64(None),
+            data_type => return Err(unsupported_type("SUM", &data_type)),
         };
 
-        Box::new(Self {
+        Ok(Box::new(Self {
             sum: scalar_value,
             column,
-        })
+        }))
     }
 }
 
@@ -36,11 +38,11 @@ macro_rules! update_batch {
             .as_any()
             .downcast_ref::<PrimitiveArray<$DT>>()
             .unwrap();
-        if let Scalar::$SCALARTYPE(Some(mut sum)) = $SELF.sum {
+        if let Scalar::$SCALARTYPE(mut sum) = $SELF.sum {
             for val in column.into_iter().flatten() {
-                sum += val;
+                sum = Some(sum.unwrap_or_default() + val);
             }
-            $SELF.sum = Scalar::$SCALARTYPE(Some(sum));
+            $SELF.sum = Scalar::$SCALARTYPE(sum);
         }
     }};
 }
@@ -52,21 +54,27 @@ macro_rules! update {
             .downcast_ref::<PrimitiveArray<$DT>>()
             .unwrap();
         if !column.is_null($IDX) {
-            if let Scalar::$SCALARTYPE(Some(mut sum)) = $SELF.sum {
-                sum += column.value($IDX);
+            if let Scalar::$SCALARTYPE(sum) = $SELF.sum {
+                let sum = sum.unwrap_or_default() + column.value($IDX);
                 $SELF.sum = Scalar::$SCALARTYPE(Some(sum));
             }
         }
     }};
 }
 
+impl Display for Sum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SUM({})", self.column)
+    }
+}
+
 impl AggrOperator for Sum {
     fn to_field(&self, schema: &Schema) -> Result<Field> {
         let field = schema.field(self.column.index);
         Ok(Field::new(
             format!("SUM({})", field.name()).as_str(),
             field.data_type().clone(),
-            false,
+            true,
         ))
     }
 
@@ -77,7 +85,7 @@ impl AggrOperator for Sum {
             DataType::Int64 => update_batch!(column, Int64Type, Int64, self),
             DataType::UInt64 => update_batch!(column, UInt64Type, UInt64, self),
             DataType::Float64 => update_batch!(column, Float64Type, Float64, self),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("SUM", data_type)),
         }
 
         Ok(())
@@ -90,7 +98,7 @@ impl AggrOperator for Sum {
             DataType::Int64 => update!(column, Int64Type, Int64, self, i),
             DataType::UInt64 => update!(column, UInt64Type, UInt64, self, i),
             DataType::Float64 => update!(column, Float64Type, Float64, self, i),
-            _ => unimplemented!(),
+            data_type => return Err(unsupported_type("SUM", data_type)),
         }
 
         Ok(())
@@ -102,10 +110,10 @@ impl AggrOperator for Sum {
 
     fn clear(&mut self) -> Result<()> {
         match self.sum {
-            Scalar::Int64(_) => self.sum = Scalar::Int64(Some(0i64)),
-            Scalar::UInt64(_) => self.sum = Scalar::UInt64(Some(0u64)),
-            Scalar::Float64(_) => self.sum = Scalar::Float64(Some(0f64)),
-            _ => unimplemented!(),
+            Scalar::Int64(_) => self.sum = Scalar::Int64(None),
+            Scalar::UInt64(_) => self.sum = Scalar::UInt64(None),
+            Scalar::Float64(_) => self.sum = Scalar::Float64(None),
+            ref scalar => return Err(unsupported_type("SUM", scalar.to_field().data_type())),
         }
 
         Ok(())
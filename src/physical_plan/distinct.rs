@@ -0,0 +1,142 @@
+use crate::datatype::scalar::Scalar;
+use crate::datatype::schema::Schema;
+use crate::error::Result;
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef, RecordBatchStream};
+use arrow::array::UInt32Array;
+use arrow::compute;
+use arrow::record_batch::RecordBatch;
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Removes duplicate rows, the first occurrence of each row is kept in input order
+pub struct Distinct {
+    input: PhysicalPlanRef,
+}
+
+impl Distinct {
+    pub fn new(input: PhysicalPlanRef) -> PhysicalPlanRef {
+        Arc::new(Self { input })
+    }
+}
+
+/// Returns the batch without the rows that were already seen,
+/// the whole row is used as the key and NULLs are equal to each other
+pub fn remove_seen_rows(
+    batch: &RecordBatch,
+    seen: &mut HashSet<Vec<Scalar>>,
+) -> Result<RecordBatch> {
+    let mut indices = vec![];
+
+    for i in 0..batch.num_rows() {
+        let row = batch
+            .columns()
+            .iter()
+            .map(|column| Scalar::try_from_array(column, i))
+            .collect::<Result<Vec<_>>>()?;
+
+        if seen.insert(row) {
+            indices.push(i as u32);
+        }
+    }
+
+    if indices.len() == batch.num_rows() {
+        return Ok(batch.clone());
+    }
+
+    let indices = UInt32Array::from(indices);
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|column| compute::take(column.as_ref(), &indices, None))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(RecordBatch::try_new(batch.schema(), columns)?)
+}
+
+impl Display for Distinct {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Distinct")
+    }
+}
+
+impl PhysicalPlan for Distinct {
+    fn schema(&self) -> &Schema {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        self.execute_stream()?.collect()
+    }
+
+    /// Deduplicates each batch against all rows returned before it
+    fn execute_stream(&self) -> Result<RecordBatchStream<'_>> {
+        let input = self.input.execute_stream()?;
+        let mut seen = HashSet::new();
+
+        Ok(Box::new(
+            input.map(move |batch| remove_seen_rows(&batch?, &mut seen)),
+        ))
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.input.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::datatype::field::Field;
+    use crate::logical_plan::logical_expr::Operator;
+    use crate::physical_plan::expr::binary::BinaryExpr;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::expr::literal::LiteralExpr;
+    use crate::physical_plan::projection::Projection;
+    use crate::physical_plan::scan::Scan;
+    use arrow::array::{Array, ArrayRef, BooleanArray, Int64Array};
+    use arrow::datatypes::DataType;
+
+    #[test]
+    fn distinct_physical_plan() -> Result<()> {
+        let source = CSVTable::try_create_table("data/test.csv")?;
+        let scan = Scan::new(source, None);
+
+        let schema = Schema::new(vec![
+            Field::new("age", DataType::Int64, false),
+            Field::new("age <= 24", DataType::Boolean, true),
+            Field::new("null", DataType::Utf8, true),
+        ]);
+
+        let projection = Projection::new(
+            scan,
+            schema,
+            vec![
+                ColumnExpr::new(2),
+                BinaryExpr::new(
+                    ColumnExpr::new(2),
+                    Operator::LtEq,
+                    LiteralExpr::new(Scalar::Int64(Some(24))),
+                ),
+                LiteralExpr::new(Scalar::Utf8(None)),
+            ],
+        );
+
+        let batches = Distinct::new(projection).execute()?;
+        assert_eq!(batches.len(), 1);
+
+        let batch = &batches[0];
+        assert_eq!(
+            batch.column(0),
+            &(Arc::new(Int64Array::from(vec![24, 18, 26])) as ArrayRef)
+        );
+        assert_eq!(
+            batch.column(1),
+            &(Arc::new(BooleanArray::from(vec![true, true, false])) as ArrayRef)
+        );
+        assert_eq!(batch.column(2).null_count(), 3);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use crate::datatype::schema::Schema;
+use crate::error::Result;
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Returns no batches at all
+pub struct EmptyRelation {
+    schema: Schema,
+}
+
+impl EmptyRelation {
+    pub fn new(schema: Schema) -> PhysicalPlanRef {
+        Arc::new(Self { schema })
+    }
+}
+
+impl Display for EmptyRelation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "EmptyRelation")
+    }
+}
+
+impl PhysicalPlan for EmptyRelation {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        Ok(vec![])
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,107 @@
+use crate::datatype::schema::Schema;
+use crate::error::Result;
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use arrow::array::{ArrayRef, StringArray};
+use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Returns the plans of an EXPLAIN statement, they are created by the query planner
+/// so the explained plan is never executed
+pub struct Explain {
+    /// Pairs of the plan type and the formatted plan
+    plans: Vec<(String, String)>,
+    schema: Schema,
+}
+
+impl Explain {
+    pub fn new(plans: Vec<(String, String)>, schema: Schema) -> PhysicalPlanRef {
+        Arc::new(Self { plans, schema })
+    }
+}
+
+impl Display for Explain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Explain")
+    }
+}
+
+impl PhysicalPlan for Explain {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        Ok(vec![plans_to_batch(&self.plans, &self.schema)?])
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![])
+    }
+}
+
+/// Executes its input and returns the plans of an EXPLAIN ANALYZE statement,
+/// the physical plan is shown with the metrics recorded by the execution
+pub struct Analyze {
+    input: PhysicalPlanRef,
+    /// Pairs of the plan type and the formatted plan, the physical plan is added on execution
+    plans: Vec<(String, String)>,
+    schema: Schema,
+}
+
+impl Analyze {
+    pub fn new(
+        input: PhysicalPlanRef,
+        plans: Vec<(String, String)>,
+        schema: Schema,
+    ) -> PhysicalPlanRef {
+        Arc::new(Self {
+            input,
+            plans,
+            schema,
+        })
+    }
+}
+
+impl Display for Analyze {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Analyze")
+    }
+}
+
+impl PhysicalPlan for Analyze {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        // The output of the input is dropped batch by batch, only its metrics are kept
+        for batch in self.input.execute_stream()? {
+            batch?;
+        }
+
+        let mut plans = self.plans.clone();
+        plans.push((
+            "physical_plan_with_metrics".to_string(),
+            self.input.display_indent(true)?,
+        ));
+        Ok(vec![plans_to_batch(&plans, &self.schema)?])
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.input.clone()])
+    }
+}
+
+fn plans_to_batch(plans: &[(String, String)], schema: &Schema) -> Result<RecordBatch> {
+    let plan_types = StringArray::from_iter_values(plans.iter().map(|(t, _)| t));
+    let plans = StringArray::from_iter_values(plans.iter().map(|(_, p)| p));
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema.clone().into()),
+        vec![
+            Arc::new(plan_types) as ArrayRef,
+            Arc::new(plans) as ArrayRef,
+        ],
+    )?)
+}
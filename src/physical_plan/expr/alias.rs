@@ -4,6 +4,7 @@ use crate::datatype::field::Field;
 use crate::error::Result;
 use arrow::record_batch::RecordBatch;
 use std::any::Any;
+use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
 
 pub struct AliasExpr {
@@ -17,6 +18,12 @@ impl AliasExpr {
     }
 }
 
+impl Display for AliasExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} AS {}", self.expr, self.name)
+    }
+}
+
 impl PhysicalExpr for AliasExpr {
     fn as_any(&self) -> &dyn Any {
         self
@@ -0,0 +1,140 @@
+use crate::datatype::column_array::ColumnArray;
+use crate::datatype::field::Field;
+use crate::error::{Error, Result};
+use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
+use arrow::array::new_null_array;
+use arrow::compute::{and_kleene, gt_eq_dyn, lt_eq_dyn, not};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Checks whether the values of an expr are between two bounds, including the bounds
+pub struct BetweenExpr {
+    expr: PhysicalExprRef,
+    negated: bool,
+    low: PhysicalExprRef,
+    high: PhysicalExprRef,
+}
+
+impl BetweenExpr {
+    pub fn new(
+        expr: PhysicalExprRef,
+        negated: bool,
+        low: PhysicalExprRef,
+        high: PhysicalExprRef,
+    ) -> PhysicalExprRef {
+        Arc::new(Self {
+            expr,
+            negated,
+            low,
+            high,
+        })
+    }
+}
+
+impl Display for BetweenExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let not = if self.negated { "NOT " } else { "" };
+        write!(
+            f,
+            "{} {}BETWEEN {} AND {}",
+            self.expr, not, self.low, self.high
+        )
+    }
+}
+
+impl PhysicalExpr for BetweenExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn evaluate(&self, input: &RecordBatch) -> Result<ColumnArray> {
+        let array = self.expr.evaluate(input)?.to_array();
+        let low = self.low.evaluate(input)?.to_array();
+        let high = self.high.evaluate(input)?.to_array();
+
+        for bound in [&low, &high] {
+            if bound.data_type() != array.data_type() {
+                return Err(Error::IntervalError(format!(
+                    "Cannot evaluate BETWEEN with types {:?} and {:?}",
+                    array.data_type(),
+                    bound.data_type()
+                )));
+            }
+        }
+        // There are no comparison kernels for arrays of NULLs, all the results are NULL
+        if array.data_type() == &DataType::Null {
+            return Ok(ColumnArray::Array(new_null_array(
+                &DataType::Boolean,
+                array.len(),
+            )));
+        }
+
+        // low <= expr AND expr <= high, false if either comparison is false even if
+        // the other one is NULL
+        let result = and_kleene(
+            &gt_eq_dyn(array.as_ref(), low.as_ref())?,
+            &lt_eq_dyn(array.as_ref(), high.as_ref())?,
+        )?;
+        let result = if self.negated { not(&result)? } else { result };
+        Ok(ColumnArray::Array(Arc::new(result)))
+    }
+
+    fn to_field(&self, input: &RecordBatch) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        let not = if self.negated { "NOT " } else { "" };
+        Ok(Field::new(
+            &format!(
+                "{} {}BETWEEN {} AND {}",
+                field.name(),
+                not,
+                self.low,
+                self.high
+            ),
+            DataType::Boolean,
+            true,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::datatype::scalar::Scalar;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::expr::literal::LiteralExpr;
+    use crate::physical_plan::scan::Scan;
+    use arrow::array::{Array, BooleanArray};
+
+    #[test]
+    fn between_expr() -> Result<()> {
+        let source = CSVTable::try_create_table("data/test.csv")?;
+        let batches = Scan::new(source, None).execute()?;
+        let batch = &batches[0];
+        let int = |value| LiteralExpr::new(Scalar::Int64(value));
+        let evaluate = |expr: PhysicalExprRef| -> Result<Vec<Option<bool>>> {
+            let array = expr.evaluate(batch)?.to_array();
+            let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+            Ok(array.iter().collect())
+        };
+
+        // age BETWEEN 18 AND 24
+        let expr = BetweenExpr::new(ColumnExpr::new(2), false, int(Some(18)), int(Some(24)));
+        assert_eq!(
+            evaluate(expr)?,
+            vec![Some(true), Some(true), Some(true), Some(true), Some(false)]
+        );
+
+        // age NOT BETWEEN 20 AND NULL, true for the ages below 20 and otherwise NULL
+        let expr = BetweenExpr::new(ColumnExpr::new(2), true, int(Some(20)), int(None));
+        assert_eq!(
+            evaluate(expr)?,
+            vec![None, None, Some(true), Some(true), None]
+        );
+
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use crate::error::{Error, Result};
 use crate::logical_plan::logical_expr::Operator;
 use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
 use arrow::{
-    array::{BooleanArray, PrimitiveArray},
+    array::{Array, ArrayRef, BooleanArray, PrimitiveArray},
     compute::{
         add, and, divide, eq_dyn, gt_dyn, gt_eq_dyn, lt_dyn, lt_eq_dyn, modulus, multiply, neq_dyn,
         or, subtract,
@@ -16,6 +16,7 @@ use std::any::Any;
 use std::sync::Arc;
 
 use super::literal::LiteralExpr;
+use std::fmt::{self, Display, Formatter};
 
 macro_rules! compare_op {
     ($OP:expr, $LEFT:expr, $RIGHT:expr) => {
@@ -41,7 +42,7 @@ macro_rules! binary_op {
 }
 
 macro_rules! arithmetic_op {
-    ($OP:expr, $LEFT_DATA_TYPE:expr, $LEFT:expr, $RIGHT:expr) => {
+    ($OP:expr, $LEFT_DATA_TYPE:expr, $LEFT:expr, $RIGHT:expr, $OP_TYPE:expr) => {
         match $LEFT_DATA_TYPE {
             DataType::Int64 => {
                 let left = $LEFT
@@ -79,7 +80,10 @@ macro_rules! arithmetic_op {
                 let x = $OP(left, right)?;
                 Ok(ColumnArray::Array(Arc::new(x)))
             }
-            _ => unimplemented!(),
+            data_type => Err(Error::NotImplemented(format!(
+                "Operator {} for type {:?}",
+                $OP_TYPE, data_type
+            ))),
         }
     };
 }
@@ -96,6 +100,27 @@ impl BinaryExpr {
     }
 }
 
+impl Display for BinaryExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Operands that bind less tightly than the operator are put in parentheses,
+        // the right operand also if it binds equally tightly
+        let fmt_operand = |f: &mut Formatter<'_>, expr: &PhysicalExprRef, precedence: u8| match expr
+            .as_any()
+            .downcast_ref::<BinaryExpr>()
+        {
+            Some(binary_expr) if binary_expr.op.precedence() < precedence => {
+                write!(f, "({})", expr)
+            }
+            _ => write!(f, "{}", expr),
+        };
+
+        let precedence = self.op.precedence();
+        fmt_operand(f, &self.left, precedence)?;
+        write!(f, " {} ", self.op)?;
+        fmt_operand(f, &self.right, precedence + 1)
+    }
+}
+
 impl PhysicalExpr for BinaryExpr {
     fn as_any(&self) -> &dyn Any {
         self
@@ -141,11 +166,23 @@ impl PhysicalExpr for BinaryExpr {
                 right_array,
                 Operator::Or
             ),
-            Operator::Add => arithmetic_op!(add, left_type, left_array, right_array),
-            Operator::Sub => arithmetic_op!(subtract, left_type, left_array, right_array),
-            Operator::Mul => arithmetic_op!(multiply, left_type, left_array, right_array),
-            Operator::Div => arithmetic_op!(divide, left_type, left_array, right_array),
-            Operator::Mod => arithmetic_op!(modulus, left_type, left_array, right_array),
+            Operator::Add => {
+                arithmetic_op!(add, left_type, left_array, right_array, self.op)
+            }
+            Operator::Sub => {
+                arithmetic_op!(subtract, left_type, left_array, right_array, self.op)
+            }
+            Operator::Mul => {
+                arithmetic_op!(multiply, left_type, left_array, right_array, self.op)
+            }
+            Operator::Div => {
+                arithmetic_op!(divide, left_type, left_array, right_array, self.op)
+            }
+            Operator::Mod => {
+                arithmetic_op!(modulus, left_type, left_array, right_array, self.op)
+            }
+            Operator::IsDistinctFrom => is_distinct_from(&left_array, &right_array, false),
+            Operator::IsNotDistinctFrom => is_distinct_from(&left_array, &right_array, true),
         }
     }
 
@@ -153,7 +190,7 @@ impl PhysicalExpr for BinaryExpr {
         let left = self.left.to_field(input)?;
         let left_name = left.name();
 
-        let right = &*self.right.as_any();
+        let right = self.right.as_any();
 
         let right_name = match right {
             right if right.is::<LiteralExpr>() => self.right.to_field(input)?.name().clone(),
@@ -174,12 +211,40 @@ impl PhysicalExpr for BinaryExpr {
             Operator::Mul => ("*", left.data_type().clone()),
             Operator::Div => ("/", left.data_type().clone()),
             Operator::Mod => ("%", left.data_type().clone()),
+            Operator::IsDistinctFrom => ("IS DISTINCT FROM", DataType::Boolean),
+            Operator::IsNotDistinctFrom => ("IS NOT DISTINCT FROM", DataType::Boolean),
         };
+        let nullable = !matches!(
+            self.op,
+            Operator::IsDistinctFrom | Operator::IsNotDistinctFrom
+        );
 
         Ok(Field::new(
             &format!("{} {} {}", left_name, operator, right_name),
             data_type,
-            true,
+            nullable,
         ))
     }
 }
+
+/// Compares the values like `!=`, but a NULL is distinct from any value and not from NULL,
+/// so the result is never NULL. The result is inverted for IS NOT DISTINCT FROM.
+fn is_distinct_from(left: &ArrayRef, right: &ArrayRef, negated: bool) -> Result<ColumnArray> {
+    // There are no kernels for arrays of NULLs, all their values are NULL anyway
+    let eq = match left.data_type() {
+        DataType::Null => None,
+        _ => Some(eq_dyn(left, right)?),
+    };
+
+    let array = (0..left.len())
+        .map(|i| {
+            let distinct = match (left.is_null(i), right.is_null(i), &eq) {
+                (true, true, _) => false,
+                (false, false, Some(eq)) => !eq.value(i),
+                _ => true,
+            };
+            Some(distinct != negated)
+        })
+        .collect::<BooleanArray>();
+    Ok(ColumnArray::Array(Arc::new(array)))
+}
@@ -0,0 +1,205 @@
+use crate::datatype::column_array::ColumnArray;
+use crate::datatype::field::{common_type, Field};
+use crate::error::{Error, Result};
+use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
+use arrow::array::{new_null_array, Array, ArrayRef, BooleanArray};
+use arrow::compute::kernels::filter::prep_null_mask_filter;
+use arrow::compute::kernels::zip::zip;
+use arrow::compute::{cast, eq_dyn};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Evaluates `CASE [expr] WHEN when THEN then ... [ELSE else_expr] END`.
+/// All branches are evaluated on the whole batch and the results are combined with masks.
+pub struct CaseExpr {
+    expr: Option<PhysicalExprRef>,
+    when_then_expr: Vec<(PhysicalExprRef, PhysicalExprRef)>,
+    else_expr: Option<PhysicalExprRef>,
+    /// The common type of the results, every result is cast to it
+    data_type: DataType,
+}
+
+impl CaseExpr {
+    pub fn new(
+        expr: Option<PhysicalExprRef>,
+        when_then_expr: Vec<(PhysicalExprRef, PhysicalExprRef)>,
+        else_expr: Option<PhysicalExprRef>,
+        data_type: DataType,
+    ) -> PhysicalExprRef {
+        Arc::new(Self {
+            expr,
+            when_then_expr,
+            else_expr,
+            data_type,
+        })
+    }
+
+    /// Returns a mask of the rows that take the branch, NULL conditions do not match
+    fn when_mask(&self, input: &RecordBatch, when: &PhysicalExprRef) -> Result<BooleanArray> {
+        let when = when.evaluate(input)?;
+
+        let mask = match &self.expr {
+            Some(expr) => {
+                let operand = expr.evaluate(input)?;
+                let data_type =
+                    common_type(&operand.data_type(), &when.data_type()).ok_or_else(|| {
+                        Error::IntervalError(format!(
+                            "Cannot compare CASE operand of type {:?} with WHEN value of type {:?}",
+                            operand.data_type(),
+                            when.data_type()
+                        ))
+                    })?;
+                if data_type == DataType::Null {
+                    return Ok(BooleanArray::from(vec![false; input.num_rows()]));
+                }
+                eq_dyn(
+                    cast_to(operand, &data_type)?.as_ref(),
+                    cast_to(when, &data_type)?.as_ref(),
+                )?
+            }
+            None => match when.data_type() {
+                DataType::Boolean => BooleanArray::from(when.to_array().data().clone()),
+                DataType::Null => BooleanArray::from(vec![false; input.num_rows()]),
+                data_type => {
+                    return Err(Error::IntervalError(format!(
+                        "WHEN condition must be Boolean, found {:?}",
+                        data_type
+                    )))
+                }
+            },
+        };
+
+        if mask.null_count() > 0 {
+            Ok(prep_null_mask_filter(&mask))
+        } else {
+            Ok(mask)
+        }
+    }
+}
+
+fn cast_to(column: ColumnArray, data_type: &DataType) -> Result<ArrayRef> {
+    let array = column.to_array();
+    if array.data_type() == data_type {
+        Ok(array)
+    } else {
+        Ok(cast(&array, data_type)?)
+    }
+}
+
+impl Display for CaseExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CASE")?;
+        if let Some(expr) = &self.expr {
+            write!(f, " {}", expr)?;
+        }
+        for (when, then) in &self.when_then_expr {
+            write!(f, " WHEN {} THEN {}", when, then)?;
+        }
+        if let Some(else_expr) = &self.else_expr {
+            write!(f, " ELSE {}", else_expr)?;
+        }
+        write!(f, " END")
+    }
+}
+
+impl PhysicalExpr for CaseExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    /// The branches are applied from the last to the first,
+    /// so that the first matching branch decides the result of a row
+    fn evaluate(&self, input: &RecordBatch) -> Result<ColumnArray> {
+        let mut result = match &self.else_expr {
+            Some(else_expr) => cast_to(else_expr.evaluate(input)?, &self.data_type)?,
+            None => new_null_array(&self.data_type, input.num_rows()),
+        };
+
+        for (when, then) in self.when_then_expr.iter().rev() {
+            let mask = self.when_mask(input, when)?;
+            let then = cast_to(then.evaluate(input)?, &self.data_type)?;
+            result = zip(&mask, then.as_ref(), result.as_ref())?;
+        }
+
+        Ok(ColumnArray::Array(result))
+    }
+
+    fn to_field(&self, _input: &RecordBatch) -> Result<Field> {
+        Ok(Field::new("CASE", self.data_type.clone(), true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::datatype::scalar::Scalar;
+    use crate::logical_plan::logical_expr::Operator;
+    use crate::physical_plan::expr::binary::BinaryExpr;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::expr::literal::LiteralExpr;
+    use crate::physical_plan::scan::Scan;
+    use arrow::array::{Float64Array, StringArray};
+
+    #[test]
+    fn case_expr() -> Result<()> {
+        let source = CSVTable::try_create_table("data/test.csv")?;
+        let batches = Scan::new(source, None).execute()?;
+        let batch = &batches[0];
+
+        // CASE WHEN score >= 99.98 THEN 'A' WHEN age = 24 THEN 'B' END
+        let searched = CaseExpr::new(
+            None,
+            vec![
+                (
+                    BinaryExpr::new(
+                        ColumnExpr::new(3),
+                        Operator::GtEq,
+                        LiteralExpr::new(Scalar::Float64(Some(99.98))),
+                    ),
+                    LiteralExpr::new(Scalar::Utf8(Some("A".to_string()))),
+                ),
+                (
+                    BinaryExpr::new(
+                        ColumnExpr::new(2),
+                        Operator::Eq,
+                        LiteralExpr::new(Scalar::Int64(Some(24))),
+                    ),
+                    LiteralExpr::new(Scalar::Utf8(Some("B".to_string()))),
+                ),
+            ],
+            None,
+            DataType::Utf8,
+        );
+        assert_eq!(
+            &searched.evaluate(batch)?.to_array(),
+            &(Arc::new(StringArray::from(vec![
+                Some("B"),
+                Some("A"),
+                Some("A"),
+                Some("A"),
+                None
+            ])) as ArrayRef)
+        );
+
+        // CASE age WHEN 18 THEN score ELSE 0 END, the Int64 result is cast to Float64
+        let simple = CaseExpr::new(
+            Some(ColumnExpr::new(2)),
+            vec![(
+                LiteralExpr::new(Scalar::Int64(Some(18))),
+                ColumnExpr::new(3),
+            )],
+            Some(LiteralExpr::new(Scalar::Int64(Some(0)))),
+            DataType::Float64,
+        );
+        assert_eq!(
+            &simple.evaluate(batch)?.to_array(),
+            &(Arc::new(Float64Array::from(vec![0.0, 0.0, 99.99, 99.98, 0.0])) as ArrayRef)
+        );
+
+        Ok(())
+    }
+}
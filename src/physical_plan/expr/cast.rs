@@ -0,0 +1,123 @@
+use crate::datatype::column_array::ColumnArray;
+use crate::datatype::field::Field;
+use crate::error::Result;
+use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
+use arrow::array::{Array, ArrayRef};
+use arrow::compute::{cast_with_options, CastOptions};
+use arrow::datatypes::DataType;
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Converts the values of an expr to another data type
+pub struct CastExpr {
+    expr: PhysicalExprRef,
+    data_type: DataType,
+    /// Values that cannot be converted become NULL instead of failing the query (TRY_CAST)
+    safe: bool,
+}
+
+impl CastExpr {
+    pub fn new(expr: PhysicalExprRef, data_type: DataType, safe: bool) -> PhysicalExprRef {
+        Arc::new(Self {
+            expr,
+            data_type,
+            safe,
+        })
+    }
+}
+
+impl Display for CastExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let func = if self.safe { "TRY_CAST" } else { "CAST" };
+        write!(f, "{}({} AS {:?})", func, self.expr, self.data_type)
+    }
+}
+
+impl PhysicalExpr for CastExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn evaluate(&self, input: &RecordBatch) -> Result<ColumnArray> {
+        let array = self.expr.evaluate(input)?.to_array();
+        if array.data_type() == &self.data_type {
+            return Ok(ColumnArray::Array(array));
+        }
+
+        if !self.safe {
+            return Ok(ColumnArray::Array(cast_checked(&array, &self.data_type)?));
+        }
+        let options = CastOptions { safe: true };
+        Ok(ColumnArray::Array(cast_with_options(
+            &array,
+            &self.data_type,
+            &options,
+        )?))
+    }
+
+    fn to_field(&self, input: &RecordBatch) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        Ok(Field::new(
+            &format!("CAST({} AS {:?})", field.name(), self.data_type),
+            self.data_type.clone(),
+            true,
+        ))
+    }
+}
+
+/// Casts the array, a value that cannot be converted is an error. Arrow turns numbers that are
+/// out of the range of the type into NULL even if the cast is not safe, they are errors too.
+pub fn cast_checked(array: &ArrayRef, data_type: &DataType) -> Result<ArrayRef> {
+    let result = cast_with_options(array, data_type, &CastOptions { safe: false })?;
+    if result.null_count() > array.null_count() {
+        if let Some(i) = (0..array.len()).find(|&i| array.is_valid(i) && result.is_null(i)) {
+            return Err(ArrowError::CastError(format!(
+                "Cannot cast {} to {:?}",
+                array_value_to_string(array, i)?,
+                data_type
+            ))
+            .into());
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::scan::Scan;
+    use arrow::array::{Array, ArrayRef, Float64Array};
+
+    #[test]
+    fn cast_expr() -> Result<()> {
+        let source = CSVTable::try_create_table("data/test.csv")?;
+        let batches = Scan::new(source, None).execute()?;
+        let batch = &batches[0];
+
+        // CAST(age AS Float64)
+        assert_eq!(
+            &CastExpr::new(ColumnExpr::new(2), DataType::Float64, false)
+                .evaluate(batch)?
+                .to_array(),
+            &(Arc::new(Float64Array::from(vec![24.0, 24.0, 18.0, 18.0, 26.0])) as ArrayRef)
+        );
+
+        // Names are not numbers, CAST fails and TRY_CAST returns NULLs
+        assert!(CastExpr::new(ColumnExpr::new(1), DataType::Int64, false)
+            .evaluate(batch)
+            .is_err());
+        let array = CastExpr::new(ColumnExpr::new(1), DataType::Int64, true)
+            .evaluate(batch)?
+            .to_array();
+        assert_eq!(array.data_type(), &DataType::Int64);
+        assert_eq!(array.null_count(), 5);
+
+        Ok(())
+    }
+}
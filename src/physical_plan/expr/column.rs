@@ -4,6 +4,7 @@ use crate::error::Result;
 use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
 use arrow::record_batch::RecordBatch;
 use std::any::Any;
+use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -17,6 +18,13 @@ impl ColumnExpr {
     }
 }
 
+/// Columns are shown by their index in the input, e.g. `#2`
+impl Display for ColumnExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.index)
+    }
+}
+
 impl PhysicalExpr for ColumnExpr {
     fn as_any(&self) -> &dyn Any {
         self
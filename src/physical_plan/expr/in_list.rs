@@ -0,0 +1,216 @@
+use crate::datatype::column_array::ColumnArray;
+use crate::datatype::field::Field;
+use crate::datatype::scalar::Scalar;
+use crate::error::{Error, Result};
+use crate::physical_plan::expr::literal::LiteralExpr;
+use crate::physical_plan::expr::{format_exprs, PhysicalExpr, PhysicalExprRef};
+use arrow::array::{
+    new_null_array, Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray,
+    UInt64Array,
+};
+use arrow::compute::{eq_dyn, not, or_kleene};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use std::any::Any;
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Looks up each value of the array in the set, NULL if the value is NULL
+/// or if it is not found and the list contains NULL
+macro_rules! probe_set {
+    ($ARRAY:expr, $ARRAY_TYPE:ident, $SCALAR_TYPE:ident, $SET:expr, $HAS_NULL:expr) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        array
+            .iter()
+            .map(|value| {
+                let found = $SET.contains(&Scalar::$SCALAR_TYPE(Some(value?.into())));
+                if found || !$HAS_NULL {
+                    Some(found)
+                } else {
+                    None
+                }
+            })
+            .collect::<BooleanArray>()
+    }};
+}
+
+/// Checks whether the values of an expr are in a list of exprs
+pub struct InListExpr {
+    expr: PhysicalExprRef,
+    list: Vec<PhysicalExprRef>,
+    negated: bool,
+    /// The values of the list if all of them are literals, hashed once per query
+    set: Option<HashSet<Scalar>>,
+    /// Whether the literal list contains NULL, which is not in the set
+    has_null: bool,
+}
+
+impl InListExpr {
+    pub fn new(
+        expr: PhysicalExprRef,
+        list: Vec<PhysicalExprRef>,
+        negated: bool,
+    ) -> PhysicalExprRef {
+        let literals = list
+            .iter()
+            .map(|expr| {
+                expr.as_any()
+                    .downcast_ref::<LiteralExpr>()
+                    .map(|literal| &literal.literal)
+            })
+            .collect::<Option<Vec<_>>>();
+        let has_null = literals.iter().flatten().any(|literal| literal.is_null());
+        let set = literals.map(|literals| {
+            literals
+                .into_iter()
+                .filter(|literal| !literal.is_null())
+                .cloned()
+                .collect()
+        });
+
+        Arc::new(Self {
+            expr,
+            list,
+            negated,
+            set,
+            has_null,
+        })
+    }
+
+    fn probe(&self, array: &ArrayRef, set: &HashSet<Scalar>) -> Result<BooleanArray> {
+        let has_null = self.has_null;
+        Ok(match array.data_type() {
+            DataType::Boolean => probe_set!(array, BooleanArray, Boolean, set, has_null),
+            DataType::Int64 => probe_set!(array, Int64Array, Int64, set, has_null),
+            DataType::UInt64 => probe_set!(array, UInt64Array, UInt64, set, has_null),
+            DataType::Float64 => probe_set!(array, Float64Array, Float64, set, has_null),
+            DataType::Utf8 => probe_set!(array, StringArray, Utf8, set, has_null),
+            data_type => {
+                return Err(Error::NotImplemented(format!(
+                    "IN list of type {:?}",
+                    data_type
+                )))
+            }
+        })
+    }
+
+    /// Compares the values with each expr of the list, as `expr = a OR expr = b ...`
+    fn compare_each(&self, array: &ArrayRef, input: &RecordBatch) -> Result<BooleanArray> {
+        let mut result = BooleanArray::from(vec![Some(false); array.len()]);
+        for expr in &self.list {
+            let item = expr.evaluate(input)?.to_array();
+            if item.data_type() != array.data_type() {
+                return Err(Error::IntervalError(format!(
+                    "Cannot evaluate IN list with types {:?} and {:?}",
+                    array.data_type(),
+                    item.data_type()
+                )));
+            }
+            result = or_kleene(&result, &eq_dyn(array.as_ref(), item.as_ref())?)?;
+        }
+        Ok(result)
+    }
+}
+
+impl Display for InListExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let not = if self.negated { "NOT " } else { "" };
+        write!(f, "{} {}IN ({})", self.expr, not, format_exprs(&self.list))
+    }
+}
+
+impl PhysicalExpr for InListExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn evaluate(&self, input: &RecordBatch) -> Result<ColumnArray> {
+        let array = self.expr.evaluate(input)?.to_array();
+        // NULL is not equal to any value
+        if array.data_type() == &DataType::Null {
+            return Ok(ColumnArray::Array(new_null_array(
+                &DataType::Boolean,
+                array.len(),
+            )));
+        }
+
+        let result = match &self.set {
+            Some(set) => self.probe(&array, set)?,
+            None => self.compare_each(&array, input)?,
+        };
+        let result = if self.negated { not(&result)? } else { result };
+        Ok(ColumnArray::Array(Arc::new(result)))
+    }
+
+    fn to_field(&self, input: &RecordBatch) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        let not = if self.negated { "NOT " } else { "" };
+        Ok(Field::new(
+            &format!("{} {}IN ({})", field.name(), not, format_exprs(&self.list)),
+            DataType::Boolean,
+            true,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::logical_plan::logical_expr::Operator;
+    use crate::physical_plan::expr::binary::BinaryExpr;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::scan::Scan;
+
+    fn evaluate(expr: PhysicalExprRef, batch: &RecordBatch) -> Result<Vec<Option<bool>>> {
+        let array = expr.evaluate(batch)?.to_array();
+        let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+        Ok(array.iter().collect())
+    }
+
+    #[test]
+    fn in_list_expr() -> Result<()> {
+        let source = CSVTable::try_create_table("data/test.csv")?;
+        let batches = Scan::new(source, None).execute()?;
+        let batch = &batches[0];
+        let int = |value| LiteralExpr::new(Scalar::Int64(Some(value)));
+
+        // id IN (1, 3, 5)
+        let expr = InListExpr::new(ColumnExpr::new(0), vec![int(1), int(3), int(5)], false);
+        assert_eq!(
+            evaluate(expr, batch)?,
+            vec![Some(true), Some(false), Some(true), Some(false), Some(true)]
+        );
+
+        // name NOT IN ('Brian', NULL) is never true
+        let expr = InListExpr::new(
+            ColumnExpr::new(1),
+            vec![
+                LiteralExpr::new(Scalar::Utf8(Some("Brian".to_string()))),
+                LiteralExpr::new(Scalar::Utf8(None)),
+            ],
+            true,
+        );
+        assert_eq!(
+            evaluate(expr, batch)?,
+            vec![None, None, None, None, Some(false)]
+        );
+
+        // age IN (id + 22, 18) is compared row by row
+        let expr = InListExpr::new(
+            ColumnExpr::new(2),
+            vec![
+                BinaryExpr::new(ColumnExpr::new(0), Operator::Add, int(22)),
+                int(18),
+            ],
+            false,
+        );
+        assert_eq!(
+            evaluate(expr, batch)?,
+            vec![Some(false), Some(true), Some(true), Some(true), Some(false)]
+        );
+
+        Ok(())
+    }
+}
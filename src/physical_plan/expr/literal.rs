@@ -5,6 +5,7 @@ use crate::error::Result;
 use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
 use arrow::record_batch::RecordBatch;
 use std::any::Any;
+use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
 
 pub struct LiteralExpr {
@@ -17,6 +18,15 @@ impl LiteralExpr {
     }
 }
 
+impl Display for LiteralExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.literal {
+            Scalar::Utf8(Some(value)) => write!(f, "'{}'", value),
+            literal => write!(f, "{}", literal),
+        }
+    }
+}
+
 impl PhysicalExpr for LiteralExpr {
     fn as_any(&self) -> &dyn Any {
         self
@@ -1,21 +1,39 @@
 pub mod alias;
+pub mod between;
 pub mod binary;
+pub mod case;
+pub mod cast;
 pub mod column;
+pub mod in_list;
 pub mod literal;
+pub mod pattern;
+pub mod placeholder;
+pub mod unary;
 
 use crate::datatype::column_array::ColumnArray;
 use crate::datatype::field::Field;
 use crate::error::Result;
 use arrow::record_batch::RecordBatch;
 use std::any::Any;
+use std::fmt::Display;
 use std::sync::Arc;
 
 pub type PhysicalExprRef = Arc<dyn PhysicalExpr>;
 
-pub trait PhysicalExpr {
+/// `Display` shows the expr as SQL-like text, it is used by EXPLAIN
+pub trait PhysicalExpr: Display {
     fn as_any(&self) -> &dyn Any;
 
     fn evaluate(&self, input: &RecordBatch) -> Result<ColumnArray>;
 
     fn to_field(&self, input: &RecordBatch) -> Result<Field>;
 }
+
+/// Formats a list of exprs separated by commas
+pub fn format_exprs(exprs: &[PhysicalExprRef]) -> String {
+    exprs
+        .iter()
+        .map(|expr| expr.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,302 @@
+use crate::datatype::column_array::ColumnArray;
+use crate::datatype::field::Field;
+use crate::datatype::scalar::Scalar;
+use crate::error::{Error, Result};
+use crate::logical_plan::logical_expr::PatternKind;
+use crate::physical_plan::expr::literal::LiteralExpr;
+use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
+use arrow::array::{new_null_array, Array, ArrayRef, BooleanArray, StringArray};
+use arrow::compute::{not, regexp_is_match_utf8, regexp_is_match_utf8_scalar};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use regex::Regex;
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Matches the strings of an expr against a LIKE, ILIKE, SIMILAR TO or regex pattern
+pub struct PatternMatchExpr {
+    kind: PatternKind,
+    negated: bool,
+    expr: PhysicalExprRef,
+    pattern: PhysicalExprRef,
+    escape_char: Option<char>,
+    /// The compiled pattern if it is a literal, so it is compiled only once per query
+    regex: Option<Regex>,
+}
+
+impl PatternMatchExpr {
+    pub fn new(
+        kind: PatternKind,
+        negated: bool,
+        expr: PhysicalExprRef,
+        pattern: PhysicalExprRef,
+        escape_char: Option<char>,
+    ) -> Result<PhysicalExprRef> {
+        let regex = match pattern.as_any().downcast_ref::<LiteralExpr>() {
+            Some(LiteralExpr {
+                literal: Scalar::Utf8(Some(pattern)),
+            }) => {
+                let regex = to_regex(&kind, pattern, escape_char)?;
+                Some(Regex::new(&regex).map_err(|e| {
+                    Error::PhysicalPlanError(format!("Invalid pattern '{}': {}", pattern, e))
+                })?)
+            }
+            _ => None,
+        };
+
+        Ok(Arc::new(Self {
+            kind,
+            negated,
+            expr,
+            pattern,
+            escape_char,
+            regex,
+        }))
+    }
+
+    fn operator(&self) -> &'static str {
+        self.kind.operator(self.negated)
+    }
+
+    /// Matches the strings against patterns that are only known when the batch is evaluated,
+    /// each distinct pattern is translated to a regex and compiled once per batch
+    fn match_patterns(&self, strings: &StringArray, patterns: ColumnArray) -> Result<BooleanArray> {
+        if let ColumnArray::Literal(Scalar::Utf8(Some(pattern)), _) = &patterns {
+            let regex = to_regex(&self.kind, pattern, self.escape_char)?;
+            return Ok(regexp_is_match_utf8_scalar(strings, &regex, None)?);
+        }
+
+        let patterns = patterns.to_array();
+        if patterns.data_type() == &DataType::Null {
+            return Ok(BooleanArray::from(vec![None; strings.len()]));
+        }
+        let patterns = patterns
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| self.type_error(patterns.data_type()))?;
+        let regexes = patterns
+            .iter()
+            .map(|pattern| {
+                pattern
+                    .map(|pattern| to_regex(&self.kind, pattern, self.escape_char))
+                    .transpose()
+            })
+            .collect::<Result<StringArray>>()?;
+        Ok(regexp_is_match_utf8(strings, &regexes, None)?)
+    }
+
+    fn type_error(&self, data_type: &DataType) -> Error {
+        Error::PhysicalPlanError(format!(
+            "{} can only match Utf8 values with a Utf8 pattern, found {:?}",
+            self.operator(),
+            data_type
+        ))
+    }
+}
+
+impl Display for PatternMatchExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.expr, self.operator(), self.pattern)?;
+        if let Some(escape_char) = self.escape_char {
+            write!(f, " ESCAPE '{}'", escape_char)?;
+        }
+        Ok(())
+    }
+}
+
+impl PhysicalExpr for PatternMatchExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn evaluate(&self, input: &RecordBatch) -> Result<ColumnArray> {
+        let strings = self.expr.evaluate(input)?.to_array();
+        if strings.data_type() == &DataType::Null {
+            return Ok(ColumnArray::Array(new_null_array(
+                &DataType::Boolean,
+                strings.len(),
+            )));
+        }
+        let strings = strings
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| self.type_error(strings.data_type()))?;
+
+        let matches = match &self.regex {
+            Some(regex) => strings
+                .iter()
+                .map(|string| string.map(|string| regex.is_match(string)))
+                .collect::<BooleanArray>(),
+            None => self.match_patterns(strings, self.pattern.evaluate(input)?)?,
+        };
+
+        let matches = if self.negated {
+            not(&matches)?
+        } else {
+            matches
+        };
+        Ok(ColumnArray::Array(Arc::new(matches) as ArrayRef))
+    }
+
+    fn to_field(&self, input: &RecordBatch) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        let pattern = self.pattern.to_field(input)?;
+        Ok(Field::new(
+            &format!("{} {} {}", field.name(), self.operator(), pattern.name()),
+            DataType::Boolean,
+            true,
+        ))
+    }
+}
+
+/// Translates a pattern to an equivalent regex
+fn to_regex(kind: &PatternKind, pattern: &str, escape_char: Option<char>) -> Result<String> {
+    let like = match kind {
+        PatternKind::Regex => return Ok(pattern.to_string()),
+        PatternKind::Like => "(?s)^",
+        PatternKind::ILike => "(?si)^",
+        PatternKind::SimilarTo => "(?s)^(?:",
+    };
+
+    let mut regex = like.to_string();
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if Some(ch) == escape_char => match chars.next() {
+                Some(ch) => regex.push_str(&regex::escape(&ch.to_string())),
+                None => {
+                    return Err(Error::PhysicalPlanError(format!(
+                        "The pattern '{}' must not end with the escape character",
+                        pattern
+                    )))
+                }
+            },
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            // SIMILAR TO keeps the alternatives, repetitions, groups and brackets of regexes
+            '|' | '*' | '+' | '?' | '{' | '}' | '(' | ')' | '[' | ']'
+                if kind == &PatternKind::SimilarTo =>
+            {
+                regex.push(ch)
+            }
+            ch => regex.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+
+    if kind == &PatternKind::SimilarTo {
+        regex.push(')');
+    }
+    regex.push('$');
+    Ok(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::scan::Scan;
+
+    fn evaluate(expr: PhysicalExprRef, batch: &RecordBatch) -> Result<Vec<Option<bool>>> {
+        let array = expr.evaluate(batch)?.to_array();
+        let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+        Ok(array.iter().collect())
+    }
+
+    #[test]
+    fn pattern_match_expr() -> Result<()> {
+        let source = CSVTable::try_create_table("data/test.csv")?;
+        let batches = Scan::new(source, None).execute()?;
+        let batch = &batches[0];
+        let pattern = |pattern: &str| LiteralExpr::new(Scalar::Utf8(Some(pattern.to_string())));
+
+        // name LIKE '%e%r'
+        let expr = PatternMatchExpr::new(
+            PatternKind::Like,
+            false,
+            ColumnExpr::new(1),
+            pattern("%e%r"),
+            None,
+        )?;
+        assert_eq!(
+            evaluate(expr, batch)?,
+            vec![
+                Some(false),
+                Some(false),
+                Some(true),
+                Some(true),
+                Some(false)
+            ]
+        );
+
+        // name NOT ILIKE 'b%'
+        let expr = PatternMatchExpr::new(
+            PatternKind::ILike,
+            true,
+            ColumnExpr::new(1),
+            pattern("b%"),
+            None,
+        )?;
+        assert_eq!(
+            evaluate(expr, batch)?,
+            vec![Some(false), Some(true), Some(true), Some(true), Some(false)]
+        );
+
+        // name SIMILAR TO '(Brian|Kamen)%'
+        let expr = PatternMatchExpr::new(
+            PatternKind::SimilarTo,
+            false,
+            ColumnExpr::new(1),
+            pattern("(Brian|Kamen)%"),
+            None,
+        )?;
+        assert_eq!(
+            evaluate(expr, batch)?,
+            vec![
+                Some(false),
+                Some(false),
+                Some(true),
+                Some(false),
+                Some(true)
+            ]
+        );
+
+        // The pattern is a column, name ~ name matches every name
+        let expr = PatternMatchExpr::new(
+            PatternKind::Regex,
+            false,
+            ColumnExpr::new(1),
+            ColumnExpr::new(1),
+            None,
+        )?;
+        assert_eq!(evaluate(expr, batch)?, vec![Some(true); 5]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn translate_patterns() -> Result<()> {
+        assert_eq!(to_regex(&PatternKind::Like, "a_%.", None)?, "(?s)^a..*\\.$");
+        assert_eq!(
+            to_regex(&PatternKind::Like, "100!%!!", Some('!'))?,
+            "(?s)^100%!$"
+        );
+        assert_eq!(
+            to_regex(&PatternKind::SimilarTo, "(a|b)+_", None)?,
+            "(?s)^(?:(a|b)+.)$"
+        );
+        assert_eq!(to_regex(&PatternKind::Regex, "^a.", None)?, "^a.");
+        assert!(to_regex(&PatternKind::Like, "abc!", Some('!')).is_err());
+        assert!(PatternMatchExpr::new(
+            PatternKind::SimilarTo,
+            false,
+            ColumnExpr::new(1),
+            LiteralExpr::new(Scalar::Utf8(Some("(a".to_string()))),
+            None,
+        )
+        .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::datatype::column_array::ColumnArray;
+use crate::datatype::field::Field;
+use crate::datatype::scalar::Scalar;
+use crate::error::{Error, Result};
+use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
+use arrow::record_batch::RecordBatch;
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The values bound to the placeholders of a prepared statement, `$1` is the first value.
+/// They are shared by the statement and its physical plan, so that the plan can be
+/// executed again with other values without planning it again.
+pub type ParamValues = Rc<RefCell<Vec<Scalar>>>;
+
+/// Returns the value currently bound to placeholder `$id`
+pub struct PlaceholderExpr {
+    id: usize,
+    values: ParamValues,
+}
+
+impl PlaceholderExpr {
+    pub fn new(id: usize, values: ParamValues) -> PhysicalExprRef {
+        Arc::new(Self { id, values })
+    }
+
+    fn value(&self) -> Result<Scalar> {
+        self.values
+            .borrow()
+            .get(self.id - 1)
+            .cloned()
+            .ok_or_else(|| Error::PhysicalPlanError(format!("No value bound to ${}", self.id)))
+    }
+}
+
+impl Display for PlaceholderExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "${}", self.id)
+    }
+}
+
+impl PhysicalExpr for PlaceholderExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn evaluate(&self, input: &RecordBatch) -> Result<ColumnArray> {
+        Ok(ColumnArray::Literal(self.value()?, input.num_rows()))
+    }
+
+    fn to_field(&self, _input: &RecordBatch) -> Result<Field> {
+        let field = self.value()?.to_field();
+        Ok(Field::new(
+            &format!("${}", self.id),
+            field.data_type().clone(),
+            true,
+        ))
+    }
+}
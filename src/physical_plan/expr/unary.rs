@@ -0,0 +1,211 @@
+use crate::datatype::column_array::ColumnArray;
+use crate::datatype::field::Field;
+use crate::error::{Error, Result};
+use crate::logical_plan::logical_expr::UnaryOperator;
+use crate::physical_plan::expr::binary::BinaryExpr;
+use crate::physical_plan::expr::{PhysicalExpr, PhysicalExprRef};
+use arrow::array::{new_null_array, Array, ArrayRef, BooleanArray, Float64Array, Int64Array};
+use arrow::compute::{is_not_null, is_null, negate, not};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Applies an operator with a single operand, e.g. `NOT expr`, `-expr` or `expr IS NULL`
+pub struct UnaryExpr {
+    op: UnaryOperator,
+    expr: PhysicalExprRef,
+}
+
+impl UnaryExpr {
+    pub fn new(op: UnaryOperator, expr: PhysicalExprRef) -> PhysicalExprRef {
+        Arc::new(Self { op, expr })
+    }
+
+    fn type_error(&self, data_type: &DataType) -> Error {
+        Error::NotImplemented(format!("Operator {} for type {:?}", self.op, data_type))
+    }
+
+    fn boolean_array<'a>(&self, array: &'a ArrayRef) -> Result<&'a BooleanArray> {
+        array
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .ok_or_else(|| self.type_error(array.data_type()))
+    }
+}
+
+impl Display for UnaryExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let is_binary = self.expr.as_any().is::<BinaryExpr>();
+        match (&self.op, is_binary) {
+            (UnaryOperator::Not, _) => write!(f, "NOT {}", self.expr),
+            (UnaryOperator::Negative, true) => write!(f, "-({})", self.expr),
+            (UnaryOperator::Negative, false) => write!(f, "-{}", self.expr),
+            (op, true) => write!(f, "({}) {}", self.expr, op),
+            (op, false) => write!(f, "{} {}", self.expr, op),
+        }
+    }
+}
+
+impl PhysicalExpr for UnaryExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn evaluate(&self, input: &RecordBatch) -> Result<ColumnArray> {
+        let array = self.expr.evaluate(input)?.to_array();
+        let data_type = array.data_type();
+
+        let result: ArrayRef = match &self.op {
+            // NOT and - of NULL are NULL
+            UnaryOperator::Not if data_type == &DataType::Null => {
+                new_null_array(&DataType::Boolean, array.len())
+            }
+            UnaryOperator::Not => Arc::new(not(self.boolean_array(&array)?)?),
+            UnaryOperator::Negative => match data_type {
+                DataType::Null => new_null_array(&DataType::Int64, array.len()),
+                DataType::Int64 => {
+                    let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+                    Arc::new(negate(array)?)
+                }
+                DataType::Float64 => {
+                    let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+                    Arc::new(negate(array)?)
+                }
+                data_type => return Err(self.type_error(data_type)),
+            },
+            // The kernels only look at the validity bitmap, which arrays of NULLs do not have
+            UnaryOperator::IsNull if data_type == &DataType::Null => {
+                Arc::new(BooleanArray::from(vec![true; array.len()]))
+            }
+            UnaryOperator::IsNotNull if data_type == &DataType::Null => {
+                Arc::new(BooleanArray::from(vec![false; array.len()]))
+            }
+            UnaryOperator::IsNull => Arc::new(is_null(array.as_ref())?),
+            UnaryOperator::IsNotNull => Arc::new(is_not_null(array.as_ref())?),
+            op => {
+                let (value, negated) = match op {
+                    UnaryOperator::IsTrue => (true, false),
+                    UnaryOperator::IsFalse => (false, false),
+                    UnaryOperator::IsNotTrue => (true, true),
+                    _ => (false, true),
+                };
+                // Only a non-NULL value can be TRUE or FALSE
+                let matches = |is: Option<bool>| (is == Some(value)) != negated;
+                let result = if data_type == &DataType::Null {
+                    vec![matches(None); array.len()]
+                } else {
+                    self.boolean_array(&array)?.iter().map(matches).collect()
+                };
+                Arc::new(BooleanArray::from(result))
+            }
+        };
+        Ok(ColumnArray::Array(result))
+    }
+
+    fn to_field(&self, input: &RecordBatch) -> Result<Field> {
+        let field = self.expr.to_field(input)?;
+        let field = match self.op {
+            UnaryOperator::Not => Field::new(
+                &format!("NOT {}", field.name()),
+                DataType::Boolean,
+                field.is_nullable(),
+            ),
+            UnaryOperator::Negative => {
+                let data_type = match field.data_type() {
+                    DataType::Null => DataType::Int64,
+                    data_type => data_type.clone(),
+                };
+                Field::new(
+                    &format!("-{}", field.name()),
+                    data_type,
+                    field.is_nullable(),
+                )
+            }
+            _ => Field::new(
+                &format!("{} {}", field.name(), self.op),
+                DataType::Boolean,
+                false,
+            ),
+        };
+        Ok(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatype::scalar::Scalar;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::expr::literal::LiteralExpr;
+    use arrow::datatypes::{Field as ArrowField, Schema};
+
+    fn evaluate(op: UnaryOperator, expr: PhysicalExprRef, batch: &RecordBatch) -> Result<ArrayRef> {
+        Ok(UnaryExpr::new(op, expr).evaluate(batch)?.to_array())
+    }
+
+    fn booleans(array: ArrayRef) -> Vec<Option<bool>> {
+        let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+        array.iter().collect()
+    }
+
+    #[test]
+    fn unary_expr() -> Result<()> {
+        let schema = Schema::new(vec![
+            ArrowField::new("active", DataType::Boolean, true),
+            ArrowField::new("amount", DataType::Int64, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(BooleanArray::from(vec![Some(true), Some(false), None])),
+                Arc::new(Int64Array::from(vec![Some(1), None, Some(-3)])),
+            ],
+        )?;
+        let active = || ColumnExpr::new(0);
+
+        assert_eq!(
+            booleans(evaluate(UnaryOperator::Not, active(), &batch)?),
+            vec![Some(false), Some(true), None]
+        );
+        assert_eq!(
+            &evaluate(UnaryOperator::Negative, ColumnExpr::new(1), &batch)?,
+            &(Arc::new(Int64Array::from(vec![Some(-1), None, Some(3)])) as ArrayRef)
+        );
+        assert_eq!(
+            booleans(evaluate(UnaryOperator::IsNull, ColumnExpr::new(1), &batch)?),
+            vec![Some(false), Some(true), Some(false)]
+        );
+        assert_eq!(
+            booleans(evaluate(UnaryOperator::IsTrue, active(), &batch)?),
+            vec![Some(true), Some(false), Some(false)]
+        );
+        assert_eq!(
+            booleans(evaluate(UnaryOperator::IsNotFalse, active(), &batch)?),
+            vec![Some(true), Some(false), Some(true)]
+        );
+
+        // NULL literals
+        let null = || LiteralExpr::new(Scalar::Null);
+        assert_eq!(
+            booleans(evaluate(UnaryOperator::IsNotNull, null(), &batch)?),
+            vec![Some(false); 3]
+        );
+        assert_eq!(
+            booleans(evaluate(UnaryOperator::Not, null(), &batch)?),
+            vec![None; 3]
+        );
+
+        // IS results are never NULL, NOT is NULL for NULL
+        let field = UnaryExpr::new(UnaryOperator::IsFalse, active()).to_field(&batch)?;
+        assert_eq!(field.name(), "active IS FALSE");
+        assert!(!field.is_nullable());
+        assert!(UnaryExpr::new(UnaryOperator::Not, active())
+            .to_field(&batch)?
+            .is_nullable());
+
+        assert!(evaluate(UnaryOperator::Not, ColumnExpr::new(1), &batch).is_err());
+        Ok(())
+    }
+}
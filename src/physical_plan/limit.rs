@@ -0,0 +1,179 @@
+use crate::datatype::schema::Schema;
+use crate::error::Result;
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef, RecordBatchStream};
+use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+pub struct Limit {
+    input: PhysicalPlanRef,
+    /// Number of rows to skip before returning any rows
+    skip: usize,
+    /// Maximum number of rows to return, `None` means no limit
+    fetch: Option<usize>,
+}
+
+impl Limit {
+    pub fn new(input: PhysicalPlanRef, skip: usize, fetch: Option<usize>) -> PhysicalPlanRef {
+        Arc::new(Self { input, skip, fetch })
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.fetch {
+            Some(fetch) => write!(f, "Limit: skip={}, fetch={}", self.skip, fetch),
+            None => write!(f, "Limit: skip={}, fetch=None", self.skip),
+        }
+    }
+}
+
+impl PhysicalPlan for Limit {
+    fn schema(&self) -> &Schema {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        self.execute_stream()?.collect()
+    }
+
+    /// Slices the batches of the input, no more batches are pulled
+    /// from the input once `fetch` rows have been returned.
+    fn execute_stream(&self) -> Result<RecordBatchStream<'_>> {
+        let mut input = self.input.execute_stream()?;
+        let mut skip = self.skip;
+        let mut fetch = self.fetch;
+
+        Ok(Box::new(std::iter::from_fn(move || loop {
+            if fetch == Some(0) {
+                return None;
+            }
+
+            let batch = match input.next()? {
+                Ok(batch) => batch,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if batch.num_rows() <= skip {
+                skip -= batch.num_rows();
+                continue;
+            }
+
+            let mut length = batch.num_rows() - skip;
+            if let Some(remaining) = fetch.as_mut() {
+                length = length.min(*remaining);
+                *remaining -= length;
+            }
+
+            let batch = batch.slice(skip, length);
+            skip = 0;
+            return Some(Ok(batch));
+        })))
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.input.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatype::field::Field;
+    use arrow::array::{ArrayRef, Int64Array};
+    use arrow::datatypes::DataType;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Returns `batches` batches of 3 rows and counts how many of them were pulled
+    struct CountingSource {
+        schema: Schema,
+        batches: i64,
+        pulled: Rc<Cell<usize>>,
+    }
+
+    impl Display for CountingSource {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "CountingSource")
+        }
+    }
+
+    impl PhysicalPlan for CountingSource {
+        fn schema(&self) -> &Schema {
+            &self.schema
+        }
+
+        fn execute(&self) -> Result<Vec<RecordBatch>> {
+            self.execute_stream()?.collect()
+        }
+
+        fn execute_stream(&self) -> Result<RecordBatchStream<'_>> {
+            Ok(Box::new((0..self.batches).map(move |i| {
+                self.pulled.set(self.pulled.get() + 1);
+                let column = Int64Array::from(vec![i * 3, i * 3 + 1, i * 3 + 2]);
+                Ok(RecordBatch::try_new(
+                    Arc::new(self.schema.clone().into()),
+                    vec![Arc::new(column)],
+                )?)
+            })))
+        }
+
+        fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+            Ok(vec![])
+        }
+    }
+
+    fn source(batches: i64, pulled: Rc<Cell<usize>>) -> PhysicalPlanRef {
+        Arc::new(CountingSource {
+            schema: Schema::new(vec![Field::new("a", DataType::Int64, false)]),
+            batches,
+            pulled,
+        })
+    }
+
+    fn values(batches: &[RecordBatch]) -> Vec<ArrayRef> {
+        batches
+            .iter()
+            .map(|batch| batch.column(0).clone())
+            .collect()
+    }
+
+    #[test]
+    fn limit_stops_pulling_input() -> Result<()> {
+        let pulled = Rc::new(Cell::new(0));
+        let limit = Limit::new(source(100, pulled.clone()), 2, Some(5));
+
+        let batches = limit.execute()?;
+
+        assert_eq!(
+            values(&batches),
+            vec![
+                Arc::new(Int64Array::from(vec![2])) as ArrayRef,
+                Arc::new(Int64Array::from(vec![3, 4, 5])) as ArrayRef,
+                Arc::new(Int64Array::from(vec![6])) as ArrayRef,
+            ]
+        );
+        assert_eq!(pulled.get(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn limit_with_offset_only() -> Result<()> {
+        let pulled = Rc::new(Cell::new(0));
+        let limit = Limit::new(source(3, pulled.clone()), 7, None);
+
+        let batches = limit.execute()?;
+
+        assert_eq!(
+            values(&batches),
+            vec![Arc::new(Int64Array::from(vec![7, 8])) as ArrayRef]
+        );
+        assert_eq!(pulled.get(), 3);
+
+        let empty = Limit::new(source(3, pulled.clone()), 0, Some(0));
+        assert!(empty.execute()?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! Runtime metrics of the physical operators, they are shown by `EXPLAIN ANALYZE`
+
+use crate::error::Result;
+use arrow::array::Array;
+use arrow::record_batch::RecordBatch;
+use std::cell::Cell;
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// Records the metrics of an operator while it is executed,
+/// the values add up over all executions of the operator
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionMetrics {
+    output_rows: Cell<usize>,
+    output_batches: Cell<usize>,
+    /// Time spent in the operator itself, without the time its inputs took
+    elapsed_compute: Cell<Duration>,
+    /// The largest amount of batch memory held by the operator at once, in bytes
+    peak_memory: Cell<usize>,
+}
+
+impl ExecutionMetrics {
+    /// Runs `f` and adds its duration to the compute time
+    pub fn time<T>(&self, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.elapsed_compute
+            .set(self.elapsed_compute.get() + start.elapsed());
+        result
+    }
+
+    /// Computes an output batch with `f` and records it
+    pub fn record(&self, f: impl FnOnce() -> Result<RecordBatch>) -> Result<RecordBatch> {
+        let batch = self.time(f)?;
+        self.record_output(&batch);
+        Ok(batch)
+    }
+
+    /// Counts an output batch, the batch is held in memory while it is produced
+    pub fn record_output(&self, batch: &RecordBatch) {
+        self.output_rows
+            .set(self.output_rows.get() + batch.num_rows());
+        self.output_batches.set(self.output_batches.get() + 1);
+        self.record_memory(batch_memory_size(batch));
+    }
+
+    /// Records that the operator holds `bytes` of memory at once
+    pub fn record_memory(&self, bytes: usize) {
+        self.peak_memory.set(self.peak_memory.get().max(bytes));
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            output_rows: self.output_rows.get(),
+            output_batches: self.output_batches.get(),
+            elapsed_compute: self.elapsed_compute.get(),
+            peak_memory: self.peak_memory.get(),
+        }
+    }
+}
+
+/// The values of `ExecutionMetrics` at some point in time
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    pub output_rows: usize,
+    pub output_batches: usize,
+    pub elapsed_compute: Duration,
+    pub peak_memory: usize,
+}
+
+impl Display for MetricsSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "output_rows={}, output_batches={}, elapsed_compute={:?}, peak_memory={} bytes",
+            self.output_rows, self.output_batches, self.elapsed_compute, self.peak_memory
+        )
+    }
+}
+
+/// The memory used by the arrays of a batch
+pub fn batch_memory_size(batch: &RecordBatch) -> usize {
+    batch
+        .columns()
+        .iter()
+        .map(|column| column.get_array_memory_size())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::datatype::scalar::Scalar;
+    use crate::logical_plan::logical_expr::Operator;
+    use crate::physical_plan::expr::binary::BinaryExpr;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::expr::literal::LiteralExpr;
+    use crate::physical_plan::scan::Scan;
+    use crate::physical_plan::selection::Selection;
+
+    #[test]
+    fn operator_metrics() -> Result<()> {
+        let source = CSVTable::try_create_table("data/test.csv")?;
+        let scan = Scan::new(source, None);
+        let selection = Selection::new(
+            scan.clone(),
+            BinaryExpr::new(
+                ColumnExpr::new(2),
+                Operator::GtEq,
+                LiteralExpr::new(Scalar::Int64(Some(24))),
+            ),
+        );
+        assert_eq!(selection.metrics(), Some(MetricsSnapshot::default()));
+
+        let batches = selection.execute_stream()?.collect::<Result<Vec<_>>>()?;
+
+        let metrics = scan.metrics().unwrap();
+        assert_eq!(metrics.output_rows, 5);
+        assert_eq!(metrics.output_batches, 1);
+        assert!(metrics.peak_memory > 0);
+
+        let metrics = selection.metrics().unwrap();
+        assert_eq!(metrics.output_rows, 3);
+        assert_eq!(metrics.output_batches, 1);
+        assert_eq!(metrics.peak_memory, batch_memory_size(&batches[0]));
+
+        // The metrics add up over executions
+        selection.execute()?;
+        assert_eq!(selection.metrics().unwrap().output_rows, 6);
+
+        Ok(())
+    }
+}
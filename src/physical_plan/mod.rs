@@ -1,8 +1,19 @@
 pub mod expr;
+#[allow(clippy::module_inception)]
 pub mod physical_plan;
 
 pub mod aggr;
+pub mod distinct;
+pub mod empty;
+pub mod explain;
+pub mod limit;
+pub mod metrics;
 pub mod nested_loop_join;
 pub mod projection;
+pub mod recursive_query;
 pub mod scan;
 pub mod selection;
+pub mod set_operation;
+pub mod sort;
+pub mod values;
+pub mod window;
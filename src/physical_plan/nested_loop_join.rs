@@ -1,62 +1,93 @@
 use std::sync::Arc;
 use std::vec;
 
-use super::expr::PhysicalExpr;
+use super::expr::PhysicalExprRef;
 use super::{
-    expr::column::ColumnExpr,
+    metrics::{batch_memory_size, ExecutionMetrics, MetricsSnapshot},
     physical_plan::{PhysicalPlan, PhysicalPlanRef},
 };
+use crate::datatype::scalar::Scalar;
 use crate::error::Result;
+use crate::logical_plan::logical_plan::JoinType;
 use crate::util::concat_batches;
 use crate::{datatype::schema::Schema, error};
 
-use arrow::array::{Array, Int64Builder, PrimitiveArray, StringArray};
+use arrow::array::{Array, ArrayRef, UInt32Array};
 use arrow::compute;
-use arrow::datatypes::{DataType, Float64Type, Int64Type, UInt64Type};
 use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
 
 pub struct NestedLoopJoin {
     left: PhysicalPlanRef,
     right: PhysicalPlanRef,
-    on: Vec<(ColumnExpr, ColumnExpr)>,
+    on: Vec<(PhysicalExprRef, PhysicalExprRef)>,
+    join_type: JoinType,
     schema: Schema,
+    metrics: ExecutionMetrics,
 }
 
 impl NestedLoopJoin {
     pub fn new(
         left: PhysicalPlanRef,
         right: PhysicalPlanRef,
-        on: Vec<(ColumnExpr, ColumnExpr)>,
+        on: Vec<(PhysicalExprRef, PhysicalExprRef)>,
+        join_type: JoinType,
         schema: Schema,
     ) -> PhysicalPlanRef {
         Arc::new(Self {
             left,
             right,
             on,
+            join_type,
             schema,
+            metrics: ExecutionMetrics::default(),
         })
     }
 }
 
-macro_rules! join_by_type {
-    ($LEFT_COL: expr, $RIGHT_COL: expr, $DT: ty, $LEFT_FLAGS: expr, $RIGHT_FLAGS: expr, $Index: expr) => {{
-        let left_col = $LEFT_COL.as_any().downcast_ref::<$DT>().unwrap();
-        let right_col = $RIGHT_COL.as_any().downcast_ref::<$DT>().unwrap();
-
-        for (left_pos, left_val) in left_col.iter().enumerate() {
-            for (right_pos, right_val) in right_col.iter().enumerate() {
-                match (left_val, right_val) {
-                    (Some(left_val), Some(right_val)) => {
-                        if left_val == right_val {
-                            $LEFT_FLAGS[$Index][left_pos] = true;
-                            $RIGHT_FLAGS[$Index][right_pos] = true;
-                        }
-                    }
-                    _ => {}
-                }
+/// The join keys of one row, `None` if the key is NULL
+fn row_keys(columns: &[ArrayRef], row: usize) -> Result<Vec<Option<Scalar>>> {
+    columns
+        .iter()
+        .map(|column| {
+            if column.is_null(row) {
+                Ok(None)
+            } else {
+                Scalar::try_from_array(column, row).map(Some)
             }
-        }
-    }};
+        })
+        .collect()
+}
+
+impl NestedLoopJoin {
+    /// Whether a pair of rows satisfies the join condition. NULL never equals anything,
+    /// except for the first key of a null-aware anti join, where it means "unknown" and
+    /// is treated as a match so that the left row is removed.
+    fn keys_match(&self, left: &[Option<Scalar>], right: &[Option<Scalar>]) -> bool {
+        left.iter()
+            .zip(right.iter())
+            .enumerate()
+            .all(|(i, keys)| match keys {
+                (Some(l), Some(r)) => l == r,
+                _ => i == 0 && matches!(self.join_type, JoinType::NullAwareAnti),
+            })
+    }
+}
+
+impl Display for NestedLoopJoin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let on = self
+            .on
+            .iter()
+            .map(|(left, right)| format!("{} = {}", left, right))
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "NestedLoopJoin: type={:?}, on=[{}]",
+            self.join_type,
+            on.join(", ")
+        )
+    }
 }
 
 impl PhysicalPlan for NestedLoopJoin {
@@ -64,111 +95,113 @@ impl PhysicalPlan for NestedLoopJoin {
         &self.schema
     }
 
+    /// Compares every row of the left input with every row of the right input,
+    /// an empty `on` joins every pair of rows
     fn execute(&self) -> Result<Vec<RecordBatch>> {
-        if self.on.is_empty() {
-            return Err(error::Error::PhysicalPlanError(
-                "`on` cannot be empty when executing nested loop join".to_string(),
-            ));
-        }
-
         let outer_table = self.left.execute()?;
-        let outer_table =
-            concat_batches(&Arc::new(self.left.schema().clone().into()), &outer_table)?;
-
         let inner_table = self.right.execute()?;
-        let inner_table =
-            concat_batches(&Arc::new(self.right.schema().clone().into()), &inner_table)?;
+        let batch = self
+            .metrics
+            .record(|| self.join(&outer_table, &inner_table))?;
+
+        Ok(vec![batch])
+    }
 
-        let mut outer_pos = Int64Builder::new(0);
-        let mut inner_pos = Int64Builder::new(0);
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.left.clone(), self.right.clone()])
+    }
 
-        let mut left_flags = Vec::<Vec<bool>>::new();
-        let mut right_flags = Vec::<Vec<bool>>::new();
+    fn metrics(&self) -> Option<MetricsSnapshot> {
+        Some(self.metrics.snapshot())
+    }
+}
 
-        for (i, (left_col, right_col)) in self.on.iter().enumerate() {
-            let left_col = left_col.evaluate(&outer_table)?.to_array();
-            let right_col = right_col.evaluate(&inner_table)?.to_array();
+impl NestedLoopJoin {
+    /// Joins the batches of both inputs, which are held in memory together
+    fn join(
+        &self,
+        outer_table: &[RecordBatch],
+        inner_table: &[RecordBatch],
+    ) -> Result<RecordBatch> {
+        let outer_table =
+            concat_batches(&Arc::new(self.left.schema().clone().into()), outer_table)?;
+        let inner_table =
+            concat_batches(&Arc::new(self.right.schema().clone().into()), inner_table)?;
+        self.metrics
+            .record_memory(batch_memory_size(&outer_table) + batch_memory_size(&inner_table));
 
-            left_flags.push(vec![false; left_col.len()]);
-            right_flags.push(vec![false; right_col.len()]);
+        let mut left_cols = vec![];
+        let mut right_cols = vec![];
 
-            let left_dt = left_col.data_type();
-            let right_dt = right_col.data_type();
+        for (left_col, right_col) in self.on.iter() {
+            let left_col = left_col.evaluate(&outer_table)?.to_array();
+            let right_col = right_col.evaluate(&inner_table)?.to_array();
 
-            if left_dt != right_dt {
-                return Err(error::Error::PhysicalPlanError(
-                    "Left and right types of on should match".to_string(),
-                ));
+            if left_col.data_type() != right_col.data_type() {
+                return Err(error::Error::PhysicalPlanError(format!(
+                    "Left and right types of on should match, found {:?} and {:?}",
+                    left_col.data_type(),
+                    right_col.data_type()
+                )));
             }
 
-            match left_dt {
-                DataType::Int64 => join_by_type!(
-                    left_col,
-                    right_col,
-                    PrimitiveArray<Int64Type>,
-                    &mut left_flags,
-                    &mut right_flags,
-                    i
-                ),
-                DataType::UInt64 => join_by_type!(
-                    left_col,
-                    right_col,
-                    PrimitiveArray<UInt64Type>,
-                    &mut left_flags,
-                    &mut right_flags,
-                    i
-                ),
-                DataType::Float64 => join_by_type!(
-                    left_col,
-                    right_col,
-                    PrimitiveArray<Float64Type>,
-                    &mut left_flags,
-                    &mut right_flags,
-                    i
-                ),
-                DataType::Utf8 => join_by_type!(
-                    left_col,
-                    right_col,
-                    StringArray,
-                    &mut left_flags,
-                    &mut right_flags,
-                    i
-                ),
-                _ => unimplemented!(),
-            }
+            left_cols.push(left_col);
+            right_cols.push(right_col);
         }
 
-        let left_column_len = left_flags[0].len();
+        let right_keys = (0..inner_table.num_rows())
+            .map(|row| row_keys(&right_cols, row))
+            .collect::<Result<Vec<_>>>()?;
 
-        for left_pos in 0..left_column_len {
-            let mut flag = true;
-            for left_flag in left_flags.iter() {
-                if !left_flag[left_pos] {
-                    flag = false;
-                    break;
+        let mut outer_pos = vec![];
+        let mut inner_pos = vec![];
+        let mut right_matched = vec![false; inner_table.num_rows()];
+
+        for left_row in 0..outer_table.num_rows() {
+            let left_keys = row_keys(&left_cols, left_row)?;
+            let mut matched = false;
+
+            for (right_row, keys) in right_keys.iter().enumerate() {
+                if !self.keys_match(&left_keys, keys) {
+                    continue;
                 }
-            }
+                matched = true;
+                right_matched[right_row] = true;
 
-            if flag {
-                outer_pos.append_value(left_pos as i64)?;
+                match self.join_type {
+                    JoinType::Inner | JoinType::Left | JoinType::Right => {
+                        outer_pos.push(Some(left_row as u32));
+                        inner_pos.push(Some(right_row as u32));
+                    }
+                    // A left row is returned at most once and only its columns are needed
+                    JoinType::Semi | JoinType::Anti | JoinType::NullAwareAnti => break,
+                }
             }
-        }
-
-        let right_column_len = right_flags[0].len();
 
-        for right_pos in 0..right_column_len {
-            let mut flag = false;
-            for right_flag in right_flags.iter() {
-                flag = right_flag[right_pos];
+            match self.join_type {
+                JoinType::Left if !matched => {
+                    outer_pos.push(Some(left_row as u32));
+                    inner_pos.push(None);
+                }
+                JoinType::Semi if matched => outer_pos.push(Some(left_row as u32)),
+                JoinType::Anti | JoinType::NullAwareAnti if !matched => {
+                    outer_pos.push(Some(left_row as u32))
+                }
+                _ => {}
             }
+        }
 
-            if flag {
-                inner_pos.append_value(right_pos as i64)?;
+        if let JoinType::Right = self.join_type {
+            for (right_row, matched) in right_matched.iter().enumerate() {
+                if !matched {
+                    outer_pos.push(None);
+                    inner_pos.push(Some(right_row as u32));
+                }
             }
         }
 
-        let outer_pos = outer_pos.finish();
-        let inner_pos = inner_pos.finish();
+        let outer_pos = UInt32Array::from(outer_pos);
+        let inner_pos = UInt32Array::from(inner_pos);
 
         let mut columns = vec![];
 
@@ -176,17 +209,19 @@ impl PhysicalPlan for NestedLoopJoin {
             columns.push(compute::take(outer_table.column(i), &outer_pos, None)?);
         }
 
-        for i in 0..self.right.schema().fields().len() {
-            columns.push(compute::take(inner_table.column(i), &inner_pos, None)?);
+        if !matches!(
+            self.join_type,
+            JoinType::Semi | JoinType::Anti | JoinType::NullAwareAnti
+        ) {
+            for i in 0..self.right.schema().fields().len() {
+                columns.push(compute::take(inner_table.column(i), &inner_pos, None)?);
+            }
         }
 
-        let batch = RecordBatch::try_new(Arc::new(self.schema().clone().into()), columns)?;
-
-        Ok(vec![batch])
-    }
-
-    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
-        Ok(vec![self.left.clone(), self.right.clone()])
+        Ok(RecordBatch::try_new(
+            Arc::new(self.schema().clone().into()),
+            columns,
+        )?)
     }
 }
 
@@ -196,7 +231,15 @@ mod tests {
     use arrow::util::pretty;
 
     use super::*;
-    use crate::{datasource::csv_table::CSVTable, error::Result, physical_plan::scan::Scan};
+    use crate::datatype::field::Field;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::selection::Selection;
+    use crate::{
+        datasource::csv_table::CSVTable,
+        error::Result,
+        logical_plan::logical_expr::Operator,
+        physical_plan::{expr::binary::BinaryExpr, expr::literal::LiteralExpr, scan::Scan},
+    };
 
     #[test]
     fn test_nested_loop_join() -> Result<()> {
@@ -206,29 +249,14 @@ mod tests {
         let test_source_scan = Scan::new(test_source.clone(), None);
         let salary_source_scan = Scan::new(salary_source.clone(), None);
 
-        let id_column = ColumnExpr::new(0)
-            .as_any()
-            .downcast_ref::<ColumnExpr>()
-            .unwrap()
-            .clone();
+        let id_column = ColumnExpr::new(0);
+        let name_column = ColumnExpr::new(1);
 
-        let name_column = ColumnExpr::new(1)
-            .as_any()
-            .downcast_ref::<ColumnExpr>()
-            .unwrap()
-            .clone();
-
-        let fields = vec![
+        let fields = [
             test_source.schema().fields().clone(),
             salary_source.schema().fields().clone(),
         ];
-        let schema = Schema::new(
-            fields
-                .iter()
-                .flatten()
-                .map(|field| field.clone())
-                .collect::<Vec<_>>(),
-        );
+        let schema = Schema::new(fields.iter().flatten().cloned().collect::<Vec<_>>());
 
         let nested_loop_join = NestedLoopJoin::new(
             test_source_scan.clone(),
@@ -237,6 +265,7 @@ mod tests {
                 (id_column.clone(), id_column.clone()),
                 (name_column.clone(), name_column.clone()),
             ],
+            JoinType::Inner,
             schema.clone(),
         );
 
@@ -244,6 +273,78 @@ mod tests {
 
         pretty::print_batches(&batch)?;
 
+        // Only the first row has the same id and name in both tables
+        assert_eq!(batch[0].num_rows(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn outer_semi_and_anti_joins() -> Result<()> {
+        let test_source = CSVTable::try_create_table("data/test.csv")?;
+        let salary_source = CSVTable::try_create_table("data/salary.csv")?;
+
+        // salary rows with id > 3
+        let salary = Selection::new(
+            Scan::new(salary_source.clone(), None),
+            BinaryExpr::new(
+                ColumnExpr::new(0),
+                Operator::Gt,
+                LiteralExpr::new(Scalar::Int64(Some(3))),
+            ),
+        );
+
+        let joined_schema = Schema::new(
+            test_source
+                .schema()
+                .fields()
+                .iter()
+                .chain(salary_source.schema().fields().iter())
+                .map(|field| Field::new(field.name(), field.data_type().clone(), true))
+                .collect(),
+        );
+
+        let join = |join_type: JoinType, schema: &Schema| {
+            NestedLoopJoin::new(
+                Scan::new(test_source.clone(), None),
+                salary.clone(),
+                vec![(ColumnExpr::new(0), ColumnExpr::new(0))],
+                join_type,
+                schema.clone(),
+            )
+            .execute()
+        };
+
+        let left = join(JoinType::Left, &joined_schema)?;
+        assert_eq!(left[0].num_rows(), 5);
+        assert_eq!(left[0].column(4).null_count(), 3);
+
+        let inner = join(JoinType::Inner, &joined_schema)?;
+        assert_eq!(inner[0].num_rows(), 2);
+
+        let semi = join(JoinType::Semi, test_source.schema())?;
+        assert_eq!(
+            semi[0].column(0),
+            &(Arc::new(arrow::array::Int64Array::from(vec![4, 5])) as ArrayRef)
+        );
+
+        let anti = join(JoinType::Anti, test_source.schema())?;
+        assert_eq!(
+            anti[0].column(0),
+            &(Arc::new(arrow::array::Int64Array::from(vec![1, 2, 3])) as ArrayRef)
+        );
+
+        // Without join keys every pair of rows is joined
+        let cross = NestedLoopJoin::new(
+            Scan::new(test_source.clone(), None),
+            salary,
+            vec![],
+            JoinType::Inner,
+            joined_schema,
+        )
+        .execute()?;
+        assert_eq!(cross[0].num_rows(), 10);
+
         Ok(())
     }
 }
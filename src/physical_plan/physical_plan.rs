@@ -1,14 +1,47 @@
 use crate::datatype::schema::Schema;
 use crate::error::Result;
+use crate::physical_plan::metrics::MetricsSnapshot;
 use arrow::record_batch::RecordBatch;
+use std::fmt::Display;
 use std::sync::Arc;
 
 pub type PhysicalPlanRef = Arc<dyn PhysicalPlan>;
 
-pub trait PhysicalPlan {
+/// An iterator over record batches, batches are only computed when they are pulled
+pub type RecordBatchStream<'a> = Box<dyn Iterator<Item = Result<RecordBatch>> + 'a>;
+
+/// `Display` describes the node without its children in a single line
+pub trait PhysicalPlan: Display {
     fn schema(&self) -> &Schema;
 
     fn execute(&self) -> Result<Vec<RecordBatch>>;
 
+    /// Returns the output as a stream of batches, so that the consumer can stop early.
+    /// By default all batches are computed by `execute` up front.
+    fn execute_stream(&self) -> Result<RecordBatchStream<'_>> {
+        Ok(Box::new(self.execute()?.into_iter().map(Ok)))
+    }
+
     fn children(&self) -> Result<Vec<PhysicalPlanRef>>;
+
+    /// The metrics recorded while the node was executed, `None` if the node records none
+    fn metrics(&self) -> Option<MetricsSnapshot> {
+        None
+    }
+
+    /// Returns the plan with one node per line, the children of a node are indented below it.
+    /// With `with_metrics` each node is followed by its metrics, if it records any.
+    fn display_indent(&self, with_metrics: bool) -> Result<String> {
+        let mut output = self.to_string();
+        if let (true, Some(metrics)) = (with_metrics, self.metrics()) {
+            output.push_str(&format!(", metrics=[{}]", metrics));
+        }
+        for child in self.children()? {
+            for line in child.display_indent(with_metrics)?.lines() {
+                output.push_str("\n  ");
+                output.push_str(line);
+            }
+        }
+        Ok(output)
+    }
 }
@@ -1,16 +1,18 @@
-use crate::datatype::column_array::ColumnArray;
 use crate::datatype::schema::Schema;
 use crate::error::Result;
-use crate::physical_plan::expr::PhysicalExprRef;
-use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use crate::physical_plan::expr::{format_exprs, PhysicalExprRef};
+use crate::physical_plan::metrics::{ExecutionMetrics, MetricsSnapshot};
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef, RecordBatchStream};
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
 
 pub struct Projection {
     input: PhysicalPlanRef,
     schema: Schema,
     expr: Vec<PhysicalExprRef>,
+    metrics: ExecutionMetrics,
 }
 
 impl Projection {
@@ -23,8 +25,28 @@ impl Projection {
             input,
             schema,
             expr,
+            metrics: ExecutionMetrics::default(),
         })
     }
+
+    /// Evaluates the projection expressions on a single batch
+    fn project(&self, record_batch: &RecordBatch) -> Result<RecordBatch> {
+        let columns = self
+            .expr
+            .iter()
+            .map(|expr| Ok(expr.evaluate(record_batch)?.to_array()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(
+            SchemaRef::from(self.schema.clone()),
+            columns,
+        )?)
+    }
+}
+
+impl Display for Projection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Projection: {}", format_exprs(&self.expr))
+    }
 }
 
 impl PhysicalPlan for Projection {
@@ -33,32 +55,29 @@ impl PhysicalPlan for Projection {
     }
 
     fn execute(&self) -> Result<Vec<RecordBatch>> {
-        let input = self.input.execute()?;
-
-        if self.schema.fields().is_empty() {
-            Ok(input)
-        } else {
-            let batches = input
-                .iter()
-                .map(|record_batch| {
-                    let columns = self
-                        .expr
-                        .iter()
-                        .map(|expr| expr.evaluate(record_batch).unwrap())
-                        .collect::<Vec<ColumnArray>>()
-                        .iter()
-                        .map(|column_array| column_array.clone().to_array())
-                        .collect::<Vec<_>>();
-                    RecordBatch::try_new(SchemaRef::from(self.schema.clone()), columns).unwrap()
-                })
-                .collect::<Vec<_>>();
-            Ok(batches)
-        }
+        self.execute_stream()?.collect()
+    }
+
+    fn execute_stream(&self) -> Result<RecordBatchStream<'_>> {
+        let input = self.input.execute_stream()?;
+
+        Ok(Box::new(input.map(move |record_batch| {
+            let record_batch = record_batch?;
+            if self.schema.fields().is_empty() {
+                self.metrics.record(|| Ok(record_batch))
+            } else {
+                self.metrics.record(|| self.project(&record_batch))
+            }
+        })))
     }
 
     fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
         Ok(vec![self.input.clone()])
     }
+
+    fn metrics(&self) -> Option<MetricsSnapshot> {
+        Some(self.metrics.snapshot())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,247 @@
+use crate::datatype::schema::Schema;
+use crate::error::{Error, Result};
+use crate::physical_plan::distinct::remove_seen_rows;
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Holds the rows produced by the previous iteration of a recursive query
+#[derive(Default)]
+pub struct WorkTable {
+    batches: RefCell<Vec<RecordBatch>>,
+}
+
+pub type WorkTableRef = Rc<WorkTable>;
+
+impl WorkTable {
+    pub fn new() -> WorkTableRef {
+        Rc::new(Self::default())
+    }
+}
+
+/// Reads the rows of a work table, it is the reference to a recursive CTE in its recursive term
+pub struct WorkTableScan {
+    work_table: WorkTableRef,
+    schema: Schema,
+}
+
+impl WorkTableScan {
+    pub fn new(work_table: WorkTableRef, schema: Schema) -> PhysicalPlanRef {
+        Arc::new(Self { work_table, schema })
+    }
+}
+
+impl Display for WorkTableScan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "WorkTableScan")
+    }
+}
+
+impl PhysicalPlan for WorkTableScan {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        Ok(self.work_table.batches.borrow().clone())
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![])
+    }
+}
+
+/// Evaluates a recursive CTE. The static term is executed once, then the recursive term is
+/// executed with the rows of the previous iteration in the work table, until an iteration
+/// produces no new rows. With `distinct` (UNION instead of UNION ALL), rows that were already
+/// returned are not returned again and do not take part in the next iteration.
+pub struct RecursiveQuery {
+    static_term: PhysicalPlanRef,
+    recursive_term: PhysicalPlanRef,
+    work_table: WorkTableRef,
+    distinct: bool,
+    /// An error is returned if the recursion does not end within this number of iterations
+    max_iterations: usize,
+    schema: Schema,
+}
+
+impl RecursiveQuery {
+    pub fn new(
+        static_term: PhysicalPlanRef,
+        recursive_term: PhysicalPlanRef,
+        work_table: WorkTableRef,
+        distinct: bool,
+        max_iterations: usize,
+        schema: Schema,
+    ) -> PhysicalPlanRef {
+        Arc::new(Self {
+            static_term,
+            recursive_term,
+            work_table,
+            distinct,
+            max_iterations,
+            schema,
+        })
+    }
+
+    /// The terms name their columns differently, their batches are converted to the output schema
+    fn to_output(&self, schema: &SchemaRef, batches: Vec<RecordBatch>) -> Result<Vec<RecordBatch>> {
+        batches
+            .into_iter()
+            .filter(|batch| batch.num_rows() > 0)
+            .map(|batch| {
+                Ok(RecordBatch::try_new(
+                    schema.clone(),
+                    batch.columns().to_vec(),
+                )?)
+            })
+            .collect()
+    }
+}
+
+impl Display for RecursiveQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RecursiveQuery: distinct={}, max_iterations={}",
+            self.distinct, self.max_iterations
+        )
+    }
+}
+
+impl PhysicalPlan for RecursiveQuery {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let schema: SchemaRef = Arc::new(self.schema.clone().into());
+        let mut seen = HashSet::new();
+        let mut output = vec![];
+
+        let mut batches = self.to_output(&schema, self.static_term.execute()?)?;
+        let mut iterations = 0;
+
+        loop {
+            if self.distinct {
+                batches = batches
+                    .iter()
+                    .map(|batch| remove_seen_rows(batch, &mut seen))
+                    .filter(|batch| !matches!(batch, Ok(batch) if batch.num_rows() == 0))
+                    .collect::<Result<Vec<_>>>()?;
+            }
+
+            if batches.is_empty() {
+                break;
+            }
+
+            if iterations == self.max_iterations {
+                return Err(Error::PhysicalPlanError(format!(
+                    "Recursive query did not finish within {} iterations",
+                    self.max_iterations
+                )));
+            }
+            iterations += 1;
+
+            output.extend(batches.iter().cloned());
+            *self.work_table.batches.borrow_mut() = batches;
+            batches = self.to_output(&schema, self.recursive_term.execute()?)?;
+        }
+
+        self.work_table.batches.borrow_mut().clear();
+
+        if output.is_empty() {
+            return Ok(vec![RecordBatch::new_empty(schema)]);
+        }
+        Ok(output)
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.static_term.clone(), self.recursive_term.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::datatype::scalar::Scalar;
+    use crate::logical_plan::logical_expr::Operator;
+    use crate::logical_plan::logical_plan::JoinType;
+    use crate::physical_plan::expr::binary::BinaryExpr;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::expr::literal::LiteralExpr;
+    use crate::physical_plan::nested_loop_join::NestedLoopJoin;
+    use crate::physical_plan::projection::Projection;
+    use crate::physical_plan::scan::Scan;
+    use crate::physical_plan::selection::Selection;
+    use arrow::array::{ArrayRef, Int64Array};
+
+    /// Employees reporting to employee 2, directly or indirectly
+    fn reports(distinct: bool, max_iterations: usize) -> Result<Vec<RecordBatch>> {
+        let employee = CSVTable::try_create_table("data/employee.csv")?;
+        let schema = employee.schema().clone();
+
+        let static_term = Selection::new(
+            Scan::new(employee.clone(), None),
+            BinaryExpr::new(
+                ColumnExpr::new(0),
+                Operator::Eq,
+                LiteralExpr::new(Scalar::Int64(Some(2))),
+            ),
+        );
+
+        let work_table = WorkTable::new();
+        let join = NestedLoopJoin::new(
+            WorkTableScan::new(work_table.clone(), schema.clone()),
+            Scan::new(employee, None),
+            vec![(ColumnExpr::new(0), ColumnExpr::new(2))],
+            JoinType::Inner,
+            schema.join(&schema),
+        );
+        let recursive_term = Projection::new(
+            join,
+            schema.clone(),
+            vec![ColumnExpr::new(3), ColumnExpr::new(4), ColumnExpr::new(5)],
+        );
+
+        RecursiveQuery::new(
+            static_term,
+            recursive_term,
+            work_table,
+            distinct,
+            max_iterations,
+            schema,
+        )
+        .execute()
+    }
+
+    #[test]
+    fn recursive_query_physical_plan() -> Result<()> {
+        let batches = reports(false, 10)?;
+
+        let ids = batches
+            .iter()
+            .map(|batch| batch.column(0).clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![
+                Arc::new(Int64Array::from(vec![2])) as ArrayRef,
+                Arc::new(Int64Array::from(vec![4])) as ArrayRef,
+                Arc::new(Int64Array::from(vec![5])) as ArrayRef,
+            ]
+        );
+
+        // Three iterations are needed, the fourth one produces no rows
+        assert!(reports(true, 3).is_ok());
+        assert!(reports(true, 2).is_err());
+
+        Ok(())
+    }
+}
@@ -1,19 +1,45 @@
 use crate::datasource::table::TableRef;
 use crate::datatype::schema::Schema;
 use crate::error::Result;
-use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use crate::physical_plan::metrics::{ExecutionMetrics, MetricsSnapshot};
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef, RecordBatchStream};
 use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct Scan {
     source: TableRef,
     projection: Option<Vec<usize>>,
+    metrics: ExecutionMetrics,
 }
 
 impl Scan {
     pub fn new(source: TableRef, projection: Option<Vec<usize>>) -> PhysicalPlanRef {
-        Arc::new(Self { source, projection })
+        Arc::new(Self {
+            source,
+            projection,
+            metrics: ExecutionMetrics::default(),
+        })
+    }
+}
+
+impl Display for Scan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let fields = self.source.schema().fields();
+        let names = match &self.projection {
+            Some(projection) => projection
+                .iter()
+                .map(|i| fields[*i].name().clone())
+                .collect::<Vec<_>>(),
+            None => fields.iter().map(|field| field.name().clone()).collect(),
+        };
+        write!(
+            f,
+            "Scan: {} [{}]",
+            self.source.source_type(),
+            names.join(", ")
+        )
     }
 }
 
@@ -24,13 +50,37 @@ impl PhysicalPlan for Scan {
 
     /// Gets data from the specified data source
     fn execute(&self) -> Result<Vec<RecordBatch>> {
-        self.source.scan(self.projection.clone())
+        let batches = self
+            .metrics
+            .time(|| self.source.scan(self.projection.clone()))?;
+        for batch in &batches {
+            self.metrics.record_output(batch);
+        }
+        Ok(batches)
+    }
+
+    /// Gets data from the specified data source one batch at a time
+    fn execute_stream(&self) -> Result<RecordBatchStream<'_>> {
+        let mut stream = self
+            .metrics
+            .time(|| self.source.scan_stream(self.projection.clone()))?;
+        Ok(Box::new(std::iter::from_fn(move || {
+            let batch = self.metrics.time(|| stream.next())?;
+            if let Ok(batch) = &batch {
+                self.metrics.record_output(batch);
+            }
+            Some(batch)
+        })))
     }
 
     /// Scan physical plan has no child nodes
     fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
         Ok(vec![])
     }
+
+    fn metrics(&self) -> Option<MetricsSnapshot> {
+        Some(self.metrics.snapshot())
+    }
 }
 
 #[cfg(test)]
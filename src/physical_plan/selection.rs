@@ -1,8 +1,9 @@
 use crate::datatype::schema::Schema;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::physical_plan::{
     expr::PhysicalExprRef,
-    physical_plan::{PhysicalPlan, PhysicalPlanRef},
+    metrics::{batch_memory_size, ExecutionMetrics, MetricsSnapshot},
+    physical_plan::{PhysicalPlan, PhysicalPlanRef, RecordBatchStream},
 };
 use crate::util::concat_batches;
 use arrow::array::{
@@ -12,94 +13,143 @@ use arrow::array::{
 use arrow::datatypes::DataType;
 use arrow::{
     array::{Array, BooleanArray},
-    record_batch::RecordBatch,
+    record_batch::{RecordBatch, RecordBatchOptions},
 };
+use std::fmt::{self, Display, Formatter};
 use std::{sync::Arc, vec};
 
 pub struct Selection {
     pub input: PhysicalPlanRef,
     pub expr: PhysicalExprRef,
+    metrics: ExecutionMetrics,
 }
 
 impl Selection {
     pub fn new(input: PhysicalPlanRef, expr: PhysicalExprRef) -> PhysicalPlanRef {
-        Arc::new(Self { input, expr })
+        Arc::new(Self {
+            input,
+            expr,
+            metrics: ExecutionMetrics::default(),
+        })
     }
 }
 
 macro_rules! build_array_by_predicate {
     // $PREDICATE represent the result of predicate select on a column
     // $COLUMN represent the column waiting to be selected
-    ($COLUMN: expr, $PREDICATE: expr, $ARRAY_TYPE: ty, $ARRAY_BUILDER: ty, $TYPE: ty) => {{
+    ($COLUMN: expr, $PREDICATE: expr, $ARRAY_TYPE: ty, $ARRAY_BUILDER: ty) => {{
         let array = $COLUMN.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
         let mut builder = <$ARRAY_BUILDER>::new(array.len());
         let iter = $PREDICATE.iter().zip(array.iter());
         for (valid, val) in iter {
-            match valid {
-                Some(valid) => {
-                    // If this item of the column meets the selection criteria, add it to the new Aarry
-                    if valid {
-                        builder.append_option(val)?;
-                    }
-                }
-                None => builder.append_option(None::<$TYPE>)?,
+            // If this item of the column meets the selection criteria, add it to the new Aarry,
+            // a NULL predicate is not satisfied
+            if valid == Some(true) {
+                builder.append_option(val)?;
             }
         }
         Arc::new(builder.finish())
     }};
 }
 
-impl PhysicalPlan for Selection {
-    fn schema(&self) -> &Schema {
-        self.input.schema()
-    }
-
-    fn execute(&self) -> Result<Vec<RecordBatch>> {
-        let input = self.input.execute()?;
-        let input = &concat_batches(&self.schema().clone().into(), &input)?;
-
+impl Selection {
+    /// Keeps the rows of the batch that satisfy the predicate
+    fn filter(&self, input: &RecordBatch) -> Result<RecordBatch> {
         let predicates = self.expr.evaluate(input)?.to_array();
-        let predicates = predicates.as_any().downcast_ref::<BooleanArray>().unwrap();
-
-        let mut batches = vec![];
+        let predicates = predicates
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .ok_or_else(|| {
+                Error::PhysicalPlanError(format!(
+                    "The predicate {} is {:?}, but a selection needs a Boolean",
+                    self.expr,
+                    predicates.data_type()
+                ))
+            })?;
 
         let mut columns = vec![];
         for column in input.columns() {
             let data_type = column.data_type();
             let array_ref: ArrayRef = match data_type {
-                DataType::Boolean => build_array_by_predicate!(
-                    column,
-                    predicates,
-                    BooleanArray,
-                    BooleanBuilder,
-                    bool
-                ),
+                DataType::Boolean => {
+                    build_array_by_predicate!(column, predicates, BooleanArray, BooleanBuilder)
+                }
                 DataType::Int64 => {
-                    build_array_by_predicate!(column, predicates, Int64Array, Int64Builder, i64)
+                    build_array_by_predicate!(column, predicates, Int64Array, Int64Builder)
                 }
                 DataType::UInt64 => {
-                    build_array_by_predicate!(column, predicates, UInt64Array, UInt64Builder, u64)
+                    build_array_by_predicate!(column, predicates, UInt64Array, UInt64Builder)
                 }
                 DataType::Float64 => {
-                    build_array_by_predicate!(column, predicates, Float64Array, Float64Builder, f64)
+                    build_array_by_predicate!(column, predicates, Float64Array, Float64Builder)
                 }
                 DataType::Utf8 => {
-                    build_array_by_predicate!(column, predicates, StringArray, StringBuilder, &str)
+                    build_array_by_predicate!(column, predicates, StringArray, StringBuilder)
+                }
+                data_type => {
+                    return Err(Error::NotImplemented(format!(
+                        "Selection of a column of type {:?}",
+                        data_type
+                    )))
                 }
-                _ => unimplemented!(),
             };
             columns.push(array_ref);
         }
 
-        let record_batch = RecordBatch::try_new(Arc::new(self.schema().clone().into()), columns)?;
-        batches.push(record_batch);
+        // The row count is needed for an input without columns
+        let mut options = RecordBatchOptions::default();
+        options.row_count = Some(
+            predicates
+                .iter()
+                .filter(|valid| *valid == Some(true))
+                .count(),
+        );
+        Ok(RecordBatch::try_new_with_options(
+            Arc::new(self.schema().clone().into()),
+            columns,
+            &options,
+        )?)
+    }
+}
 
-        Ok(batches)
+impl Display for Selection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Selection: {}", self.expr)
+    }
+}
+
+impl PhysicalPlan for Selection {
+    fn schema(&self) -> &Schema {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let input = self.input.execute()?;
+        let batch = self.metrics.record(|| {
+            let input = &concat_batches(&self.schema().clone().into(), &input)?;
+            self.metrics.record_memory(batch_memory_size(input));
+            self.filter(input)
+        })?;
+
+        Ok(vec![batch])
+    }
+
+    /// Filters each batch of the input as it is pulled
+    fn execute_stream(&self) -> Result<RecordBatchStream<'_>> {
+        let input = self.input.execute_stream()?;
+        Ok(Box::new(input.map(move |batch| {
+            let batch = batch?;
+            self.metrics.record(|| self.filter(&batch))
+        })))
     }
 
     fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
         Ok(vec![self.input.clone()])
     }
+
+    fn metrics(&self) -> Option<MetricsSnapshot> {
+        Some(self.metrics.snapshot())
+    }
 }
 
 #[cfg(test)]
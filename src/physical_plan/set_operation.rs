@@ -0,0 +1,358 @@
+use crate::datatype::scalar::Scalar;
+use crate::datatype::schema::Schema;
+use crate::error::Result;
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef, RecordBatchStream};
+use arrow::array::UInt32Array;
+use arrow::compute;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Converts a batch of one input of a set operation to the output schema,
+/// the columns are cast to the common type of both inputs
+fn to_output(schema: &SchemaRef, batch: &RecordBatch) -> Result<RecordBatch> {
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(column, field)| {
+            if column.data_type() == field.data_type() {
+                Ok(column.clone())
+            } else {
+                compute::cast(column, field.data_type())
+            }
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// The values of one row, NULLs are equal to each other
+fn row(batch: &RecordBatch, i: usize) -> Result<Vec<Scalar>> {
+    batch
+        .columns()
+        .iter()
+        .map(|column| Scalar::try_from_array(column, i))
+        .collect()
+}
+
+/// Counts how many times each row is in the output of `plan`
+fn row_counts(schema: &SchemaRef, plan: &PhysicalPlanRef) -> Result<HashMap<Vec<Scalar>, usize>> {
+    let mut counts = HashMap::new();
+    for batch in plan.execute()? {
+        let batch = to_output(schema, &batch)?;
+        for i in 0..batch.num_rows() {
+            *counts.entry(row(&batch, i)?).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Keeps the rows of the batch for which `keep` returns true
+fn filter_rows(
+    batch: &RecordBatch,
+    mut keep: impl FnMut(Vec<Scalar>) -> bool,
+) -> Result<RecordBatch> {
+    let mut indices = vec![];
+    for i in 0..batch.num_rows() {
+        if keep(row(batch, i)?) {
+            indices.push(i as u32);
+        }
+    }
+
+    let indices = UInt32Array::from(indices);
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|column| compute::take(column.as_ref(), &indices, None))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(RecordBatch::try_new(batch.schema(), columns)?)
+}
+
+/// Returns the rows of the left input followed by the rows of the right input,
+/// duplicates are kept
+pub struct Union {
+    left: PhysicalPlanRef,
+    right: PhysicalPlanRef,
+    schema: Schema,
+}
+
+impl Union {
+    pub fn new(left: PhysicalPlanRef, right: PhysicalPlanRef, schema: Schema) -> PhysicalPlanRef {
+        Arc::new(Self {
+            left,
+            right,
+            schema,
+        })
+    }
+}
+
+impl Display for Union {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Union")
+    }
+}
+
+impl PhysicalPlan for Union {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        self.execute_stream()?.collect()
+    }
+
+    /// The right input is only executed after all batches of the left input are pulled
+    fn execute_stream(&self) -> Result<RecordBatchStream<'_>> {
+        let schema: SchemaRef = Arc::new(self.schema.clone().into());
+        let left = self.left.execute_stream()?;
+        let right = std::iter::once_with(move || self.right.execute_stream()).flat_map(
+            |stream| -> RecordBatchStream<'_> {
+                match stream {
+                    Ok(stream) => stream,
+                    Err(e) => Box::new(std::iter::once(Err(e))),
+                }
+            },
+        );
+
+        Ok(Box::new(
+            left.chain(right)
+                .map(move |batch| to_output(&schema, &batch?)),
+        ))
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.left.clone(), self.right.clone()])
+    }
+}
+
+/// Returns the rows of the left input that are also in the right input, in left input order
+pub struct Intersect {
+    left: PhysicalPlanRef,
+    right: PhysicalPlanRef,
+    /// A row is returned as many times as it is in both inputs rather than once
+    all: bool,
+    schema: Schema,
+}
+
+impl Intersect {
+    pub fn new(
+        left: PhysicalPlanRef,
+        right: PhysicalPlanRef,
+        all: bool,
+        schema: Schema,
+    ) -> PhysicalPlanRef {
+        Arc::new(Self {
+            left,
+            right,
+            all,
+            schema,
+        })
+    }
+}
+
+impl Display for Intersect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Intersect: all={}", self.all)
+    }
+}
+
+impl PhysicalPlan for Intersect {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let schema: SchemaRef = Arc::new(self.schema.clone().into());
+        let mut counts = row_counts(&schema, &self.right)?;
+
+        self.left
+            .execute()?
+            .iter()
+            .map(|batch| {
+                filter_rows(&to_output(&schema, batch)?, |row| {
+                    match counts.get_mut(&row) {
+                        Some(count) if *count > 0 => {
+                            // Without ALL the row is not returned again
+                            *count = if self.all { *count - 1 } else { 0 };
+                            true
+                        }
+                        _ => false,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.left.clone(), self.right.clone()])
+    }
+}
+
+/// Returns the rows of the left input that are not in the right input, in left input order
+pub struct Except {
+    left: PhysicalPlanRef,
+    right: PhysicalPlanRef,
+    /// Each right row only removes one matching left row and duplicates are kept
+    all: bool,
+    schema: Schema,
+}
+
+impl Except {
+    pub fn new(
+        left: PhysicalPlanRef,
+        right: PhysicalPlanRef,
+        all: bool,
+        schema: Schema,
+    ) -> PhysicalPlanRef {
+        Arc::new(Self {
+            left,
+            right,
+            all,
+            schema,
+        })
+    }
+}
+
+impl Display for Except {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Except: all={}", self.all)
+    }
+}
+
+impl PhysicalPlan for Except {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let schema: SchemaRef = Arc::new(self.schema.clone().into());
+        let mut counts = row_counts(&schema, &self.right)?;
+        let mut seen = HashSet::new();
+
+        self.left
+            .execute()?
+            .iter()
+            .map(|batch| {
+                filter_rows(&to_output(&schema, batch)?, |row| {
+                    if !self.all {
+                        return !counts.contains_key(&row) && seen.insert(row);
+                    }
+                    match counts.get_mut(&row) {
+                        Some(count) if *count > 0 => {
+                            *count -= 1;
+                            false
+                        }
+                        _ => true,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.left.clone(), self.right.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::datatype::field::Field;
+    use crate::logical_plan::logical_expr::Operator;
+    use crate::physical_plan::expr::binary::BinaryExpr;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::expr::literal::LiteralExpr;
+    use crate::physical_plan::projection::Projection;
+    use crate::physical_plan::scan::Scan;
+    use crate::physical_plan::selection::Selection;
+    use arrow::array::{ArrayRef, Float64Array, Int64Array};
+    use arrow::datatypes::DataType;
+
+    fn column_values(batches: &[RecordBatch]) -> Vec<ArrayRef> {
+        batches
+            .iter()
+            .filter(|batch| batch.num_rows() > 0)
+            .map(|batch| batch.column(0).clone())
+            .collect()
+    }
+
+    #[test]
+    fn set_operation_physical_plans() -> Result<()> {
+        let test_source = CSVTable::try_create_table("data/test.csv")?;
+        let salary_source = CSVTable::try_create_table("data/salary.csv")?;
+
+        // ages of test: 24, 24, 18, 18, 26
+        let ages = Projection::new(
+            Scan::new(test_source, None),
+            Schema::new(vec![Field::new("age", DataType::Int64, false)]),
+            vec![ColumnExpr::new(2)],
+        );
+        // ids of salary with awards >= 6: 3, 4, 5
+        let ids = Projection::new(
+            Selection::new(
+                Scan::new(salary_source, None),
+                BinaryExpr::new(
+                    ColumnExpr::new(3),
+                    Operator::GtEq,
+                    LiteralExpr::new(Scalar::Int64(Some(6))),
+                ),
+            ),
+            Schema::new(vec![Field::new("id", DataType::Int64, false)]),
+            vec![ColumnExpr::new(0)],
+        );
+        let schema = Schema::new(vec![Field::new("age", DataType::Int64, false)]);
+
+        let union = Union::new(ages.clone(), ids.clone(), schema.clone()).execute()?;
+        assert_eq!(
+            column_values(&union),
+            vec![
+                Arc::new(Int64Array::from(vec![24, 24, 18, 18, 26])) as ArrayRef,
+                Arc::new(Int64Array::from(vec![3, 4, 5])) as ArrayRef,
+            ]
+        );
+
+        let scores = Projection::new(
+            Scan::new(CSVTable::try_create_table("data/test.csv")?, None),
+            Schema::new(vec![Field::new("score", DataType::Float64, false)]),
+            vec![ColumnExpr::new(3)],
+        );
+        // Both inputs are converted to the common type
+        let float_schema = Schema::new(vec![Field::new("age", DataType::Float64, false)]);
+        let union = Union::new(ids.clone(), scores, float_schema).execute()?;
+        assert_eq!(
+            &column_values(&union)[0],
+            &(Arc::new(Float64Array::from(vec![3.0, 4.0, 5.0])) as ArrayRef)
+        );
+
+        let duplicated = Union::new(ages.clone(), ages.clone(), schema.clone());
+
+        let intersect = Intersect::new(duplicated.clone(), ages.clone(), false, schema.clone());
+        assert_eq!(
+            column_values(&intersect.execute()?),
+            vec![Arc::new(Int64Array::from(vec![24, 18, 26])) as ArrayRef]
+        );
+        let intersect = Intersect::new(duplicated.clone(), ages.clone(), true, schema.clone());
+        assert_eq!(
+            column_values(&intersect.execute()?),
+            vec![Arc::new(Int64Array::from(vec![24, 24, 18, 18, 26])) as ArrayRef]
+        );
+
+        let except = Except::new(duplicated.clone(), ids.clone(), false, schema.clone());
+        assert_eq!(
+            column_values(&except.execute()?),
+            vec![Arc::new(Int64Array::from(vec![24, 18, 26])) as ArrayRef]
+        );
+        let except = Except::new(duplicated, ages, true, schema);
+        assert_eq!(
+            column_values(&except.execute()?),
+            vec![Arc::new(Int64Array::from(vec![24, 24, 18, 18, 26])) as ArrayRef]
+        );
+
+        Ok(())
+    }
+}
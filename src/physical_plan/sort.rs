@@ -0,0 +1,140 @@
+use crate::datatype::schema::Schema;
+use crate::error::Result;
+use crate::physical_plan::expr::PhysicalExprRef;
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use crate::util::concat_batches;
+use arrow::compute::{self, SortColumn, SortOptions};
+use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Represents a physical expression to sort by and how to sort it
+pub struct PhysicalSortExpr {
+    pub expr: PhysicalExprRef,
+    pub options: SortOptions,
+}
+
+pub struct Sort {
+    input: PhysicalPlanRef,
+    exprs: Vec<PhysicalSortExpr>,
+}
+
+impl Sort {
+    pub fn new(input: PhysicalPlanRef, exprs: Vec<PhysicalSortExpr>) -> PhysicalPlanRef {
+        Arc::new(Self { input, exprs })
+    }
+}
+
+impl Display for Sort {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let exprs = self
+            .exprs
+            .iter()
+            .map(|sort_expr| {
+                format!(
+                    "{} {} NULLS {}",
+                    sort_expr.expr,
+                    if sort_expr.options.descending {
+                        "DESC"
+                    } else {
+                        "ASC"
+                    },
+                    if sort_expr.options.nulls_first {
+                        "FIRST"
+                    } else {
+                        "LAST"
+                    }
+                )
+            })
+            .collect::<Vec<_>>();
+        write!(f, "Sort: {}", exprs.join(", "))
+    }
+}
+
+impl PhysicalPlan for Sort {
+    fn schema(&self) -> &Schema {
+        self.input.schema()
+    }
+
+    /// Sorts all rows returned by the input, the result is a single batch
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let input = self.input.execute()?;
+        let schema = Arc::new(self.schema().clone().into());
+        let batch = concat_batches(&schema, &input)?;
+
+        if batch.num_rows() == 0 || self.exprs.is_empty() {
+            return Ok(vec![batch]);
+        }
+
+        let sort_columns = self
+            .exprs
+            .iter()
+            .map(|sort_expr| {
+                Ok(SortColumn {
+                    values: sort_expr.expr.evaluate(&batch)?.to_array(),
+                    options: Some(sort_expr.options),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let indices = compute::lexsort_to_indices(&sort_columns, None)?;
+
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|column| compute::take(column.as_ref(), &indices, None))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(vec![RecordBatch::try_new(schema, columns)?])
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.input.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::csv_table::CSVTable;
+    use crate::physical_plan::expr::column::ColumnExpr;
+    use crate::physical_plan::scan::Scan;
+    use arrow::array::{ArrayRef, Int64Array};
+
+    #[test]
+    fn sort_physical_plan() -> Result<()> {
+        let source = CSVTable::try_create_table("data/test.csv")?;
+        let scan = Scan::new(source, None);
+
+        // ORDER BY age DESC, score ASC
+        let sort = Sort::new(
+            scan,
+            vec![
+                PhysicalSortExpr {
+                    expr: ColumnExpr::new(2),
+                    options: SortOptions {
+                        descending: true,
+                        nulls_first: true,
+                    },
+                },
+                PhysicalSortExpr {
+                    expr: ColumnExpr::new(3),
+                    options: SortOptions {
+                        descending: false,
+                        nulls_first: false,
+                    },
+                },
+            ],
+        );
+
+        let batches = sort.execute()?;
+        assert_eq!(batches.len(), 1);
+
+        assert_eq!(
+            batches[0].column(0),
+            &(Arc::new(Int64Array::from(vec![5, 1, 2, 4, 3])) as ArrayRef)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use crate::datatype::schema::Schema;
+use crate::error::Result;
+use crate::physical_plan::expr::{format_exprs, PhysicalExprRef};
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use arrow::array::{Array, ArrayRef};
+use arrow::compute;
+use arrow::datatypes::{self, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// Returns one row for each list of expressions, the values are cast to the types of the schema
+pub struct Values {
+    values: Vec<Vec<PhysicalExprRef>>,
+    schema: Schema,
+}
+
+impl Values {
+    pub fn new(values: Vec<Vec<PhysicalExprRef>>, schema: Schema) -> PhysicalPlanRef {
+        Arc::new(Self { values, schema })
+    }
+}
+
+impl Display for Values {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .values
+            .iter()
+            .map(|row| format!("({})", format_exprs(row)))
+            .collect::<Vec<_>>();
+        write!(f, "Values: {}", rows.join(", "))
+    }
+}
+
+impl PhysicalPlan for Values {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let schema: SchemaRef = Arc::new(self.schema.clone().into());
+
+        // The expressions do not read any columns, they are evaluated on a single empty row
+        let mut options = RecordBatchOptions::default();
+        options.row_count = Some(1);
+        let input = RecordBatch::try_new_with_options(
+            Arc::new(datatypes::Schema::empty()),
+            vec![],
+            &options,
+        )?;
+
+        let mut columns: Vec<Vec<ArrayRef>> = vec![vec![]; schema.fields().len()];
+        for row in &self.values {
+            for ((expr, field), column) in row.iter().zip(schema.fields()).zip(&mut columns) {
+                let array = expr.evaluate(&input)?.to_array();
+                let array = if array.data_type() == field.data_type() {
+                    array
+                } else {
+                    compute::cast(&array, field.data_type())?
+                };
+                column.push(array);
+            }
+        }
+
+        let columns = columns
+            .iter()
+            .map(|arrays| {
+                let arrays = arrays
+                    .iter()
+                    .map(|array| array.as_ref())
+                    .collect::<Vec<_>>();
+                compute::concat(&arrays)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // The rows have no columns when the values are the input of a SELECT without FROM
+        let mut options = RecordBatchOptions::default();
+        options.row_count = Some(self.values.len());
+        Ok(vec![RecordBatch::try_new_with_options(
+            schema, columns, &options,
+        )?])
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![])
+    }
+}
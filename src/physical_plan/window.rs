@@ -0,0 +1,393 @@
+use crate::datatype::scalar::Scalar;
+use crate::datatype::schema::Schema;
+use crate::error::{Error, Result};
+use crate::logical_plan::logical_expr::{
+    AggregateFunc, WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunc,
+};
+use crate::physical_plan::aggr::AggrOperatorRef;
+use crate::physical_plan::expr::{format_exprs, PhysicalExprRef};
+use crate::physical_plan::metrics::{batch_memory_size, ExecutionMetrics, MetricsSnapshot};
+use crate::physical_plan::physical_plan::{PhysicalPlan, PhysicalPlanRef};
+use crate::physical_plan::sort::PhysicalSortExpr;
+use crate::util::concat_batches;
+use arrow::array::{new_empty_array, new_null_array, Array, ArrayRef, UInt64Array};
+use arrow::compute::{self, SortColumn, SortOptions};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+/// A window function evaluated by the `Window` plan
+pub struct PhysicalWindowExpr {
+    pub func: WindowFunc,
+    /// LAG and LEAD always have three arguments: the value, the offset and the default
+    pub args: Vec<PhysicalExprRef>,
+    pub partition_by: Vec<PhysicalExprRef>,
+    pub order_by: Vec<PhysicalSortExpr>,
+    /// The rows aggregates, FIRST_VALUE and LAST_VALUE are computed from
+    pub window_frame: WindowFrame,
+    /// The operator of an aggregate window function, it reads the argument from column 0
+    pub aggr_op: Option<AggrOperatorRef>,
+    /// The type of the results
+    pub data_type: DataType,
+}
+
+impl Display for PhysicalWindowExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({}) OVER (", self.func, format_exprs(&self.args))?;
+
+        let mut clauses = vec![];
+        if !self.partition_by.is_empty() {
+            clauses.push(format!("PARTITION BY {}", format_exprs(&self.partition_by)));
+        }
+        if !self.order_by.is_empty() {
+            let exprs = self
+                .order_by
+                .iter()
+                .map(|sort_expr| {
+                    let order = if sort_expr.options.descending {
+                        " DESC"
+                    } else {
+                        ""
+                    };
+                    format!("{}{}", sort_expr.expr, order)
+                })
+                .collect::<Vec<_>>();
+            clauses.push(format!("ORDER BY {}", exprs.join(", ")));
+        }
+        if self.func.uses_frame() {
+            clauses.push(self.window_frame.to_string());
+        }
+        write!(f, "{})", clauses.join(" "))
+    }
+}
+
+pub struct Window {
+    input: PhysicalPlanRef,
+    window_expr: Mutex<Vec<PhysicalWindowExpr>>,
+    schema: Schema,
+    metrics: ExecutionMetrics,
+}
+
+impl Window {
+    pub fn new(
+        input: PhysicalPlanRef,
+        window_expr: Vec<PhysicalWindowExpr>,
+        schema: Schema,
+    ) -> PhysicalPlanRef {
+        Arc::new(Self {
+            input,
+            window_expr: Mutex::new(window_expr),
+            schema,
+            metrics: ExecutionMetrics::default(),
+        })
+    }
+}
+
+impl Display for Window {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let window_expr = self.window_expr.lock().map_err(|_| fmt::Error)?;
+        let window_expr = window_expr
+            .iter()
+            .map(|expr| expr.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "Window: window_expr=[{}]", window_expr.join(", "))
+    }
+}
+
+impl PhysicalPlan for Window {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Appends the results of the window functions to the rows of the input,
+    /// the rows keep their order and are returned as a single batch
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let input = self.input.execute()?;
+        let batch = self.metrics.record(|| self.compute(&input))?;
+
+        Ok(vec![batch])
+    }
+
+    fn children(&self) -> Result<Vec<PhysicalPlanRef>> {
+        Ok(vec![self.input.clone()])
+    }
+
+    fn metrics(&self) -> Option<MetricsSnapshot> {
+        Some(self.metrics.snapshot())
+    }
+}
+
+impl Window {
+    /// Computes the window functions on the batches of the input, which are held in memory together
+    fn compute(&self, input: &[RecordBatch]) -> Result<RecordBatch> {
+        let batch = concat_batches(&Arc::new(self.input.schema().clone().into()), input)?;
+        self.metrics.record_memory(batch_memory_size(&batch));
+
+        let mut window_expr = self.window_expr.lock().unwrap();
+        let mut columns = batch.columns().to_vec();
+        for expr in window_expr.iter_mut() {
+            columns.push(expr.evaluate(&batch)?);
+        }
+
+        Ok(RecordBatch::try_new(
+            Arc::new(self.schema.clone().into()),
+            columns,
+        )?)
+    }
+}
+
+/// The rows of a partition, given as positions in the sorted order of the batch
+struct Partition {
+    rows: Range<usize>,
+    /// The peer groups of the partition in order, the rows of a group have equal ORDER BY values
+    peer_groups: Vec<Range<usize>>,
+}
+
+impl PhysicalWindowExpr {
+    /// Computes the result of every row of the batch, in the order of the rows
+    fn evaluate(&mut self, batch: &RecordBatch) -> Result<ArrayRef> {
+        if batch.num_rows() == 0 {
+            return Ok(new_empty_array(&self.data_type));
+        }
+
+        let sorted = self.sort_indices(batch)?;
+        let partitions = self.partitions(batch, &sorted)?;
+        let args = self
+            .args
+            .iter()
+            .map(|arg| Ok(arg.evaluate(batch)?.to_array()))
+            .collect::<Result<Vec<_>>>()?;
+
+        // The results are written to the positions of their rows in the batch
+        let mut results = vec![None; batch.num_rows()];
+        match &self.func {
+            WindowFunc::Aggregate(func) => {
+                let nullable = !matches!(func, AggregateFunc::COUNT);
+                let aggr_op = self.aggr_op.as_mut().ok_or_else(|| {
+                    Error::PhysicalPlanError(format!("{} has no aggregate operator", self.func))
+                })?;
+                let arg_batch = RecordBatch::try_from_iter(vec![("arg", args[0].clone())])?;
+
+                for partition in &partitions {
+                    // The rows that are aggregated by `aggr_op`, they are only aggregated again
+                    // when the start of the frame moves or its end moves back
+                    let mut aggregated = partition.rows.start..partition.rows.start;
+                    aggr_op.clear()?;
+
+                    for (pos, peers) in positions(partition) {
+                        let frame = frame_rows(&self.window_frame, pos, &partition.rows, peers);
+                        if frame.start != aggregated.start || frame.end < aggregated.end {
+                            aggr_op.clear()?;
+                            aggregated = frame.start..frame.start;
+                        }
+                        for &row in &sorted[aggregated.end..frame.end] {
+                            aggr_op.update(&arg_batch, row)?;
+                        }
+                        aggregated.end = frame.end;
+
+                        results[sorted[pos]] = if frame.is_empty() && nullable {
+                            None
+                        } else {
+                            Some(aggr_op.evaluate()?)
+                        };
+                    }
+                }
+            }
+            WindowFunc::RowNumber | WindowFunc::Rank | WindowFunc::DenseRank => {
+                for partition in &partitions {
+                    for (group, peers) in partition.peer_groups.iter().enumerate() {
+                        for pos in peers.clone() {
+                            let number = match self.func {
+                                WindowFunc::RowNumber => pos - partition.rows.start + 1,
+                                WindowFunc::Rank => peers.start - partition.rows.start + 1,
+                                _ => group + 1,
+                            };
+                            results[sorted[pos]] = Some(Scalar::UInt64(Some(number as u64)));
+                        }
+                    }
+                }
+            }
+            WindowFunc::Lag | WindowFunc::Lead => {
+                let (values, offsets, defaults) = (&args[0], &args[1], &args[2]);
+                for partition in &partitions {
+                    for pos in partition.rows.clone() {
+                        let row = sorted[pos];
+                        let offset = match Scalar::try_from_array(offsets, row)? {
+                            Scalar::Int64(Some(offset)) if offset >= 0 => offset as usize,
+                            Scalar::Int64(None) | Scalar::Null => continue,
+                            offset => {
+                                return Err(Error::PhysicalPlanError(format!(
+                                    "The offset of {} must be a non-negative integer, got {:?}",
+                                    self.func, offset
+                                )))
+                            }
+                        };
+                        let target = match self.func {
+                            WindowFunc::Lag => pos.checked_sub(offset),
+                            _ => pos.checked_add(offset),
+                        };
+                        results[row] = Some(match target {
+                            Some(target) if partition.rows.contains(&target) => {
+                                Scalar::try_from_array(values, sorted[target])?
+                            }
+                            _ => Scalar::try_from_array(defaults, row)?,
+                        });
+                    }
+                }
+            }
+            WindowFunc::FirstValue | WindowFunc::LastValue => {
+                for partition in &partitions {
+                    for (pos, peers) in positions(partition) {
+                        let frame = frame_rows(&self.window_frame, pos, &partition.rows, peers);
+                        if frame.is_empty() {
+                            continue;
+                        }
+                        let target = match self.func {
+                            WindowFunc::FirstValue => frame.start,
+                            _ => frame.end - 1,
+                        };
+                        results[sorted[pos]] =
+                            Some(Scalar::try_from_array(&args[0], sorted[target])?);
+                    }
+                }
+            }
+        }
+
+        scalars_to_array(results, &self.data_type)
+    }
+
+    /// The indices of the rows sorted by the partition keys and then by the ORDER BY keys.
+    /// Peers stay in the order of the input so the results do not change between executions.
+    fn sort_indices(&self, batch: &RecordBatch) -> Result<Vec<usize>> {
+        let mut sort_columns = vec![];
+        for expr in &self.partition_by {
+            sort_columns.push(SortColumn {
+                values: expr.evaluate(batch)?.to_array(),
+                options: Some(SortOptions::default()),
+            });
+        }
+        for sort_expr in &self.order_by {
+            sort_columns.push(SortColumn {
+                values: sort_expr.expr.evaluate(batch)?.to_array(),
+                options: Some(sort_expr.options),
+            });
+        }
+        sort_columns.push(SortColumn {
+            values: Arc::new(UInt64Array::from_iter_values(0..batch.num_rows() as u64)),
+            options: None,
+        });
+
+        let indices = compute::lexsort_to_indices(&sort_columns, None)?;
+        Ok(indices.values().iter().map(|i| *i as usize).collect())
+    }
+
+    /// Splits the sorted rows into partitions and the partitions into peer groups
+    fn partitions(&self, batch: &RecordBatch, sorted: &[usize]) -> Result<Vec<Partition>> {
+        let evaluate = |exprs: Vec<&PhysicalExprRef>| {
+            exprs
+                .into_iter()
+                .map(|expr| Ok(expr.evaluate(batch)?.to_array()))
+                .collect::<Result<Vec<_>>>()
+        };
+        let partition_keys = evaluate(self.partition_by.iter().collect())?;
+        let order_keys = evaluate(
+            self.order_by
+                .iter()
+                .map(|sort_expr| &sort_expr.expr)
+                .collect(),
+        )?;
+
+        let mut partitions: Vec<Partition> = vec![];
+        let mut last_keys = None;
+        for (pos, &row) in sorted.iter().enumerate() {
+            let keys = (key(&partition_keys, row)?, key(&order_keys, row)?);
+            match (partitions.last_mut(), &last_keys) {
+                (Some(partition), Some((partition_key, order_key))) if partition_key == &keys.0 => {
+                    partition.rows.end = pos + 1;
+                    match partition.peer_groups.last_mut() {
+                        Some(peers) if order_key == &keys.1 => peers.end = pos + 1,
+                        _ => partition.peer_groups.push(pos..pos + 1),
+                    }
+                }
+                _ => {
+                    let rows = pos..pos + 1;
+                    partitions.push(Partition {
+                        peer_groups: vec![rows.clone()],
+                        rows,
+                    })
+                }
+            }
+            last_keys = Some(keys);
+        }
+        Ok(partitions)
+    }
+}
+
+/// The values of the columns at `row`
+fn key(columns: &[ArrayRef], row: usize) -> Result<Vec<Scalar>> {
+    columns
+        .iter()
+        .map(|column| Scalar::try_from_array(column, row))
+        .collect()
+}
+
+/// The sorted positions of the rows of the partition, each with the peer group it belongs to
+fn positions(partition: &Partition) -> impl Iterator<Item = (usize, &Range<usize>)> {
+    partition
+        .peer_groups
+        .iter()
+        .flat_map(|peers| peers.clone().map(move |pos| (pos, peers)))
+}
+
+/// The sorted positions of the rows in the frame of the row at `pos`
+fn frame_rows(
+    window_frame: &WindowFrame,
+    pos: usize,
+    partition: &Range<usize>,
+    peers: &Range<usize>,
+) -> Range<usize> {
+    // The current row of a RANGE frame stands for all of its peers,
+    // `current` is where it starts and, since ranges are exclusive, `current_end` where it ends
+    let current = match window_frame.units {
+        WindowFrameUnits::Rows => pos..pos + 1,
+        WindowFrameUnits::Range => peers.clone(),
+    };
+    let bound = |bound: &WindowFrameBound, current: usize| match bound {
+        WindowFrameBound::UnboundedPreceding => partition.start,
+        WindowFrameBound::Preceding(n) => current.saturating_sub(*n),
+        WindowFrameBound::CurrentRow => current,
+        WindowFrameBound::Following(n) => current.saturating_add(*n),
+        WindowFrameBound::UnboundedFollowing => partition.end,
+    };
+
+    let clamp = |pos: usize| pos.clamp(partition.start, partition.end);
+    let start = clamp(bound(&window_frame.start_bound, current.start));
+    let end = clamp(bound(&window_frame.end_bound, current.end));
+    start..end.max(start)
+}
+
+/// Converts the results to an array of `data_type`, `None` and NULL values become nulls
+fn scalars_to_array(scalars: Vec<Option<Scalar>>, data_type: &DataType) -> Result<ArrayRef> {
+    let arrays = scalars
+        .into_iter()
+        .map(|scalar| match scalar {
+            None | Some(Scalar::Null) => Ok(new_null_array(data_type, 1)),
+            Some(scalar) => {
+                let array = scalar.to_array(1);
+                if array.data_type() == data_type {
+                    Ok(array)
+                } else {
+                    Ok(compute::cast(&array, data_type)?)
+                }
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let arrays = arrays
+        .iter()
+        .map(|array| array.as_ref())
+        .collect::<Vec<_>>();
+    Ok(compute::concat(&arrays)?)
+}
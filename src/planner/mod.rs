@@ -1,29 +1,79 @@
+use crate::datatype::scalar::Scalar;
+use crate::datatype::schema::Schema;
 use crate::error::Error;
 use crate::error::Result;
-use crate::logical_plan::logical_expr::AggregateFunc::{AVG, COUNT, MAX, MIN, SUM};
-use crate::physical_plan::aggr::avg::Avg;
+use crate::logical_plan::logical_expr::{AggregateFunc, WindowFrame, WindowFunc};
+use crate::logical_plan::optimizer::Optimizer;
+use crate::logical_plan::type_coercion::coerce_expr;
 use crate::physical_plan::aggr::count::Count;
-use crate::physical_plan::aggr::max::Max;
-use crate::physical_plan::aggr::min::Min;
-use crate::physical_plan::aggr::sum::Sum;
-use crate::physical_plan::aggr::Aggregation;
+use crate::physical_plan::aggr::grouping::Grouping;
+use crate::physical_plan::aggr::{create_aggr_operator, Aggregation};
+use crate::physical_plan::distinct::Distinct;
+use crate::physical_plan::empty::EmptyRelation;
+use crate::physical_plan::explain::{Analyze, Explain};
 use crate::physical_plan::expr::alias::AliasExpr;
+use crate::physical_plan::expr::between::BetweenExpr;
+use crate::physical_plan::expr::case::CaseExpr;
+use crate::physical_plan::expr::cast::CastExpr;
 use crate::physical_plan::expr::column::ColumnExpr;
+use crate::physical_plan::expr::in_list::InListExpr;
 use crate::physical_plan::expr::literal::LiteralExpr;
-use crate::physical_plan::expr::PhysicalExpr;
+use crate::physical_plan::expr::pattern::PatternMatchExpr;
+use crate::physical_plan::expr::placeholder::{ParamValues, PlaceholderExpr};
+use crate::physical_plan::expr::unary::UnaryExpr;
+use crate::physical_plan::limit::Limit;
 use crate::physical_plan::nested_loop_join::NestedLoopJoin;
 use crate::physical_plan::physical_plan::PhysicalPlanRef;
 use crate::physical_plan::projection::Projection;
+use crate::physical_plan::recursive_query::{
+    RecursiveQuery, WorkTable, WorkTableRef, WorkTableScan,
+};
 use crate::physical_plan::scan::Scan;
 use crate::physical_plan::selection::Selection;
+use crate::physical_plan::set_operation::{Except, Intersect, Union};
+use crate::physical_plan::sort::{PhysicalSortExpr, Sort};
+use crate::physical_plan::values::Values;
+use crate::physical_plan::window::{PhysicalWindowExpr, Window};
 use crate::{
     logical_plan::{logical_expr::LogicalExpr, logical_plan::LogicalPlan},
     physical_plan::expr::{binary::BinaryExpr, PhysicalExprRef},
 };
+use arrow::compute::SortOptions;
+use std::collections::HashMap;
+
 pub struct QueryPlanner;
 
+/// The state needed by the nodes of a plan that is not part of the logical plan itself
+#[derive(Clone, Default)]
+struct PlanContext {
+    /// The work tables of the recursive queries that contain the plan
+    work_tables: HashMap<String, WorkTableRef>,
+    /// The values placeholders are bound to, `None` if the plan is not a prepared statement
+    params: Option<ParamValues>,
+}
+
 impl QueryPlanner {
+    /// Optimizes the logical plan and creates the physical plan of the optimized plan
     pub fn create_physical_plan(logical_plan: &LogicalPlan) -> Result<PhysicalPlanRef> {
+        let optimized = Optimizer::default().optimize(logical_plan)?;
+        QueryPlanner::plan_with_context(&optimized, &PlanContext::default())
+    }
+
+    /// Creates the physical plan of a prepared statement, its placeholders read their
+    /// values from `params` each time the plan is executed
+    pub fn create_prepared_physical_plan(
+        logical_plan: &LogicalPlan,
+        params: ParamValues,
+    ) -> Result<PhysicalPlanRef> {
+        let ctx = PlanContext {
+            work_tables: HashMap::new(),
+            params: Some(params),
+        };
+        let optimized = Optimizer::default().optimize(logical_plan)?;
+        QueryPlanner::plan_with_context(&optimized, &ctx)
+    }
+
+    fn plan_with_context(logical_plan: &LogicalPlan, ctx: &PlanContext) -> Result<PhysicalPlanRef> {
         match logical_plan {
             LogicalPlan::Scan(scan) => {
                 Ok(Scan::new(scan.data_source.clone(), scan.projection.clone()))
@@ -32,112 +82,331 @@ impl QueryPlanner {
                 let exprs = projection
                     .exprs
                     .iter()
-                    .map(|expr| {
-                        QueryPlanner::create_physical_expr(&projection.input, expr).unwrap()
-                    })
-                    .collect::<Vec<_>>();
+                    .map(|expr| QueryPlanner::plan_expr(&projection.input, expr, ctx))
+                    .collect::<Result<Vec<_>>>()?;
 
-                let input = QueryPlanner::create_physical_plan(&projection.input)?;
+                let input = QueryPlanner::plan_with_context(&projection.input, ctx)?;
                 Ok(Projection::new(input, projection.schema.clone(), exprs))
             }
             LogicalPlan::Selection(selection) => {
-                let expr = QueryPlanner::create_physical_expr(&selection.input, &selection.expr)?;
-                let input = QueryPlanner::create_physical_plan(&selection.input)?;
+                let expr = QueryPlanner::plan_expr(&selection.input, &selection.expr, ctx)?;
+                let input = QueryPlanner::plan_with_context(&selection.input, ctx)?;
                 Ok(Selection::new(input, expr))
             }
             LogicalPlan::Aggregation(aggreagtion) => {
-                let field = aggreagtion.group_expr.to_field(&aggreagtion.input)?;
-
-                let group_expr = QueryPlanner::create_physical_expr(
-                    &aggreagtion.input,
-                    &aggreagtion.group_expr,
-                )?;
+                let group_expr = aggreagtion
+                    .group_expr
+                    .iter()
+                    .map(|expr| QueryPlanner::plan_expr(&aggreagtion.input, expr, ctx))
+                    .collect::<Result<Vec<_>>>()?;
 
                 let mut aggr_expr = vec![];
 
                 for aggr_func_expr in aggreagtion.aggr_expr.iter() {
-                    let column = QueryPlanner::create_physical_expr(
-                        &aggreagtion.input,
-                        &aggr_func_expr.expr,
-                    )?;
-                    let column = column.as_any().downcast_ref::<ColumnExpr>().unwrap();
-
-                    match aggr_func_expr.func {
-                        SUM => aggr_expr.push(Sum::new(field.data_type().clone(), column.clone())),
-                        MIN => aggr_expr.push(Min::new(field.data_type().clone(), column.clone())),
-                        MAX => aggr_expr.push(Max::new(field.data_type().clone(), column.clone())),
-                        AVG => aggr_expr.push(Avg::new(field.data_type().clone(), column.clone())),
-                        COUNT => aggr_expr.push(Count::new(column.clone())),
+                    let field = aggr_func_expr.expr.to_field(&aggreagtion.input)?;
+                    if let AggregateFunc::GROUPING = aggr_func_expr.func {
+                        // The argument of GROUPING is one of the group exprs,
+                        // the operator only needs to know which one
+                        let name = field.qualified_name();
+                        let group_index = aggreagtion
+                            .group_expr
+                            .iter()
+                            .map(|expr| expr.to_field(&aggreagtion.input))
+                            .collect::<Result<Vec<_>>>()?
+                            .iter()
+                            .position(|field| field.qualified_name() == name)
+                            .ok_or_else(|| {
+                                Error::PhysicalPlanError(format!(
+                                    "The argument {} of GROUPING must be a GROUP BY expression",
+                                    aggr_func_expr.expr
+                                ))
+                            })?;
+                        aggr_expr.push(Grouping::new(group_index, group_expr[group_index].clone()));
+                        continue;
+                    }
+                    if let LogicalExpr::Wildcard = aggr_func_expr.expr.as_ref() {
+                        aggr_expr.push(Count::rows());
+                        continue;
                     }
+                    let column =
+                        QueryPlanner::plan_expr(&aggreagtion.input, &aggr_func_expr.expr, ctx)?;
+                    let column = column
+                        .as_any()
+                        .downcast_ref::<ColumnExpr>()
+                        .ok_or_else(|| {
+                            Error::PhysicalPlanError(format!(
+                                "The argument of aggregate function {:?} must be a column",
+                                aggr_func_expr.func
+                            ))
+                        })?;
+
+                    aggr_expr.push(create_aggr_operator(
+                        &aggr_func_expr.func,
+                        aggr_func_expr.distinct,
+                        field.data_type().clone(),
+                        column.clone(),
+                    )?);
                 }
 
-                let input = QueryPlanner::create_physical_plan(&aggreagtion.input)?;
+                let aggr_filter = aggreagtion
+                    .aggr_expr
+                    .iter()
+                    .map(|aggr_func_expr| match &aggr_func_expr.filter {
+                        Some(filter) => Ok(Some(QueryPlanner::plan_expr(
+                            &aggreagtion.input,
+                            filter,
+                            ctx,
+                        )?)),
+                        None => Ok(None),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
 
-                Ok(Aggregation::new(input, Some(group_expr), aggr_expr))
-            }
-            LogicalPlan::Join(join) => {
-                let left = QueryPlanner::create_physical_plan(join.left.as_ref())?;
-                let right = QueryPlanner::create_physical_plan(join.right.as_ref())?;
+                let input = QueryPlanner::plan_with_context(&aggreagtion.input, ctx)?;
 
-                let mut on = vec![];
+                Ok(Aggregation::new_with_grouping_sets(
+                    input,
+                    group_expr,
+                    aggreagtion.grouping_sets.clone(),
+                    aggr_expr,
+                    aggr_filter,
+                    aggreagtion.schema.clone(),
+                ))
+            }
+            LogicalPlan::Window(window) => {
+                let mut window_expr = vec![];
 
-                for (left_col, right_col) in join.on.iter() {
-                    let mut left_idx = -1;
-                    let mut right_idx = -1;
+                for expr in &window.window_expr {
+                    let plan_expr =
+                        |expr: &LogicalExpr| QueryPlanner::plan_expr(&window.input, expr, ctx);
+                    let args = expr
+                        .args
+                        .iter()
+                        .map(plan_expr)
+                        .collect::<Result<Vec<_>>>()?;
 
-                    for (i, field) in left.schema().fields().iter().enumerate() {
-                        if field.name() == left_col {
-                            left_idx = i as i64;
+                    // The argument of an aggregate is evaluated first, the operator reads it
+                    // from the first column of a batch of its own
+                    let aggr_op = match &expr.func {
+                        WindowFunc::Aggregate(func) => {
+                            let field = expr.args[0].to_field(&window.input)?;
+                            Some(create_aggr_operator(
+                                func,
+                                false,
+                                field.data_type().clone(),
+                                ColumnExpr { index: 0 },
+                            )?)
                         }
-                    }
+                        _ => None,
+                    };
 
-                    for (i, field) in right.schema().fields().iter().enumerate() {
-                        if field.name() == left_col {
-                            right_idx = i as i64;
-                        }
-                    }
+                    window_expr.push(PhysicalWindowExpr {
+                        func: expr.func.clone(),
+                        args,
+                        partition_by: expr
+                            .partition_by
+                            .iter()
+                            .map(plan_expr)
+                            .collect::<Result<Vec<_>>>()?,
+                        order_by: expr
+                            .order_by
+                            .iter()
+                            .map(|sort_expr| {
+                                Ok(PhysicalSortExpr {
+                                    expr: plan_expr(&sort_expr.expr)?,
+                                    options: SortOptions {
+                                        descending: !sort_expr.asc,
+                                        nulls_first: sort_expr.nulls_first,
+                                    },
+                                })
+                            })
+                            .collect::<Result<Vec<_>>>()?,
+                        window_frame: expr.window_frame.clone().unwrap_or_else(|| {
+                            WindowFrame::default_frame(!expr.order_by.is_empty())
+                        }),
+                        aggr_op,
+                        data_type: expr.to_field(&window.input)?.data_type().clone(),
+                    });
+                }
 
-                    if left_idx == -1 {
-                        return Err(Error::NoSuchColumn(format!(
-                            "Column {} does not exist",
-                            left_col
-                        )));
-                    }
+                let input = QueryPlanner::plan_with_context(&window.input, ctx)?;
+                Ok(Window::new(input, window_expr, window.schema.clone()))
+            }
+            LogicalPlan::Join(join) => {
+                let left = QueryPlanner::plan_with_context(join.left.as_ref(), ctx)?;
+                let right = QueryPlanner::plan_with_context(join.right.as_ref(), ctx)?;
 
-                    if right_idx == -1 {
-                        return Err(Error::NoSuchColumn(format!(
-                            "Column {} does not exist",
-                            right_col
-                        )));
-                    }
+                let on = join
+                    .on
+                    .iter()
+                    .map(|(left_key, right_key)| {
+                        Ok((
+                            QueryPlanner::plan_expr(&join.left, left_key, ctx)?,
+                            QueryPlanner::plan_expr(&join.right, right_key, ctx)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(NestedLoopJoin::new(
+                    left,
+                    right,
+                    on,
+                    join.join_type.clone(),
+                    join.schema.clone(),
+                ))
+            }
+            LogicalPlan::Sort(sort) => {
+                let exprs = sort
+                    .exprs
+                    .iter()
+                    .map(|sort_expr| {
+                        Ok(PhysicalSortExpr {
+                            expr: QueryPlanner::plan_expr(&sort.input, &sort_expr.expr, ctx)?,
+                            options: SortOptions {
+                                descending: !sort_expr.asc,
+                                nulls_first: sort_expr.nulls_first,
+                            },
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let input = QueryPlanner::plan_with_context(&sort.input, ctx)?;
+                Ok(Sort::new(input, exprs))
+            }
+            LogicalPlan::Limit(limit) => {
+                let input = QueryPlanner::plan_with_context(&limit.input, ctx)?;
+                Ok(Limit::new(input, limit.skip, limit.fetch))
+            }
+            LogicalPlan::Distinct(distinct) => {
+                let input = QueryPlanner::plan_with_context(&distinct.input, ctx)?;
+                Ok(Distinct::new(input))
+            }
+            // Qualifiers only matter for resolving columns, the data is not changed
+            LogicalPlan::SubqueryAlias(subquery_alias) => {
+                QueryPlanner::plan_with_context(&subquery_alias.input, ctx)
+            }
+            LogicalPlan::RecursiveQuery(recursive_query) => {
+                let static_term =
+                    QueryPlanner::plan_with_context(&recursive_query.static_term, ctx)?;
 
-                    on.push((
-                        ColumnExpr::new(left_idx as usize)
-                            .as_any()
-                            .downcast_ref::<ColumnExpr>()
-                            .unwrap()
-                            .clone(),
-                        ColumnExpr::new(right_idx as usize)
-                            .as_any()
-                            .downcast_ref::<ColumnExpr>()
-                            .unwrap()
-                            .clone(),
+                // The recursive term reads the work table of this query by its name
+                let work_table = WorkTable::new();
+                let mut ctx = ctx.clone();
+                ctx.work_tables
+                    .insert(recursive_query.name.clone(), work_table.clone());
+                let recursive_term =
+                    QueryPlanner::plan_with_context(&recursive_query.recursive_term, &ctx)?;
+
+                Ok(RecursiveQuery::new(
+                    static_term,
+                    recursive_term,
+                    work_table,
+                    recursive_query.distinct,
+                    recursive_query.max_iterations,
+                    logical_plan.schema().clone(),
+                ))
+            }
+            LogicalPlan::WorkTableScan(work_table_scan) => {
+                let work_table = ctx.work_tables.get(&work_table_scan.name).ok_or_else(|| {
+                    Error::PhysicalPlanError(format!(
+                        "{} can only be read in the recursive term of its recursive query",
+                        work_table_scan.name
                     ))
+                })?;
+                Ok(WorkTableScan::new(
+                    work_table.clone(),
+                    work_table_scan.schema.clone(),
+                ))
+            }
+            LogicalPlan::Union(union) => {
+                let left = QueryPlanner::plan_with_context(&union.left, ctx)?;
+                let right = QueryPlanner::plan_with_context(&union.right, ctx)?;
+                let plan = Union::new(left, right, union.schema.clone());
+                if union.all {
+                    Ok(plan)
+                } else {
+                    Ok(Distinct::new(plan))
                 }
+            }
+            LogicalPlan::Intersect(intersect) => {
+                let left = QueryPlanner::plan_with_context(&intersect.left, ctx)?;
+                let right = QueryPlanner::plan_with_context(&intersect.right, ctx)?;
+                Ok(Intersect::new(
+                    left,
+                    right,
+                    intersect.all,
+                    intersect.schema.clone(),
+                ))
+            }
+            LogicalPlan::Except(except) => {
+                let left = QueryPlanner::plan_with_context(&except.left, ctx)?;
+                let right = QueryPlanner::plan_with_context(&except.right, ctx)?;
+                Ok(Except::new(left, right, except.all, except.schema.clone()))
+            }
+            LogicalPlan::Explain(explain) => {
+                let mut plans = vec![(
+                    "logical_plan".to_string(),
+                    explain.plan.display_indent().to_string(),
+                )];
+                let optimized =
+                    Optimizer::default().optimize_with_observer(&explain.plan, |plan, rule| {
+                        if explain.verbose {
+                            plans.push((
+                                format!("logical_plan after {}", rule.name()),
+                                plan.display_indent().to_string(),
+                            ));
+                        }
+                    })?;
+                plans.push((
+                    "optimized_logical_plan".to_string(),
+                    optimized.display_indent().to_string(),
+                ));
+
+                let physical_plan = QueryPlanner::plan_with_context(&optimized, ctx)?;
+                if explain.analyze {
+                    return Ok(Analyze::new(physical_plan, plans, explain.schema.clone()));
+                }
+                plans.push((
+                    "physical_plan".to_string(),
+                    physical_plan.display_indent(false)?,
+                ));
 
-                Ok(NestedLoopJoin::new(left, right, on, join.schema.clone()))
+                Ok(Explain::new(plans, explain.schema.clone()))
+            }
+            LogicalPlan::EmptyRelation(empty) => Ok(EmptyRelation::new(empty.schema.clone())),
+            LogicalPlan::Values(values) => {
+                let rows = values
+                    .values
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|expr| QueryPlanner::plan_expr(logical_plan, expr, ctx))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Values::new(rows, values.schema.clone()))
             }
         }
     }
 
+    /// Creates the physical expr of `expr` evaluated on the output of `input`,
+    /// casts are inserted where the operands of an expr have different types
     pub fn create_physical_expr(
         input: &LogicalPlan,
         expr: &LogicalExpr,
+    ) -> Result<PhysicalExprRef> {
+        QueryPlanner::plan_expr(input, &coerce_expr(expr, input)?, &PlanContext::default())
+    }
+
+    /// Creates the physical expr of an expr of the optimized plan, its casts are already inserted
+    fn plan_expr(
+        input: &LogicalPlan,
+        expr: &LogicalExpr,
+        ctx: &PlanContext,
     ) -> Result<PhysicalExprRef> {
         match expr {
             LogicalExpr::BinaryExpr(binary_expr) => {
-                let left_expr = QueryPlanner::create_physical_expr(input, &binary_expr.left)?;
-                let right_expr = QueryPlanner::create_physical_expr(input, &binary_expr.right)?;
+                let left_expr = QueryPlanner::plan_expr(input, &binary_expr.left, ctx)?;
+                let right_expr = QueryPlanner::plan_expr(input, &binary_expr.right, ctx)?;
                 Ok(BinaryExpr::new(
                     left_expr,
                     binary_expr.op.clone(),
@@ -147,21 +416,100 @@ impl QueryPlanner {
             LogicalExpr::Literal(literal) => Ok(LiteralExpr::new(literal.clone())),
             LogicalExpr::Alias(alias) => Ok(AliasExpr::new(
                 alias.name.clone(),
-                QueryPlanner::create_physical_expr(input, &alias.expr)?,
+                QueryPlanner::plan_expr(input, &alias.expr, ctx)?,
             )),
             LogicalExpr::Column(column) => {
-                for (i, field) in input.schema().fields().iter().enumerate() {
-                    if field.name() == column {
-                        return Ok(ColumnExpr::new(i));
-                    }
-                }
-                Err(Error::NoSuchColumn(format!(
-                    "Column {} does not exist",
-                    column
-                )))
+                Ok(ColumnExpr::new(column_index(input.schema(), column)?))
+            }
+            // Aggregation operators are created directly when creating a physical aggregation plan
+            LogicalExpr::AggregateFuncExpr(_) => Err(Error::PhysicalPlanError(format!(
+                "Aggregate function {} can only be evaluated by an aggregation",
+                expr
+            ))),
+            // Window functions are evaluated by a window plan, which appends their results
+            LogicalExpr::WindowFuncExpr(_) => Err(Error::PhysicalPlanError(format!(
+                "Window function {} can only be evaluated by a window",
+                expr
+            ))),
+            LogicalExpr::ScalarSubquery(_)
+            | LogicalExpr::InSubquery(_)
+            | LogicalExpr::Exists(_) => Err(Error::PhysicalPlanError(
+                "Subqueries are only supported in the select list, WHERE and HAVING".to_string(),
+            )),
+            LogicalExpr::Case(case) => {
+                let data_type = case.to_field(input)?.data_type().clone();
+                let expr = |expr: &LogicalExpr| QueryPlanner::plan_expr(input, expr, ctx);
+                Ok(CaseExpr::new(
+                    case.expr.as_deref().map(expr).transpose()?,
+                    case.when_then_expr
+                        .iter()
+                        .map(|(when, then)| Ok((expr(when)?, expr(then)?)))
+                        .collect::<Result<Vec<_>>>()?,
+                    case.else_expr.as_deref().map(expr).transpose()?,
+                    data_type,
+                ))
             }
-            LogicalExpr::AggregateFuncExpr(_) => todo!(), // Create aggregation operators directly when creating a physical aggregation plan
-            _ => unimplemented!(),
+            LogicalExpr::Cast(cast) => Ok(CastExpr::new(
+                QueryPlanner::plan_expr(input, &cast.expr, ctx)?,
+                cast.data_type.clone(),
+                false,
+            )),
+            LogicalExpr::TryCast(try_cast) => Ok(CastExpr::new(
+                QueryPlanner::plan_expr(input, &try_cast.expr, ctx)?,
+                try_cast.data_type.clone(),
+                true,
+            )),
+            LogicalExpr::Placeholder(id) => match &ctx.params {
+                Some(params) => Ok(PlaceholderExpr::new(*id, params.clone())),
+                None => Err(Error::PhysicalPlanError(format!(
+                    "Placeholder ${} can only be used in a prepared statement",
+                    id
+                ))),
+            },
+            // Outside of an aggregation, e.g. in a window, COUNT(*) counts a value
+            // that is never NULL
+            LogicalExpr::Wildcard => Ok(LiteralExpr::new(Scalar::Boolean(Some(true)))),
+            LogicalExpr::OuterColumn(field) => Err(Error::PhysicalPlanError(format!(
+                "Correlated column {} is only supported in equality conditions of WHERE",
+                field.qualified_name()
+            ))),
+            LogicalExpr::ScalarFuncExpr(_) => {
+                Err(Error::NotImplemented(format!("Scalar function {}", expr)))
+            }
+            LogicalExpr::PatternMatch(pattern_match) => PatternMatchExpr::new(
+                pattern_match.kind.clone(),
+                pattern_match.negated,
+                QueryPlanner::plan_expr(input, &pattern_match.expr, ctx)?,
+                QueryPlanner::plan_expr(input, &pattern_match.pattern, ctx)?,
+                pattern_match.escape_char,
+            ),
+            LogicalExpr::UnaryExpr(unary_expr) => Ok(UnaryExpr::new(
+                unary_expr.op.clone(),
+                QueryPlanner::plan_expr(input, &unary_expr.expr, ctx)?,
+            )),
+            LogicalExpr::InList(in_list) => Ok(InListExpr::new(
+                QueryPlanner::plan_expr(input, &in_list.expr, ctx)?,
+                in_list
+                    .list
+                    .iter()
+                    .map(|expr| QueryPlanner::plan_expr(input, expr, ctx))
+                    .collect::<Result<Vec<_>>>()?,
+                in_list.negated,
+            )),
+            LogicalExpr::Between(between) => Ok(BetweenExpr::new(
+                QueryPlanner::plan_expr(input, &between.expr, ctx)?,
+                between.negated,
+                QueryPlanner::plan_expr(input, &between.low, ctx)?,
+                QueryPlanner::plan_expr(input, &between.high, ctx)?,
+            )),
         }
     }
 }
+
+/// Resolves a column name to its index in the schema of a logical plan
+fn column_index(schema: &Schema, column: &str) -> Result<usize> {
+    schema.index_of(column).map_err(|e| match e {
+        Error::NoSuchField(name) => Error::NoSuchColumn(name),
+        e => e,
+    })
+}
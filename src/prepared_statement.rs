@@ -0,0 +1,349 @@
+use crate::datatype::field::common_type;
+use crate::datatype::scalar::Scalar;
+use crate::error::{Error, Result};
+use crate::logical_plan::logical_expr::{LogicalExpr, UnaryOperator};
+use crate::logical_plan::logical_plan::LogicalPlan;
+use crate::physical_plan::expr::placeholder::ParamValues;
+use crate::physical_plan::physical_plan::PhysicalPlanRef;
+use crate::planner::QueryPlanner;
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use std::collections::{BTreeSet, HashMap};
+
+/// A statement that is parsed and planned once and can then be executed many times
+/// with different parameter values. `$1` is bound to the first value.
+pub struct PreparedStatement {
+    plan: LogicalPlan,
+    physical_plan: PhysicalPlanRef,
+    /// The type of each parameter, inferred from where its placeholder is used
+    param_types: Vec<DataType>,
+    /// Shared with the placeholders of the physical plan
+    params: ParamValues,
+}
+
+impl PreparedStatement {
+    pub fn try_new(plan: LogicalPlan) -> Result<Self> {
+        let param_types = infer_param_types(&plan)?;
+        let params = ParamValues::default();
+        let physical_plan = QueryPlanner::create_prepared_physical_plan(&plan, params.clone())?;
+
+        Ok(Self {
+            plan,
+            physical_plan,
+            param_types,
+            params,
+        })
+    }
+
+    pub fn plan(&self) -> &LogicalPlan {
+        &self.plan
+    }
+
+    pub fn param_types(&self) -> &[DataType] {
+        &self.param_types
+    }
+
+    /// Binds `params` to the placeholders and executes the statement
+    pub fn execute(&self, params: Vec<Scalar>) -> Result<Vec<RecordBatch>> {
+        if params.len() != self.param_types.len() {
+            return Err(Error::PhysicalPlanError(format!(
+                "Expected {} parameters, got {}",
+                self.param_types.len(),
+                params.len()
+            )));
+        }
+
+        let params = params
+            .into_iter()
+            .zip(self.param_types.iter())
+            .enumerate()
+            .map(|(i, (value, data_type))| bind(i + 1, value, data_type))
+            .collect::<Result<Vec<_>>>()?;
+
+        *self.params.borrow_mut() = params;
+        self.physical_plan.execute()
+    }
+}
+
+/// Converts the value of parameter `$id` to its type, only NULL and lossless
+/// conversions (e.g. Int64 to Float64) are allowed
+fn bind(id: usize, value: Scalar, data_type: &DataType) -> Result<Scalar> {
+    let value_type = value.to_field().data_type().clone();
+    if &value_type == data_type {
+        return Ok(value);
+    }
+    if common_type(&value_type, data_type).as_ref() != Some(data_type) {
+        return Err(Error::PhysicalPlanError(format!(
+            "Parameter ${} must be {:?}, got {:?}",
+            id, data_type, value_type
+        )));
+    }
+
+    let array = cast(&value.to_array(1), data_type)?;
+    Scalar::try_from_array(&array, 0)
+}
+
+/// Returns the types of the parameters `$1` to `$n` of the plan.
+/// A placeholder gets the type of the other operand of the binary expression
+/// it is used in, or the type it is cast to.
+fn infer_param_types(plan: &LogicalPlan) -> Result<Vec<DataType>> {
+    let mut inference = TypeInference::default();
+    inference.visit_plan(plan)?;
+
+    let max_id = inference.ids.iter().next_back().copied().unwrap_or(0);
+    (1..=max_id)
+        .map(|id| {
+            if !inference.ids.contains(&id) {
+                return Err(Error::LogicalPlanError(format!(
+                    "Parameter ${} is not used, parameters must be numbered from $1 without gaps",
+                    id
+                )));
+            }
+            inference.types.get(&id).cloned().ok_or_else(|| {
+                Error::LogicalPlanError(format!("Cannot infer the type of parameter ${}", id))
+            })
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct TypeInference {
+    /// All placeholders of the plan
+    ids: BTreeSet<usize>,
+    types: HashMap<usize, DataType>,
+}
+
+impl TypeInference {
+    fn visit_plan(&mut self, plan: &LogicalPlan) -> Result<()> {
+        match plan {
+            LogicalPlan::Projection(projection) => {
+                for expr in &projection.exprs {
+                    self.visit_expr(expr, &projection.input)?;
+                }
+            }
+            LogicalPlan::Selection(selection) => {
+                self.visit_expr(&selection.expr, &selection.input)?;
+            }
+            LogicalPlan::Aggregation(aggregation) => {
+                for expr in &aggregation.group_expr {
+                    self.visit_expr(expr, &aggregation.input)?;
+                }
+                for aggr_expr in &aggregation.aggr_expr {
+                    self.visit_expr(&aggr_expr.expr, &aggregation.input)?;
+                }
+            }
+            LogicalPlan::Window(window) => {
+                for window_expr in &window.window_expr {
+                    let expr = LogicalExpr::WindowFuncExpr(window_expr.clone());
+                    self.visit_expr(&expr, &window.input)?;
+                }
+            }
+            LogicalPlan::Sort(sort) => {
+                for sort_expr in &sort.exprs {
+                    self.visit_expr(&sort_expr.expr, &sort.input)?;
+                }
+            }
+            _ => {}
+        }
+
+        for child in plan.children() {
+            self.visit_plan(&child)?;
+        }
+        Ok(())
+    }
+
+    fn visit_expr(&mut self, expr: &LogicalExpr, input: &LogicalPlan) -> Result<()> {
+        match expr {
+            LogicalExpr::Placeholder(id) => {
+                self.ids.insert(*id);
+            }
+            LogicalExpr::BinaryExpr(binary_expr) => {
+                self.infer_from(&binary_expr.left, &binary_expr.right, input)?;
+                self.infer_from(&binary_expr.right, &binary_expr.left, input)?;
+                self.visit_expr(&binary_expr.left, input)?;
+                self.visit_expr(&binary_expr.right, input)?;
+            }
+            LogicalExpr::Cast(cast) => {
+                if let LogicalExpr::Placeholder(id) = cast.expr.as_ref() {
+                    self.infer(*id, &cast.data_type)?;
+                }
+                self.visit_expr(&cast.expr, input)?;
+            }
+            LogicalExpr::TryCast(try_cast) => self.visit_expr(&try_cast.expr, input)?,
+            LogicalExpr::PatternMatch(pattern_match) => {
+                // Both the string and the pattern are Utf8
+                for expr in [&pattern_match.expr, &pattern_match.pattern] {
+                    if let LogicalExpr::Placeholder(id) = expr.as_ref() {
+                        self.infer(*id, &DataType::Utf8)?;
+                    }
+                    self.visit_expr(expr, input)?;
+                }
+            }
+            LogicalExpr::UnaryExpr(unary_expr) => {
+                // `NOT $1` and `$1 IS TRUE` are only valid for Boolean values
+                if let LogicalExpr::Placeholder(id) = unary_expr.expr.as_ref() {
+                    match unary_expr.op {
+                        UnaryOperator::Not
+                        | UnaryOperator::IsTrue
+                        | UnaryOperator::IsFalse
+                        | UnaryOperator::IsNotTrue
+                        | UnaryOperator::IsNotFalse => self.infer(*id, &DataType::Boolean)?,
+                        _ => {}
+                    }
+                }
+                self.visit_expr(&unary_expr.expr, input)?;
+            }
+            LogicalExpr::InList(in_list) => {
+                for expr in &in_list.list {
+                    self.infer_from(expr, &in_list.expr, input)?;
+                    self.infer_from(&in_list.expr, expr, input)?;
+                    self.visit_expr(expr, input)?;
+                }
+                self.visit_expr(&in_list.expr, input)?;
+            }
+            LogicalExpr::Between(between) => {
+                for bound in [&between.low, &between.high] {
+                    self.infer_from(bound, &between.expr, input)?;
+                    self.infer_from(&between.expr, bound, input)?;
+                    self.visit_expr(bound, input)?;
+                }
+                self.visit_expr(&between.expr, input)?;
+            }
+            LogicalExpr::Alias(alias) => self.visit_expr(&alias.expr, input)?,
+            LogicalExpr::AggregateFuncExpr(aggr_expr) => {
+                self.visit_expr(&aggr_expr.expr, input)?;
+                if let Some(filter) = &aggr_expr.filter {
+                    self.visit_expr(filter, input)?;
+                }
+            }
+            LogicalExpr::WindowFuncExpr(window_expr) => {
+                let sort_exprs = window_expr.order_by.iter().map(|sort_expr| &sort_expr.expr);
+                for expr in window_expr
+                    .args
+                    .iter()
+                    .chain(&window_expr.partition_by)
+                    .chain(sort_exprs)
+                {
+                    self.visit_expr(expr, input)?;
+                }
+            }
+            LogicalExpr::Case(case) => {
+                if let Some(operand) = &case.expr {
+                    for (when, _) in &case.when_then_expr {
+                        self.infer_from(when, operand, input)?;
+                    }
+                    self.visit_expr(operand, input)?;
+                }
+                for (when, then) in &case.when_then_expr {
+                    self.visit_expr(when, input)?;
+                    self.visit_expr(then, input)?;
+                }
+                if let Some(else_expr) = &case.else_expr {
+                    self.visit_expr(else_expr, input)?;
+                }
+            }
+            LogicalExpr::InSubquery(in_subquery) => {
+                if let LogicalExpr::Placeholder(id) = in_subquery.expr.as_ref() {
+                    let field = in_subquery.subquery.plan.schema().field(0).clone();
+                    self.infer(*id, field.data_type())?;
+                }
+                self.visit_expr(&in_subquery.expr, input)?;
+                self.visit_plan(&in_subquery.subquery.plan)?;
+            }
+            LogicalExpr::ScalarSubquery(subquery) => self.visit_plan(&subquery.plan)?,
+            LogicalExpr::Exists(exists) => self.visit_plan(&exists.subquery.plan)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// If `expr` is a placeholder, it gets the type of `other`
+    fn infer_from(
+        &mut self,
+        expr: &LogicalExpr,
+        other: &LogicalExpr,
+        input: &LogicalPlan,
+    ) -> Result<()> {
+        if let LogicalExpr::Placeholder(id) = expr {
+            let field = other.to_field(input)?;
+            self.infer(*id, field.data_type())?;
+        }
+        Ok(())
+    }
+
+    fn infer(&mut self, id: usize, data_type: &DataType) -> Result<()> {
+        // The other operand is a placeholder or NULL too
+        if data_type == &DataType::Null {
+            return Ok(());
+        }
+
+        match self.types.get(&id) {
+            Some(existing) if existing != data_type => Err(Error::LogicalPlanError(format!(
+                "Parameter ${} is used as both {:?} and {:?}",
+                id, existing, data_type
+            ))),
+            _ => {
+                self.types.insert(id, data_type.clone());
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::Catalog;
+    use arrow::util::pretty;
+
+    #[test]
+    fn prepared_statement() -> Result<()> {
+        let mut catalog = Catalog::default();
+        catalog.add_csv_table("test", "data/test.csv")?;
+
+        let statement =
+            catalog.prepare("SELECT id, name FROM test WHERE age = $1 AND score > $2")?;
+        assert_eq!(
+            statement.param_types(),
+            &[DataType::Int64, DataType::Float64]
+        );
+
+        let batches =
+            statement.execute(vec![Scalar::Int64(Some(24)), Scalar::Float64(Some(99.0))])?;
+        assert_eq!(
+            pretty::pretty_format_batches(&batches)?.to_string(),
+            "+----+------------+\
+           \n| id | name       |\
+           \n+----+------------+\
+           \n| 2  | Vincent Hu |\
+           \n+----+------------+"
+        );
+
+        // The same plan is executed again with other values, Int64 is widened to Float64
+        let batches = statement.execute(vec![Scalar::Int64(Some(18)), Scalar::Int64(Some(100))])?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+
+        assert!(statement.execute(vec![Scalar::Int64(Some(18))]).is_err());
+        assert!(statement
+            .execute(vec![
+                Scalar::Utf8(Some("18".to_string())),
+                Scalar::Float64(Some(99.0))
+            ])
+            .is_err());
+
+        // `?` placeholders are numbered in order
+        let statement = catalog.prepare("SELECT id FROM test WHERE name = ? OR id < ?")?;
+        assert_eq!(statement.param_types(), &[DataType::Utf8, DataType::Int64]);
+
+        assert!(catalog.prepare("SELECT $1 FROM test").is_err());
+        assert!(catalog
+            .prepare("SELECT id FROM test WHERE age = $2")
+            .is_err());
+        assert!(catalog
+            .prepare("SELECT id FROM test WHERE age = $1 OR name = $1")
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,519 @@
+use crate::logical_plan::logical_expr::{Operator, PatternKind, UnaryOperator, WindowFrame};
+use crate::sql::tokenizer::Span;
+use arrow::datatypes::DataType;
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A top-level SQL statement
+pub enum Statement {
+    /// SELECT query
+    Query(Box<Query>),
+    /// `EXPLAIN [ANALYZE] [VERBOSE] statement`
+    Explain {
+        analyze: bool,
+        verbose: bool,
+        statement: Box<Statement>,
+    },
+    /// `CREATE [OR REPLACE] EXTERNAL TABLE [IF NOT EXISTS] name [(columns)] STORED AS type
+    /// [WITH HEADER ROW] [DELIMITER 'c'] LOCATION 'path'`
+    CreateExternalTable(CreateExternalTable),
+    /// `CREATE [OR REPLACE] VIEW name AS query`
+    CreateView {
+        name: String,
+        query: Box<Query>,
+        or_replace: bool,
+    },
+    /// `DROP VIEW [IF EXISTS] name`
+    DropView { name: String, if_exists: bool },
+    /// `CREATE [OR REPLACE] TABLE [IF NOT EXISTS] name [(columns)] [AS query]`
+    CreateTable(CreateTable),
+    /// `INSERT INTO name query`, the query may be `VALUES (...), ...`
+    Insert {
+        table_name: String,
+        source: Box<Query>,
+    },
+    /// `SHOW TABLES`
+    ShowTables,
+    /// `DESCRIBE name` or `SHOW COLUMNS FROM name`
+    ShowColumns { table_name: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Registers a file as a table
+pub struct CreateExternalTable {
+    pub name: String,
+    /// The columns of the file, the schema is inferred if there are none
+    pub columns: Vec<ColumnDef>,
+    /// The file type following `STORED AS`, e.g. `CSV`
+    pub file_type: String,
+    pub has_header: bool,
+    pub delimiter: char,
+    pub location: String,
+    pub if_not_exists: bool,
+    pub or_replace: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Creates a table that is stored in memory
+pub struct CreateTable {
+    pub name: String,
+    /// The columns of the table, they are taken from the query if there are none
+    pub columns: Vec<ColumnDef>,
+    /// The query whose rows fill the table
+    pub query: Option<Box<Query>>,
+    pub if_not_exists: bool,
+    pub or_replace: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A column definition, e.g. `name VARCHAR NOT NULL`
+pub struct ColumnDef {
+    pub name: String,
+    pub data_type: DataType,
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A complete query, it is also used for sub-queries
+pub struct Query {
+    /// WITH (common table expressions)
+    pub with: Option<With>,
+    pub body: SetExpr,
+    /// ORDER BY
+    pub order_by: Vec<OrderByExpr>,
+    /// `LIMIT { <N> | ALL }`
+    pub limit: Option<Expr>,
+    /// `OFFSET <N> [ { ROW | ROWS } ]`
+    pub offset: Option<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// `WITH [RECURSIVE] cte [, cte ...]`
+pub struct With {
+    pub recursive: bool,
+    pub cte_tables: Vec<Cte>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A common table expression, e.g. `chain (id, depth) AS (SELECT ...)`
+pub struct Cte {
+    pub alias: String,
+    /// Optional names for the columns of the query
+    pub columns: Vec<String>,
+    pub query: Box<Query>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The body of a query, a SELECT or a combination of queries
+pub enum SetExpr {
+    Select(Box<Select>),
+    /// A query in parentheses, e.g. `(SELECT ...)`
+    Query(Box<Query>),
+    /// `VALUES (expr, ...), ...`, the rows of the query are given literally
+    Values(Vec<Vec<Expr>>),
+    /// `left UNION | INTERSECT | EXCEPT [ALL] right`
+    SetOperation {
+        op: SetOperator,
+        all: bool,
+        left: Box<SetExpr>,
+        right: Box<SetExpr>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// SELECT [DISTINCT] projection FROM from WHERE selection GROUP BY group_by HAVING having
+pub struct Select {
+    pub distinct: bool,
+    pub projection: Vec<SelectItem>,
+    pub from: Option<TableWithJoins>,
+    pub selection: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An expression of the ORDER BY clause, e.g. `score DESC NULLS LAST`
+pub struct OrderByExpr {
+    pub expr: Expr,
+    /// Optional `ASC` or `DESC`
+    pub asc: Option<bool>,
+    /// Optional `NULLS FIRST` or `NULLS LAST`
+    pub nulls_first: Option<bool>,
+}
+
+impl Display for OrderByExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)?;
+        match self.asc {
+            Some(true) => write!(f, " ASC")?,
+            Some(false) => write!(f, " DESC")?,
+            None => {}
+        }
+        match self.nulls_first {
+            Some(true) => write!(f, " NULLS FIRST"),
+            Some(false) => write!(f, " NULLS LAST"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// One item of the comma-separated list following `SELECT`
+pub enum SelectItem {
+    /// An expression without an alias, e.g. `age + 1`
+    UnnamedExpr(Expr),
+    /// An expression followed by an alias, e.g. `age + 1 AS next_age`
+    ExprWithAlias { expr: Expr, alias: String },
+    /// An unqualified `*`
+    Wildcard,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A table in the FROM clause together with the tables joined to it
+pub struct TableWithJoins {
+    pub relation: TableFactor,
+    pub joins: Vec<Join>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A table that can appear in the FROM clause
+pub enum TableFactor {
+    /// A table registered in the catalog, e.g. `salary [AS] s`,
+    /// the name of an information_schema table is qualified, e.g. `information_schema.tables`
+    Table { name: Ident, alias: Option<String> },
+    /// A subquery in parentheses, e.g. `(SELECT id FROM test) [AS] t`
+    Derived {
+        subquery: Box<Query>,
+        alias: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// `[INNER | LEFT | RIGHT] JOIN relation ON constraint`
+pub struct Join {
+    pub relation: TableFactor,
+    pub join_type: JoinType,
+    pub constraint: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// SQL expressions
+pub enum Expr {
+    /// An identifier, e.g. a column name
+    Identifier(Ident),
+    /// A multi-part identifier, e.g. `table.column`
+    CompoundIdentifier(Vec<Ident>),
+    /// A literal value, e.g. `1` or `'abc'`
+    Value(Value),
+    /// A binary operation, e.g. `age >= 24`
+    BinaryOp {
+        left: Box<Expr>,
+        op: Operator,
+        right: Box<Expr>,
+    },
+    /// A unary operation, e.g. `NOT active`, `-score` or `name IS NULL`
+    UnaryOp { op: UnaryOperator, expr: Box<Expr> },
+    /// A parenthesized expression, e.g. `(age + 1)`
+    Nested(Box<Expr>),
+    /// A function call, e.g. `SUM(salary)`, it is a window function if it has an OVER clause
+    Function {
+        name: Ident,
+        args: Vec<Expr>,
+        /// `COUNT(DISTINCT name)`
+        distinct: bool,
+        /// `SUM(salary) FILTER (WHERE awards > 5)`
+        filter: Option<Box<Expr>>,
+        over: Option<WindowSpec>,
+    },
+    /// A parenthesized subquery used as a value, e.g. `(SELECT AVG(salary) FROM salary)`
+    Subquery(Box<Query>),
+    /// `expr [NOT] IN (subquery)`
+    InSubquery {
+        expr: Box<Expr>,
+        subquery: Box<Query>,
+        negated: bool,
+    },
+    /// `expr [NOT] IN (expr, ...)`
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    /// `expr [NOT] BETWEEN low AND high`
+    Between {
+        expr: Box<Expr>,
+        negated: bool,
+        low: Box<Expr>,
+        high: Box<Expr>,
+    },
+    /// `[NOT] EXISTS (subquery)`
+    Exists { subquery: Box<Query>, negated: bool },
+    /// `expr [NOT] { LIKE | ILIKE | SIMILAR TO } pattern [ESCAPE 'c']` or `expr { ~ | !~ } pattern`
+    PatternMatch {
+        kind: PatternKind,
+        negated: bool,
+        expr: Box<Expr>,
+        pattern: Box<Expr>,
+        escape_char: Option<char>,
+    },
+    /// `CAST(expr AS data_type)`
+    Cast {
+        expr: Box<Expr>,
+        data_type: DataType,
+    },
+    /// `TRY_CAST(expr AS data_type)`, NULL if the value cannot be converted
+    TryCast {
+        expr: Box<Expr>,
+        data_type: DataType,
+    },
+    /// `CASE [operand] WHEN condition THEN result ... [ELSE else_result] END`
+    Case {
+        operand: Option<Box<Expr>>,
+        conditions: Vec<Expr>,
+        results: Vec<Expr>,
+        else_result: Option<Box<Expr>>,
+    },
+    /// A parameter of a prepared statement, `$1` or `?`, numbered from 1
+    Placeholder(usize),
+    /// `*` as the only argument of a function, e.g. `COUNT(*)`
+    Wildcard,
+    /// `GROUPING SETS ((a, b), (a), ())`, it can only be used in GROUP BY
+    GroupingSets(Vec<Vec<Expr>>),
+    /// `ROLLUP(a, b)`, the grouping sets `(a, b), (a), ()`
+    Rollup(Vec<Vec<Expr>>),
+    /// `CUBE(a, b)`, the grouping sets `(a, b), (a), (b), ()`
+    Cube(Vec<Vec<Expr>>),
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Identifier(ident) => write!(f, "{}", ident),
+            Expr::CompoundIdentifier(idents) => {
+                let parts = idents.iter().map(|ident| ident.value.as_str());
+                write!(f, "{}", parts.collect::<Vec<_>>().join("."))
+            }
+            Expr::Value(value) => write!(f, "{}", value),
+            Expr::BinaryOp { left, op, right } => write!(f, "{} {} {}", left, op, right),
+            Expr::UnaryOp { op, expr } => match op {
+                UnaryOperator::Not => write!(f, "NOT {}", expr),
+                UnaryOperator::Negative => write!(f, "-{}", expr),
+                op => write!(f, "{} {}", expr, op),
+            },
+            Expr::Nested(expr) => write!(f, "({})", expr),
+            Expr::Function {
+                name,
+                args,
+                distinct,
+                filter,
+                over,
+            } => {
+                let distinct = if *distinct { "DISTINCT " } else { "" };
+                write!(f, "{}({}{})", name, distinct, comma_separated(args))?;
+                if let Some(filter) = filter {
+                    write!(f, " FILTER (WHERE {})", filter)?;
+                }
+                if let Some(over) = over {
+                    write!(f, " OVER ({})", over)?;
+                }
+                Ok(())
+            }
+            Expr::Subquery(_) => write!(f, "(<subquery>)"),
+            Expr::InSubquery { expr, negated, .. } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{} {}IN (<subquery>)", expr, not)
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{} {}IN ({})", expr, not, comma_separated(list))
+            }
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{} {}BETWEEN {} AND {}", expr, not, low, high)
+            }
+            Expr::Exists { negated, .. } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{}EXISTS (<subquery>)", not)
+            }
+            Expr::PatternMatch {
+                kind,
+                negated,
+                expr,
+                pattern,
+                escape_char,
+            } => {
+                write!(f, "{} {} {}", expr, kind.operator(*negated), pattern)?;
+                if let Some(escape_char) = escape_char {
+                    write!(f, " ESCAPE '{}'", escape_char)?;
+                }
+                Ok(())
+            }
+            Expr::Cast { expr, data_type } => write!(f, "CAST({} AS {:?})", expr, data_type),
+            Expr::TryCast { expr, data_type } => {
+                write!(f, "TRY_CAST({} AS {:?})", expr, data_type)
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {}", operand)?;
+                }
+                for (condition, result) in conditions.iter().zip(results) {
+                    write!(f, " WHEN {} THEN {}", condition, result)?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, " ELSE {}", else_result)?;
+                }
+                write!(f, " END")
+            }
+            Expr::Placeholder(id) => write!(f, "${}", id),
+            Expr::Wildcard => write!(f, "*"),
+            Expr::GroupingSets(sets) => write!(f, "GROUPING SETS ({})", grouping_sets(sets, true)),
+            Expr::Rollup(sets) => write!(f, "ROLLUP({})", grouping_sets(sets, false)),
+            Expr::Cube(sets) => write!(f, "CUBE({})", grouping_sets(sets, false)),
+        }
+    }
+}
+
+/// Joins the items with commas, e.g. `a, b, c`
+fn comma_separated<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Formats sets of exprs as `(a, b), (c)`, a set of one expr is not parenthesized
+/// unless `parenthesize` is set
+fn grouping_sets(sets: &[Vec<Expr>], parenthesize: bool) -> String {
+    let sets = sets
+        .iter()
+        .map(|set| match set.as_slice() {
+            [expr] if !parenthesize => expr.to_string(),
+            set => format!("({})", comma_separated(set)),
+        })
+        .collect::<Vec<_>>();
+    sets.join(", ")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The window of a window function, `OVER ([PARTITION BY exprs] [ORDER BY exprs] [frame])`
+pub struct WindowSpec {
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<OrderByExpr>,
+    /// `{ ROWS | RANGE } BETWEEN start AND end`, or `{ ROWS | RANGE } start` which ends
+    /// with the current row
+    pub window_frame: Option<WindowFrame>,
+}
+
+impl Display for WindowSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut clauses = vec![];
+        if !self.partition_by.is_empty() {
+            clauses.push(format!(
+                "PARTITION BY {}",
+                comma_separated(&self.partition_by)
+            ));
+        }
+        if !self.order_by.is_empty() {
+            clauses.push(format!("ORDER BY {}", comma_separated(&self.order_by)));
+        }
+        if let Some(window_frame) = &self.window_frame {
+            clauses.push(window_frame.to_string());
+        }
+        write!(f, "{}", clauses.join(" "))
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A name together with the part of the SQL text it was parsed from,
+/// names are equal if their values are, wherever they are
+pub struct Ident {
+    pub value: String,
+    /// None if the name was not parsed from SQL text
+    pub span: Option<Span>,
+}
+
+impl Ident {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            span: None,
+        }
+    }
+}
+
+impl PartialEq for Ident {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Display for Ident {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// The span from the first to the last of the names, None if any of them has no span
+pub fn join_spans(idents: &[Ident]) -> Option<Span> {
+    let first = idents.first()?.span?;
+    let last = idents.last()?.span?;
+    Some(Span {
+        start: first.start,
+        end: last.end,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Literal values
+pub enum Value {
+    /// Numeric literal, kept as text until the planner decides its type
+    Number(String),
+    /// `'string value'`
+    SingleQuotedString(String),
+    /// `TRUE` or `FALSE`
+    Boolean(bool),
+    /// `NULL`
+    Null,
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::SingleQuotedString(s) => write!(f, "'{}'", s.replace('\'', "''")),
+            Value::Boolean(true) => write!(f, "TRUE"),
+            Value::Boolean(false) => write!(f, "FALSE"),
+            Value::Null => write!(f, "NULL"),
+        }
+    }
+}
@@ -0,0 +1,2265 @@
+use crate::error::{Error, Result};
+use crate::logical_plan::logical_expr::{
+    Operator, PatternKind, UnaryOperator, WindowFrame, WindowFrameBound, WindowFrameUnits,
+};
+use crate::sql::ast::{
+    join_spans, ColumnDef, CreateExternalTable, CreateTable, Cte, Expr, Ident, Join, JoinType,
+    OrderByExpr, Query, Select, SelectItem, SetExpr, SetOperator, Statement, TableFactor,
+    TableWithJoins, Value, WindowSpec, With,
+};
+use crate::sql::tokenizer::{Token, TokenWithLocation, Tokenizer};
+use arrow::datatypes::DataType;
+
+/// Keywords that cannot be used as an implicit alias of a select item or a table,
+/// because they start the next clause of the query.
+const RESERVED_FOR_ALIAS: &[&str] = &[
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "GROUP",
+    "HAVING",
+    "ORDER",
+    "LIMIT",
+    "OFFSET",
+    "JOIN",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "ON",
+    "AND",
+    "OR",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+];
+
+/// Operator precedences, a higher value binds more tightly
+const OR_PREC: u8 = 5;
+const AND_PREC: u8 = 10;
+const NOT_PREC: u8 = UnaryOperator::NOT_PRECEDENCE;
+const CMP_PREC: u8 = 20;
+const PLUS_MINUS_PREC: u8 = 30;
+const MUL_DIV_PREC: u8 = 40;
+const UNARY_MINUS_PREC: u8 = 50;
+
+/// Set operator precedences
+const UNION_EXCEPT_PREC: u8 = 1;
+const INTERSECT_PREC: u8 = 2;
+
+/// Parser builds the SQL AST from the tokens produced by the `Tokenizer`
+pub struct Parser {
+    tokens: Vec<TokenWithLocation>,
+    index: usize,
+    /// Number of `?` placeholders parsed so far, each one takes the next parameter number
+    placeholders: usize,
+}
+
+impl Parser {
+    pub fn new(tokens: Vec<TokenWithLocation>) -> Self {
+        Self {
+            tokens,
+            index: 0,
+            placeholders: 0,
+        }
+    }
+
+    /// Tokenizes and parses a single SQL statement
+    pub fn parse_sql(sql: &str) -> Result<Statement> {
+        let tokens = Tokenizer::new(sql).tokenize()?;
+        let mut parser = Parser::new(tokens);
+        let statement = parser.parse_statement()?;
+
+        // A single trailing semicolon is allowed
+        parser.consume_token(&Token::SemiColon);
+        if parser.peek_token() != &Token::Eof {
+            return parser.expected("end of statement");
+        }
+
+        Ok(statement)
+    }
+
+    pub fn parse_statement(&mut self) -> Result<Statement> {
+        match self.peek_keyword().as_deref() {
+            Some("SELECT") | Some("WITH") | Some("VALUES") => {
+                Ok(Statement::Query(Box::new(self.parse_query()?)))
+            }
+            Some("EXPLAIN") => {
+                self.next_token();
+                let analyze = self.parse_keyword("ANALYZE");
+                let verbose = self.parse_keyword("VERBOSE");
+                Ok(Statement::Explain {
+                    analyze,
+                    verbose,
+                    statement: Box::new(self.parse_statement()?),
+                })
+            }
+            Some("CREATE") => {
+                self.next_token();
+                let or_replace = self.parse_keywords(&["OR", "REPLACE"]);
+                if self.parse_keyword("EXTERNAL") {
+                    self.expect_keyword("TABLE")?;
+                    self.parse_create_external_table(or_replace)
+                } else if self.parse_keyword("TABLE") {
+                    self.parse_create_table(or_replace)
+                } else if self.parse_keyword("VIEW") {
+                    let name = self.parse_identifier()?;
+                    self.expect_keyword("AS")?;
+                    Ok(Statement::CreateView {
+                        name,
+                        query: Box::new(self.parse_query()?),
+                        or_replace,
+                    })
+                } else {
+                    self.expected("TABLE, EXTERNAL TABLE or VIEW after CREATE")
+                }
+            }
+            Some("INSERT") => {
+                self.next_token();
+                self.expect_keyword("INTO")?;
+                Ok(Statement::Insert {
+                    table_name: self.parse_identifier()?,
+                    source: Box::new(self.parse_query()?),
+                })
+            }
+            Some("SHOW") => {
+                self.next_token();
+                if self.parse_keyword("TABLES") {
+                    Ok(Statement::ShowTables)
+                } else if self.parse_keyword("COLUMNS") {
+                    if !self.parse_keyword("IN") {
+                        self.expect_keyword("FROM")?;
+                    }
+                    Ok(Statement::ShowColumns {
+                        table_name: self.parse_object_name()?.value,
+                    })
+                } else {
+                    self.expected("TABLES or COLUMNS after SHOW")
+                }
+            }
+            Some("DESCRIBE") | Some("DESC") => {
+                self.next_token();
+                Ok(Statement::ShowColumns {
+                    table_name: self.parse_object_name()?.value,
+                })
+            }
+            Some("DROP") => {
+                self.next_token();
+                self.expect_keyword("VIEW")?;
+                let if_exists = self.parse_keywords(&["IF", "EXISTS"]);
+                Ok(Statement::DropView {
+                    name: self.parse_identifier()?,
+                    if_exists,
+                })
+            }
+            _ => self.expected("a SQL statement"),
+        }
+    }
+
+    /// Parses the statement after `CREATE [OR REPLACE] EXTERNAL TABLE`,
+    /// the options following the columns may be given in any order
+    fn parse_create_external_table(&mut self, or_replace: bool) -> Result<Statement> {
+        let if_not_exists = self.parse_keywords(&["IF", "NOT", "EXISTS"]);
+        if or_replace && if_not_exists {
+            return Err(Error::ParserError(
+                "OR REPLACE and IF NOT EXISTS cannot be used together".to_string(),
+            ));
+        }
+        let name = self.parse_identifier()?;
+
+        let columns = if self.consume_token(&Token::LParen) {
+            let columns = self.parse_comma_separated(Parser::parse_column_def)?;
+            self.expect_token(&Token::RParen)?;
+            columns
+        } else {
+            vec![]
+        };
+
+        let mut file_type = None;
+        let mut has_header = false;
+        let mut delimiter = ',';
+        let mut location = None;
+        loop {
+            if self.parse_keywords(&["STORED", "AS"]) {
+                file_type = Some(self.parse_identifier()?.to_uppercase());
+            } else if self.parse_keywords(&["WITH", "HEADER"]) {
+                self.parse_keyword("ROW");
+                has_header = true;
+            } else if self.parse_keyword("DELIMITER") {
+                let value = self.parse_string_literal()?;
+                let mut chars = value.chars();
+                delimiter = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return self.expected("a single character delimiter"),
+                };
+            } else if self.parse_keyword("LOCATION") {
+                location = Some(self.parse_string_literal()?);
+            } else {
+                break;
+            }
+        }
+
+        Ok(Statement::CreateExternalTable(CreateExternalTable {
+            name,
+            columns,
+            file_type: match file_type {
+                Some(file_type) => file_type,
+                None => return self.expected("STORED AS"),
+            },
+            has_header,
+            delimiter,
+            location: match location {
+                Some(location) => location,
+                None => return self.expected("LOCATION"),
+            },
+            if_not_exists,
+            or_replace,
+        }))
+    }
+
+    /// Parses the statement after `CREATE [OR REPLACE] TABLE`
+    fn parse_create_table(&mut self, or_replace: bool) -> Result<Statement> {
+        let if_not_exists = self.parse_keywords(&["IF", "NOT", "EXISTS"]);
+        if or_replace && if_not_exists {
+            return Err(Error::ParserError(
+                "OR REPLACE and IF NOT EXISTS cannot be used together".to_string(),
+            ));
+        }
+        let name = self.parse_identifier()?;
+
+        let columns = if self.consume_token(&Token::LParen) {
+            let columns = self.parse_comma_separated(Parser::parse_column_def)?;
+            self.expect_token(&Token::RParen)?;
+            columns
+        } else {
+            vec![]
+        };
+
+        let query = if self.parse_keyword("AS") {
+            Some(Box::new(self.parse_query()?))
+        } else if columns.is_empty() {
+            return self.expected("a column list or AS");
+        } else {
+            None
+        };
+
+        Ok(Statement::CreateTable(CreateTable {
+            name,
+            columns,
+            query,
+            if_not_exists,
+            or_replace,
+        }))
+    }
+
+    /// Parses `name type [NULL | NOT NULL]`
+    fn parse_column_def(&mut self) -> Result<ColumnDef> {
+        let name = self.parse_identifier()?;
+        let data_type = self.parse_data_type()?;
+        let nullable = if self.parse_keywords(&["NOT", "NULL"]) {
+            false
+        } else {
+            self.parse_keyword("NULL");
+            true
+        };
+        Ok(ColumnDef {
+            name,
+            data_type,
+            nullable,
+        })
+    }
+
+    fn parse_string_literal(&mut self) -> Result<String> {
+        match self.peek_token().clone() {
+            Token::SingleQuotedString(value) => {
+                self.next_token();
+                Ok(value)
+            }
+            _ => self.expected("a string literal"),
+        }
+    }
+
+    pub fn parse_query(&mut self) -> Result<Query> {
+        let with = if self.parse_keyword("WITH") {
+            Some(self.parse_with()?)
+        } else {
+            None
+        };
+
+        let body = self.parse_set_expr()?;
+
+        let order_by = if self.parse_keywords(&["ORDER", "BY"]) {
+            self.parse_comma_separated(Parser::parse_order_by_expr)?
+        } else {
+            vec![]
+        };
+
+        let mut limit = None;
+        let mut offset = None;
+
+        // LIMIT and OFFSET may appear in either order
+        for _ in 0..2 {
+            if limit.is_none() && self.parse_keyword("LIMIT") {
+                limit = if self.parse_keyword("ALL") {
+                    None
+                } else {
+                    Some(self.parse_expr()?)
+                };
+            } else if offset.is_none() && self.parse_keyword("OFFSET") {
+                offset = Some(self.parse_expr()?);
+                if !self.parse_keyword("ROW") {
+                    self.parse_keyword("ROWS");
+                }
+            }
+        }
+
+        Ok(Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        })
+    }
+
+    /// Parses the common table expressions after `WITH`
+    fn parse_with(&mut self) -> Result<With> {
+        let recursive = self.parse_keyword("RECURSIVE");
+        let cte_tables = self.parse_comma_separated(Parser::parse_cte)?;
+        Ok(With {
+            recursive,
+            cte_tables,
+        })
+    }
+
+    /// Parses `alias [(column, ...)] AS (query)`
+    fn parse_cte(&mut self) -> Result<Cte> {
+        let alias = self.parse_identifier()?;
+
+        let columns = if self.consume_token(&Token::LParen) {
+            let columns = self.parse_comma_separated(Parser::parse_identifier)?;
+            self.expect_token(&Token::RParen)?;
+            columns
+        } else {
+            vec![]
+        };
+
+        self.expect_keyword("AS")?;
+        self.expect_token(&Token::LParen)?;
+        let query = Box::new(self.parse_query()?);
+        self.expect_token(&Token::RParen)?;
+
+        Ok(Cte {
+            alias,
+            columns,
+            query,
+        })
+    }
+
+    /// Parses queries combined with set operators, which are left-associative.
+    /// INTERSECT binds more tightly than UNION and EXCEPT.
+    fn parse_set_expr(&mut self) -> Result<SetExpr> {
+        self.parse_set_operations(UNION_EXCEPT_PREC)
+    }
+
+    fn parse_set_operations(&mut self, precedence: u8) -> Result<SetExpr> {
+        let mut expr = if precedence == UNION_EXCEPT_PREC {
+            self.parse_set_operations(INTERSECT_PREC)?
+        } else {
+            self.parse_set_term()?
+        };
+
+        loop {
+            let op = match self.peek_keyword().as_deref() {
+                Some("UNION") if precedence == UNION_EXCEPT_PREC => SetOperator::Union,
+                Some("EXCEPT") if precedence == UNION_EXCEPT_PREC => SetOperator::Except,
+                Some("INTERSECT") if precedence == INTERSECT_PREC => SetOperator::Intersect,
+                _ => break,
+            };
+            self.next_token();
+
+            let all = self.parse_keyword("ALL");
+            if !all {
+                self.parse_keyword("DISTINCT");
+            }
+            let right = if precedence == UNION_EXCEPT_PREC {
+                self.parse_set_operations(INTERSECT_PREC)?
+            } else {
+                self.parse_set_term()?
+            };
+            expr = SetExpr::SetOperation {
+                op,
+                all,
+                left: Box::new(expr),
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses a SELECT, VALUES or a query in parentheses
+    fn parse_set_term(&mut self) -> Result<SetExpr> {
+        if self.consume_token(&Token::LParen) {
+            let query = self.parse_query()?;
+            self.expect_token(&Token::RParen)?;
+            return Ok(SetExpr::Query(Box::new(query)));
+        }
+        if self.parse_keyword("VALUES") {
+            let rows = self.parse_comma_separated(|parser| {
+                parser.expect_token(&Token::LParen)?;
+                let row = parser.parse_comma_separated(Parser::parse_expr)?;
+                parser.expect_token(&Token::RParen)?;
+                Ok(row)
+            })?;
+            return Ok(SetExpr::Values(rows));
+        }
+        Ok(SetExpr::Select(Box::new(self.parse_select()?)))
+    }
+
+    fn parse_order_by_expr(&mut self) -> Result<OrderByExpr> {
+        let expr = self.parse_expr()?;
+
+        let asc = if self.parse_keyword("ASC") {
+            Some(true)
+        } else if self.parse_keyword("DESC") {
+            Some(false)
+        } else {
+            None
+        };
+
+        let nulls_first = if self.parse_keywords(&["NULLS", "FIRST"]) {
+            Some(true)
+        } else if self.parse_keywords(&["NULLS", "LAST"]) {
+            Some(false)
+        } else {
+            None
+        };
+
+        Ok(OrderByExpr {
+            expr,
+            asc,
+            nulls_first,
+        })
+    }
+
+    fn parse_select(&mut self) -> Result<Select> {
+        self.expect_keyword("SELECT")?;
+        let distinct = self.parse_keyword("DISTINCT");
+        if !distinct {
+            self.parse_keyword("ALL");
+        }
+        let projection = self.parse_comma_separated(Parser::parse_select_item)?;
+
+        let from = if self.parse_keyword("FROM") {
+            Some(self.parse_table_and_joins()?)
+        } else {
+            None
+        };
+
+        let selection = if self.parse_keyword("WHERE") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let group_by = if self.parse_keywords(&["GROUP", "BY"]) {
+            self.parse_comma_separated(Parser::parse_group_by_item)?
+        } else {
+            vec![]
+        };
+
+        let having = if self.parse_keyword("HAVING") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok(Select {
+            distinct,
+            projection,
+            from,
+            selection,
+            group_by,
+            having,
+        })
+    }
+
+    /// Parses an expression of the GROUP BY clause, which may also be
+    /// `ROLLUP(elements)`, `CUBE(elements)` or `GROUPING SETS (elements)`
+    fn parse_group_by_item(&mut self) -> Result<Expr> {
+        let is_call = self.peek_nth_token(1) == &Token::LParen;
+        let keyword = self.peek_keyword();
+        let constructor = match keyword.as_deref() {
+            Some("ROLLUP") if is_call => Expr::Rollup,
+            Some("CUBE") if is_call => Expr::Cube,
+            Some("GROUPING") if self.peek_nth_keyword(1).as_deref() == Some("SETS") => {
+                self.next_token();
+                Expr::GroupingSets
+            }
+            _ => return self.parse_expr(),
+        };
+        self.next_token();
+
+        self.expect_token(&Token::LParen)?;
+        let elements = self.parse_comma_separated(Parser::parse_grouping_element)?;
+        self.expect_token(&Token::RParen)?;
+        Ok(constructor(elements))
+    }
+
+    /// Parses an element of a grouping construct, an expression or a parenthesized list
+    /// of expressions, `()` is the empty set
+    fn parse_grouping_element(&mut self) -> Result<Vec<Expr>> {
+        if !self.consume_token(&Token::LParen) {
+            return Ok(vec![self.parse_expr()?]);
+        }
+        if self.consume_token(&Token::RParen) {
+            return Ok(vec![]);
+        }
+        let exprs = self.parse_comma_separated(Parser::parse_expr)?;
+        self.expect_token(&Token::RParen)?;
+        Ok(exprs)
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem> {
+        if self.consume_token(&Token::Mul) {
+            return Ok(SelectItem::Wildcard);
+        }
+
+        let expr = self.parse_expr()?;
+        match self.parse_optional_alias()? {
+            Some(alias) => Ok(SelectItem::ExprWithAlias { expr, alias }),
+            None => Ok(SelectItem::UnnamedExpr(expr)),
+        }
+    }
+
+    /// Parses `[AS] alias`, the `AS` keyword is optional
+    fn parse_optional_alias(&mut self) -> Result<Option<String>> {
+        if self.parse_keyword("AS") {
+            return Ok(Some(self.parse_identifier()?));
+        }
+
+        match self.peek_token() {
+            Token::Word(word) => match word.keyword() {
+                Some(keyword) if RESERVED_FOR_ALIAS.contains(&keyword.as_str()) => Ok(None),
+                _ => Ok(Some(self.parse_identifier()?)),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_table_and_joins(&mut self) -> Result<TableWithJoins> {
+        let relation = self.parse_table_factor()?;
+
+        let mut joins = vec![];
+        loop {
+            let join_type = if self.parse_keyword("JOIN") || self.parse_keywords(&["INNER", "JOIN"])
+            {
+                JoinType::Inner
+            } else if self.parse_keyword("LEFT") {
+                self.parse_keyword("OUTER");
+                self.expect_keyword("JOIN")?;
+                JoinType::Left
+            } else if self.parse_keyword("RIGHT") {
+                self.parse_keyword("OUTER");
+                self.expect_keyword("JOIN")?;
+                JoinType::Right
+            } else {
+                break;
+            };
+
+            let relation = self.parse_table_factor()?;
+            self.expect_keyword("ON")?;
+            let constraint = self.parse_expr()?;
+
+            joins.push(Join {
+                relation,
+                join_type,
+                constraint,
+            });
+        }
+
+        Ok(TableWithJoins { relation, joins })
+    }
+
+    fn parse_table_factor(&mut self) -> Result<TableFactor> {
+        if self.consume_token(&Token::LParen) {
+            let subquery = Box::new(self.parse_query()?);
+            self.expect_token(&Token::RParen)?;
+            let alias = self.parse_optional_alias()?;
+            return Ok(TableFactor::Derived { subquery, alias });
+        }
+
+        let name = self.parse_object_name()?;
+        let alias = self.parse_optional_alias()?;
+        Ok(TableFactor::Table { name, alias })
+    }
+
+    /// Parses a possibly qualified name, e.g. `information_schema.tables`
+    fn parse_object_name(&mut self) -> Result<Ident> {
+        let mut parts = vec![self.parse_ident()?];
+        while self.consume_token(&Token::Period) {
+            parts.push(self.parse_ident()?);
+        }
+        let value = parts
+            .iter()
+            .map(|part| part.value.as_str())
+            .collect::<Vec<_>>()
+            .join(".");
+        Ok(Ident {
+            value,
+            span: join_spans(&parts),
+        })
+    }
+
+    pub fn parse_expr(&mut self) -> Result<Expr> {
+        self.parse_subexpr(0)
+    }
+
+    /// Parses an expression whose operators all bind more tightly than `precedence`
+    fn parse_subexpr(&mut self, precedence: u8) -> Result<Expr> {
+        let mut expr = self.parse_prefix()?;
+
+        loop {
+            let next_precedence = self.next_precedence();
+            if precedence >= next_precedence {
+                break;
+            }
+            expr = self.parse_infix(expr, next_precedence)?;
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expr> {
+        let start = self.index;
+        let token = self.next_token().clone();
+        let span = token.span();
+
+        match token.token {
+            Token::Word(word) => match word.keyword().as_deref() {
+                Some("TRUE") => Ok(Expr::Value(Value::Boolean(true))),
+                Some("FALSE") => Ok(Expr::Value(Value::Boolean(false))),
+                Some("NULL") => Ok(Expr::Value(Value::Null)),
+                Some("EXISTS") => self.parse_exists(false),
+                Some("CASE") => self.parse_case(),
+                Some("CAST") if self.peek_token() == &Token::LParen => self.parse_cast(false),
+                Some("TRY_CAST") if self.peek_token() == &Token::LParen => self.parse_cast(true),
+                Some("NOT") if self.parse_keyword("EXISTS") => self.parse_exists(true),
+                Some("NOT") => Ok(Expr::UnaryOp {
+                    op: UnaryOperator::Not,
+                    expr: Box::new(self.parse_subexpr(NOT_PREC)?),
+                }),
+                _ => {
+                    if self.consume_token(&Token::LParen) {
+                        let distinct = self.parse_keyword("DISTINCT");
+                        let args = if !distinct && self.consume_token(&Token::RParen) {
+                            vec![]
+                        } else if !distinct
+                            && self.peek_token() == &Token::Mul
+                            && self.peek_nth_token(1) == &Token::RParen
+                        {
+                            self.next_token();
+                            self.next_token();
+                            vec![Expr::Wildcard]
+                        } else {
+                            let args = self.parse_comma_separated(Parser::parse_expr)?;
+                            self.expect_token(&Token::RParen)?;
+                            args
+                        };
+                        // FILTER is only a keyword here if it is followed by a parenthesis,
+                        // otherwise it may be an alias
+                        let filter = if self.peek_keyword().as_deref() == Some("FILTER")
+                            && self.peek_nth_token(1) == &Token::LParen
+                        {
+                            self.next_token();
+                            self.expect_token(&Token::LParen)?;
+                            self.expect_keyword("WHERE")?;
+                            let filter = self.parse_expr()?;
+                            self.expect_token(&Token::RParen)?;
+                            Some(Box::new(filter))
+                        } else {
+                            None
+                        };
+                        let over = if self.parse_keyword("OVER") {
+                            Some(self.parse_window_spec()?)
+                        } else {
+                            None
+                        };
+                        return Ok(Expr::Function {
+                            name: Ident {
+                                value: word.value,
+                                span: Some(span),
+                            },
+                            args,
+                            distinct,
+                            filter,
+                            over,
+                        });
+                    }
+
+                    let mut idents = vec![Ident {
+                        value: word.value,
+                        span: Some(span),
+                    }];
+                    while self.consume_token(&Token::Period) {
+                        idents.push(self.parse_ident()?);
+                    }
+
+                    if idents.len() == 1 {
+                        Ok(Expr::Identifier(idents.remove(0)))
+                    } else {
+                        Ok(Expr::CompoundIdentifier(idents))
+                    }
+                }
+            },
+            Token::Number(number) => Ok(Expr::Value(Value::Number(number))),
+            // A negative number is a literal, so that e.g. -9223372036854775808 fits into Int64
+            Token::Minus => match self.peek_token().clone() {
+                Token::Number(number) => {
+                    self.next_token();
+                    Ok(Expr::Value(Value::Number(format!("-{}", number))))
+                }
+                _ => Ok(Expr::UnaryOp {
+                    op: UnaryOperator::Negative,
+                    expr: Box::new(self.parse_subexpr(UNARY_MINUS_PREC)?),
+                }),
+            },
+            Token::SingleQuotedString(s) => Ok(Expr::Value(Value::SingleQuotedString(s))),
+            Token::Placeholder(placeholder) => {
+                if placeholder == "?" {
+                    self.placeholders += 1;
+                    return Ok(Expr::Placeholder(self.placeholders));
+                }
+                match placeholder[1..].parse::<usize>() {
+                    Ok(id) if id > 0 => Ok(Expr::Placeholder(id)),
+                    _ => Err(Error::ParserError(format!(
+                        "Invalid parameter {} at {}, parameters are numbered from $1",
+                        placeholder, token.location
+                    ))),
+                }
+            }
+            Token::LParen => {
+                if self.peek_query() {
+                    let subquery = self.parse_query()?;
+                    self.expect_token(&Token::RParen)?;
+                    return Ok(Expr::Subquery(Box::new(subquery)));
+                }
+                let expr = self.parse_expr()?;
+                self.expect_token(&Token::RParen)?;
+                Ok(Expr::Nested(Box::new(expr)))
+            }
+            _ => {
+                self.index = start;
+                self.expected("an expression")
+            }
+        }
+    }
+
+    /// Parses `([PARTITION BY exprs] [ORDER BY exprs] [frame])` after `OVER`
+    fn parse_window_spec(&mut self) -> Result<WindowSpec> {
+        self.expect_token(&Token::LParen)?;
+
+        let partition_by = if self.parse_keywords(&["PARTITION", "BY"]) {
+            self.parse_comma_separated(Parser::parse_expr)?
+        } else {
+            vec![]
+        };
+        let order_by = if self.parse_keywords(&["ORDER", "BY"]) {
+            self.parse_comma_separated(Parser::parse_order_by_expr)?
+        } else {
+            vec![]
+        };
+        let window_frame = match self.peek_keyword().as_deref() {
+            Some("ROWS" | "RANGE") => Some(self.parse_window_frame()?),
+            _ => None,
+        };
+
+        self.expect_token(&Token::RParen)?;
+        Ok(WindowSpec {
+            partition_by,
+            order_by,
+            window_frame,
+        })
+    }
+
+    /// Parses `{ ROWS | RANGE } { BETWEEN start AND end | start }`
+    fn parse_window_frame(&mut self) -> Result<WindowFrame> {
+        let units = if self.parse_keyword("ROWS") {
+            WindowFrameUnits::Rows
+        } else {
+            self.expect_keyword("RANGE")?;
+            WindowFrameUnits::Range
+        };
+
+        let (start_bound, end_bound) = if self.parse_keyword("BETWEEN") {
+            let start_bound = self.parse_window_frame_bound()?;
+            self.expect_keyword("AND")?;
+            (start_bound, self.parse_window_frame_bound()?)
+        } else {
+            (
+                self.parse_window_frame_bound()?,
+                WindowFrameBound::CurrentRow,
+            )
+        };
+
+        Ok(WindowFrame {
+            units,
+            start_bound,
+            end_bound,
+        })
+    }
+
+    /// Parses `CURRENT ROW`, `UNBOUNDED { PRECEDING | FOLLOWING }` or `N { PRECEDING | FOLLOWING }`
+    fn parse_window_frame_bound(&mut self) -> Result<WindowFrameBound> {
+        if self.parse_keywords(&["CURRENT", "ROW"]) {
+            return Ok(WindowFrameBound::CurrentRow);
+        }
+
+        let offset = if self.parse_keyword("UNBOUNDED") {
+            None
+        } else {
+            match self.peek_token().clone() {
+                Token::Number(number) => match number.parse::<usize>() {
+                    Ok(offset) => {
+                        self.next_token();
+                        Some(offset)
+                    }
+                    Err(_) => return self.expected("a non-negative integer offset"),
+                },
+                _ => return self.expected("a window frame bound"),
+            }
+        };
+
+        if self.parse_keyword("PRECEDING") {
+            Ok(offset.map_or(
+                WindowFrameBound::UnboundedPreceding,
+                WindowFrameBound::Preceding,
+            ))
+        } else if self.parse_keyword("FOLLOWING") {
+            Ok(offset.map_or(
+                WindowFrameBound::UnboundedFollowing,
+                WindowFrameBound::Following,
+            ))
+        } else {
+            self.expected("PRECEDING or FOLLOWING")
+        }
+    }
+
+    fn parse_infix(&mut self, left: Expr, precedence: u8) -> Result<Expr> {
+        let start = self.index;
+        let token = self.next_token().clone();
+
+        let op = match &token.token {
+            Token::Eq => Operator::Eq,
+            Token::Neq => Operator::Neq,
+            Token::Lt => Operator::Lt,
+            Token::LtEq => Operator::LtEq,
+            Token::Gt => Operator::Gt,
+            Token::GtEq => Operator::GtEq,
+            Token::Plus => Operator::Add,
+            Token::Minus => Operator::Sub,
+            Token::Mul => Operator::Mul,
+            Token::Div => Operator::Div,
+            Token::Mod => Operator::Mod,
+            Token::Tilde => return self.parse_pattern_match(left, PatternKind::Regex, false),
+            Token::ExclamationMarkTilde => {
+                return self.parse_pattern_match(left, PatternKind::Regex, true)
+            }
+            Token::Word(word) => match word.keyword().as_deref() {
+                Some("AND") => Operator::And,
+                Some("OR") => Operator::Or,
+                Some("IN") => return self.parse_in(left, false),
+                Some("IS") => return self.parse_is(left),
+                Some("BETWEEN") => return self.parse_between(left, false),
+                Some("NOT") if self.parse_keyword("IN") => return self.parse_in(left, true),
+                Some("NOT") if self.parse_keyword("BETWEEN") => {
+                    return self.parse_between(left, true)
+                }
+                Some("NOT") => match self.parse_pattern_kind() {
+                    Some(kind) => return self.parse_pattern_match(left, kind, true),
+                    None => {
+                        self.index = start;
+                        return self.expected("an operator");
+                    }
+                },
+                Some("LIKE") => return self.parse_pattern_match(left, PatternKind::Like, false),
+                Some("ILIKE") => return self.parse_pattern_match(left, PatternKind::ILike, false),
+                Some("SIMILAR") if self.parse_keyword("TO") => {
+                    return self.parse_pattern_match(left, PatternKind::SimilarTo, false)
+                }
+                _ => {
+                    self.index = start;
+                    return self.expected("an operator");
+                }
+            },
+            _ => {
+                self.index = start;
+                return self.expected("an operator");
+            }
+        };
+
+        Ok(Expr::BinaryOp {
+            left: Box::new(left),
+            op,
+            right: Box::new(self.parse_subexpr(precedence)?),
+        })
+    }
+
+    /// Parses `[NOT] EXISTS (subquery)` after the `EXISTS` keyword
+    fn parse_exists(&mut self, negated: bool) -> Result<Expr> {
+        self.expect_token(&Token::LParen)?;
+        let subquery = Box::new(self.parse_query()?);
+        self.expect_token(&Token::RParen)?;
+        Ok(Expr::Exists { subquery, negated })
+    }
+
+    /// Parses `[operand] WHEN condition THEN result ... [ELSE else_result] END` after `CASE`
+    fn parse_case(&mut self) -> Result<Expr> {
+        let operand = if self.peek_keyword().as_deref() == Some("WHEN") {
+            None
+        } else {
+            Some(Box::new(self.parse_expr()?))
+        };
+
+        let mut conditions = vec![];
+        let mut results = vec![];
+        self.expect_keyword("WHEN")?;
+        loop {
+            conditions.push(self.parse_expr()?);
+            self.expect_keyword("THEN")?;
+            results.push(self.parse_expr()?);
+            if !self.parse_keyword("WHEN") {
+                break;
+            }
+        }
+
+        let else_result = if self.parse_keyword("ELSE") {
+            Some(Box::new(self.parse_expr()?))
+        } else {
+            None
+        };
+        self.expect_keyword("END")?;
+
+        Ok(Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        })
+    }
+
+    /// Parses `(expr AS data_type)` after `CAST` or `TRY_CAST`
+    fn parse_cast(&mut self, try_cast: bool) -> Result<Expr> {
+        self.expect_token(&Token::LParen)?;
+        let expr = Box::new(self.parse_expr()?);
+        self.expect_keyword("AS")?;
+        let data_type = self.parse_data_type()?;
+        self.expect_token(&Token::RParen)?;
+
+        if try_cast {
+            Ok(Expr::TryCast { expr, data_type })
+        } else {
+            Ok(Expr::Cast { expr, data_type })
+        }
+    }
+
+    fn parse_data_type(&mut self) -> Result<DataType> {
+        let data_type = match self.peek_keyword().as_deref() {
+            Some("INT" | "INTEGER" | "BIGINT") => {
+                self.next_token();
+                if self.parse_keyword("UNSIGNED") {
+                    DataType::UInt64
+                } else {
+                    DataType::Int64
+                }
+            }
+            Some("DOUBLE") => {
+                self.next_token();
+                self.parse_keyword("PRECISION");
+                DataType::Float64
+            }
+            Some("FLOAT" | "REAL") => {
+                self.next_token();
+                DataType::Float64
+            }
+            Some("VARCHAR" | "CHAR" | "TEXT" | "STRING") => {
+                self.next_token();
+                // The length is accepted but not enforced
+                if self.consume_token(&Token::LParen) {
+                    match self.next_token().token {
+                        Token::Number(_) => {}
+                        _ => return self.expected("a length"),
+                    }
+                    self.expect_token(&Token::RParen)?;
+                }
+                DataType::Utf8
+            }
+            Some("BOOLEAN" | "BOOL") => {
+                self.next_token();
+                DataType::Boolean
+            }
+            Some("DATE") => {
+                self.next_token();
+                DataType::Date32
+            }
+            _ => return self.expected("a data type"),
+        };
+        Ok(data_type)
+    }
+
+    /// Parses `(subquery)` or `(expr, ...)` after `expr [NOT] IN`
+    fn parse_in(&mut self, expr: Expr, negated: bool) -> Result<Expr> {
+        self.expect_token(&Token::LParen)?;
+        if !self.peek_query() {
+            let list = self.parse_comma_separated(Parser::parse_expr)?;
+            self.expect_token(&Token::RParen)?;
+            return Ok(Expr::InList {
+                expr: Box::new(expr),
+                list,
+                negated,
+            });
+        }
+        let subquery = Box::new(self.parse_query()?);
+        self.expect_token(&Token::RParen)?;
+        Ok(Expr::InSubquery {
+            expr: Box::new(expr),
+            subquery,
+            negated,
+        })
+    }
+
+    /// Parses `low AND high` after `expr [NOT] BETWEEN`, the bounds cannot contain AND or OR
+    fn parse_between(&mut self, expr: Expr, negated: bool) -> Result<Expr> {
+        let low = self.parse_subexpr(CMP_PREC)?;
+        self.expect_keyword("AND")?;
+        let high = self.parse_subexpr(CMP_PREC)?;
+        Ok(Expr::Between {
+            expr: Box::new(expr),
+            negated,
+            low: Box::new(low),
+            high: Box::new(high),
+        })
+    }
+
+    /// Parses `[NOT] { NULL | TRUE | FALSE | DISTINCT FROM expr }` after `expr IS`
+    fn parse_is(&mut self, expr: Expr) -> Result<Expr> {
+        let negated = self.parse_keyword("NOT");
+        let op = match self.peek_keyword().as_deref() {
+            Some("NULL") if negated => UnaryOperator::IsNotNull,
+            Some("NULL") => UnaryOperator::IsNull,
+            Some("TRUE") if negated => UnaryOperator::IsNotTrue,
+            Some("TRUE") => UnaryOperator::IsTrue,
+            Some("FALSE") if negated => UnaryOperator::IsNotFalse,
+            Some("FALSE") => UnaryOperator::IsFalse,
+            Some("DISTINCT") => {
+                self.next_token();
+                self.expect_keyword("FROM")?;
+                let op = if negated {
+                    Operator::IsNotDistinctFrom
+                } else {
+                    Operator::IsDistinctFrom
+                };
+                return Ok(Expr::BinaryOp {
+                    left: Box::new(expr),
+                    op,
+                    right: Box::new(self.parse_subexpr(CMP_PREC)?),
+                });
+            }
+            _ => return self.expected("NULL, TRUE, FALSE or DISTINCT FROM"),
+        };
+        self.next_token();
+
+        Ok(Expr::UnaryOp {
+            op,
+            expr: Box::new(expr),
+        })
+    }
+
+    /// Consumes `LIKE`, `ILIKE` or `SIMILAR TO` following `NOT`
+    fn parse_pattern_kind(&mut self) -> Option<PatternKind> {
+        if self.parse_keyword("LIKE") {
+            Some(PatternKind::Like)
+        } else if self.parse_keyword("ILIKE") {
+            Some(PatternKind::ILike)
+        } else if self.parse_keywords(&["SIMILAR", "TO"]) {
+            Some(PatternKind::SimilarTo)
+        } else {
+            None
+        }
+    }
+
+    /// Parses `pattern [ESCAPE 'c']` after the operator of a pattern match,
+    /// only LIKE, ILIKE and SIMILAR TO have an escape character
+    fn parse_pattern_match(
+        &mut self,
+        expr: Expr,
+        kind: PatternKind,
+        negated: bool,
+    ) -> Result<Expr> {
+        let pattern = self.parse_subexpr(CMP_PREC)?;
+        let escape_char = if kind != PatternKind::Regex && self.parse_keyword("ESCAPE") {
+            match self.peek_token().clone() {
+                Token::SingleQuotedString(s) if s.chars().count() == 1 => {
+                    self.next_token();
+                    s.chars().next()
+                }
+                _ => return self.expected("a single character"),
+            }
+        } else {
+            None
+        };
+
+        Ok(Expr::PatternMatch {
+            kind,
+            negated,
+            expr: Box::new(expr),
+            pattern: Box::new(pattern),
+            escape_char,
+        })
+    }
+
+    /// Gets the precedence of the next token, 0 if it is not an infix operator
+    fn next_precedence(&self) -> u8 {
+        match self.peek_token() {
+            Token::Word(word) => match word.keyword().as_deref() {
+                Some("OR") => OR_PREC,
+                Some("AND") => AND_PREC,
+                Some("IN") | Some("IS") | Some("BETWEEN") | Some("LIKE") | Some("ILIKE")
+                | Some("SIMILAR") => CMP_PREC,
+                Some("NOT")
+                    if matches!(
+                        self.peek_nth_keyword(1).as_deref(),
+                        Some("IN")
+                            | Some("BETWEEN")
+                            | Some("LIKE")
+                            | Some("ILIKE")
+                            | Some("SIMILAR")
+                    ) =>
+                {
+                    CMP_PREC
+                }
+                _ => 0,
+            },
+            Token::Eq | Token::Neq | Token::Lt | Token::LtEq | Token::Gt | Token::GtEq => CMP_PREC,
+            Token::Tilde | Token::ExclamationMarkTilde => CMP_PREC,
+            Token::Plus | Token::Minus => PLUS_MINUS_PREC,
+            Token::Mul | Token::Div | Token::Mod => MUL_DIV_PREC,
+            _ => 0,
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String> {
+        Ok(self.parse_ident()?.value)
+    }
+
+    /// Parses an identifier together with where it is in the SQL text
+    fn parse_ident(&mut self) -> Result<Ident> {
+        match self.peek_token().clone() {
+            Token::Word(word) => Ok(Ident {
+                value: word.value,
+                span: Some(self.next_token().span()),
+            }),
+            _ => self.expected("an identifier"),
+        }
+    }
+
+    fn parse_comma_separated<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Parser) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut values = vec![f(self)?];
+        while self.consume_token(&Token::Comma) {
+            values.push(f(self)?);
+        }
+        Ok(values)
+    }
+
+    fn peek_token(&self) -> &Token {
+        &self.tokens[self.index].token
+    }
+
+    /// Gets the token `n` tokens after the next token, EOF if there are not enough tokens
+    fn peek_nth_token(&self, n: usize) -> &Token {
+        let index = (self.index + n).min(self.tokens.len() - 1);
+        &self.tokens[index].token
+    }
+
+    fn peek_keyword(&self) -> Option<String> {
+        self.peek_nth_keyword(0)
+    }
+
+    /// Whether the next token starts a query
+    fn peek_query(&self) -> bool {
+        matches!(
+            self.peek_keyword().as_deref(),
+            Some("SELECT") | Some("WITH") | Some("VALUES")
+        )
+    }
+
+    /// Gets the keyword `n` tokens after the next token, if there is one
+    fn peek_nth_keyword(&self, n: usize) -> Option<String> {
+        let index = (self.index + n).min(self.tokens.len() - 1);
+        match &self.tokens[index].token {
+            Token::Word(word) => word.keyword(),
+            _ => None,
+        }
+    }
+
+    fn next_token(&mut self) -> &TokenWithLocation {
+        let token = &self.tokens[self.index];
+        // The last token is always EOF, never move past it
+        if self.index < self.tokens.len() - 1 {
+            self.index += 1;
+        }
+        token
+    }
+
+    /// Consumes the next token if it matches the expected token
+    fn consume_token(&mut self, expected: &Token) -> bool {
+        if self.peek_token() == expected {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_token(&mut self, expected: &Token) -> Result<()> {
+        if self.consume_token(expected) {
+            Ok(())
+        } else {
+            self.expected(&expected.to_string())
+        }
+    }
+
+    /// Consumes the next token if it is the expected keyword
+    fn parse_keyword(&mut self, expected: &str) -> bool {
+        if self.peek_keyword().as_deref() == Some(expected) {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the next tokens only if all of them match the expected keywords
+    fn parse_keywords(&mut self, expected: &[&str]) -> bool {
+        let index = self.index;
+        for keyword in expected {
+            if !self.parse_keyword(keyword) {
+                self.index = index;
+                return false;
+            }
+        }
+        true
+    }
+
+    fn expect_keyword(&mut self, expected: &str) -> Result<()> {
+        if self.parse_keyword(expected) {
+            Ok(())
+        } else {
+            self.expected(expected)
+        }
+    }
+
+    /// Reports that something else was found where `expected` should be
+    fn expected<T>(&self, expected: &str) -> Result<T> {
+        let found = &self.tokens[self.index];
+        Err(Error::ParserError(format!(
+            "Expected {}, found {} at {}",
+            expected, found.token, found.location
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::tokenizer::{Location, Span};
+
+    fn ident(name: &str) -> Box<Expr> {
+        Box::new(Expr::Identifier(Ident::new(name)))
+    }
+
+    fn select(query: &Query) -> &Select {
+        match &query.body {
+            SetExpr::Select(select) => select,
+            body => panic!("Expected a SELECT, found {:?}", body),
+        }
+    }
+
+    #[test]
+    fn parse_select_with_clauses() -> Result<()> {
+        let statement = Parser::parse_sql(
+            "SELECT name, SUM(salary) AS total FROM salary WHERE awards > 5 OR id = 1 GROUP BY name",
+        )?;
+
+        let expected = Statement::Query(Box::new(Query {
+            with: None,
+            body: SetExpr::Select(Box::new(Select {
+                distinct: false,
+                projection: vec![
+                    SelectItem::UnnamedExpr(Expr::Identifier(Ident::new("name"))),
+                    SelectItem::ExprWithAlias {
+                        expr: Expr::Function {
+                            name: Ident::new("SUM"),
+                            args: vec![Expr::Identifier(Ident::new("salary"))],
+                            distinct: false,
+                            filter: None,
+                            over: None,
+                        },
+                        alias: "total".to_string(),
+                    },
+                ],
+                from: Some(TableWithJoins {
+                    relation: TableFactor::Table {
+                        name: Ident::new("salary"),
+                        alias: None,
+                    },
+                    joins: vec![],
+                }),
+                selection: Some(Expr::BinaryOp {
+                    left: Box::new(Expr::BinaryOp {
+                        left: ident("awards"),
+                        op: Operator::Gt,
+                        right: Box::new(Expr::Value(Value::Number("5".to_string()))),
+                    }),
+                    op: Operator::Or,
+                    right: Box::new(Expr::BinaryOp {
+                        left: ident("id"),
+                        op: Operator::Eq,
+                        right: Box::new(Expr::Value(Value::Number("1".to_string()))),
+                    }),
+                }),
+                group_by: vec![Expr::Identifier(Ident::new("name"))],
+                having: None,
+            })),
+            order_by: vec![],
+            limit: None,
+            offset: None,
+        }));
+
+        assert_eq!(statement, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_operator_precedence() -> Result<()> {
+        let mut parser = Parser::new(Tokenizer::new("a + b * c - d").tokenize()?);
+        let expr = parser.parse_expr()?;
+
+        assert_eq!(
+            expr,
+            Expr::BinaryOp {
+                left: Box::new(Expr::BinaryOp {
+                    left: ident("a"),
+                    op: Operator::Add,
+                    right: Box::new(Expr::BinaryOp {
+                        left: ident("b"),
+                        op: Operator::Mul,
+                        right: ident("c"),
+                    }),
+                }),
+                op: Operator::Sub,
+                right: ident("d"),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_join() -> Result<()> {
+        let statement =
+            Parser::parse_sql("SELECT * FROM test LEFT JOIN salary ON test.id = salary.id;")?;
+
+        let from = match statement {
+            Statement::Query(query) => select(&query).from.clone().unwrap(),
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        assert_eq!(from.joins.len(), 1);
+        assert_eq!(from.joins[0].join_type, JoinType::Left);
+        assert_eq!(
+            from.joins[0].constraint,
+            Expr::BinaryOp {
+                left: Box::new(Expr::CompoundIdentifier(vec![
+                    Ident::new("test"),
+                    Ident::new("id")
+                ])),
+                op: Operator::Eq,
+                right: Box::new(Expr::CompoundIdentifier(vec![
+                    Ident::new("salary"),
+                    Ident::new("id")
+                ])),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_table_alias_and_derived_table() -> Result<()> {
+        let statement = Parser::parse_sql(
+            "SELECT t.id FROM (SELECT id FROM test) AS t JOIN salary s ON t.id = s.id",
+        )?;
+
+        let from = match statement {
+            Statement::Query(query) => select(&query).from.clone().unwrap(),
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        match from.relation {
+            TableFactor::Derived { subquery, alias } => {
+                assert_eq!(alias, Some("t".to_string()));
+                assert_eq!(select(&subquery).projection.len(), 1);
+            }
+            relation => panic!("Expected a derived table, found {:?}", relation),
+        }
+        assert_eq!(
+            from.joins[0].relation,
+            TableFactor::Table {
+                name: Ident::new("salary"),
+                alias: Some("s".to_string()),
+            }
+        );
+
+        assert!(Parser::parse_sql("SELECT id FROM (SELECT id FROM test").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_subqueries() -> Result<()> {
+        let mut parser = Parser::new(
+            Tokenizer::new(
+                "salary > (SELECT AVG(salary) FROM salary) AND id NOT IN (SELECT id FROM test) \
+                 OR NOT EXISTS (SELECT * FROM test WHERE test.id = s.id)",
+            )
+            .tokenize()?,
+        );
+
+        let (left, right) = match parser.parse_expr()? {
+            Expr::BinaryOp {
+                left,
+                op: Operator::Or,
+                right,
+            } => (left, right),
+            expr => panic!("Expected OR, found {:?}", expr),
+        };
+
+        match *left {
+            Expr::BinaryOp {
+                left,
+                op: Operator::And,
+                right,
+            } => {
+                assert!(matches!(
+                    *left,
+                    Expr::BinaryOp { right, .. } if matches!(*right, Expr::Subquery(_))
+                ));
+                assert!(matches!(*right, Expr::InSubquery { negated: true, .. }));
+            }
+            expr => panic!("Expected AND, found {:?}", expr),
+        }
+        assert!(matches!(*right, Expr::Exists { negated: true, .. }));
+
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE id IN ()").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_with_and_union() -> Result<()> {
+        let query = match Parser::parse_sql(
+            "WITH RECURSIVE t(n) AS (SELECT id FROM test UNION ALL SELECT n FROM t), \
+             u AS (SELECT id FROM salary) SELECT * FROM t",
+        )? {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+
+        let with = query.with.as_ref().unwrap();
+        assert!(with.recursive);
+        assert_eq!(with.cte_tables.len(), 2);
+        assert_eq!(with.cte_tables[0].alias, "t");
+        assert_eq!(with.cte_tables[0].columns, vec!["n".to_string()]);
+        assert!(matches!(
+            with.cte_tables[0].query.body,
+            SetExpr::SetOperation {
+                op: SetOperator::Union,
+                all: true,
+                ..
+            }
+        ));
+        assert!(with.cte_tables[1].columns.is_empty());
+        assert!(matches!(query.body, SetExpr::Select(_)));
+
+        // UNION is left associative
+        let query = match Parser::parse_sql(
+            "SELECT id FROM a UNION SELECT id FROM b UNION ALL (SELECT id FROM c)",
+        )? {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        match query.body {
+            SetExpr::SetOperation {
+                all: true,
+                left,
+                right,
+                ..
+            } => {
+                assert!(matches!(*left, SetExpr::SetOperation { all: false, .. }));
+                assert!(matches!(*right, SetExpr::Query(_)));
+            }
+            body => panic!("Expected UNION ALL, found {:?}", body),
+        }
+
+        // INTERSECT binds more tightly than EXCEPT
+        let query = match Parser::parse_sql(
+            "SELECT id FROM a EXCEPT ALL SELECT id FROM b INTERSECT SELECT id FROM c",
+        )? {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        match query.body {
+            SetExpr::SetOperation {
+                op: SetOperator::Except,
+                all: true,
+                left,
+                right,
+            } => {
+                assert!(matches!(*left, SetExpr::Select(_)));
+                assert!(matches!(
+                    *right,
+                    SetExpr::SetOperation {
+                        op: SetOperator::Intersect,
+                        all: false,
+                        ..
+                    }
+                ));
+            }
+            body => panic!("Expected EXCEPT ALL, found {:?}", body),
+        }
+
+        assert!(Parser::parse_sql("WITH t AS SELECT id FROM test SELECT * FROM t").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_case() -> Result<()> {
+        let mut parser = Parser::new(
+            Tokenizer::new("CASE WHEN age > 20 THEN 'old' ELSE 'young' END").tokenize()?,
+        );
+        assert_eq!(
+            parser.parse_expr()?,
+            Expr::Case {
+                operand: None,
+                conditions: vec![Expr::BinaryOp {
+                    left: ident("age"),
+                    op: Operator::Gt,
+                    right: Box::new(Expr::Value(Value::Number("20".to_string()))),
+                }],
+                results: vec![Expr::Value(Value::SingleQuotedString("old".to_string()))],
+                else_result: Some(Box::new(Expr::Value(Value::SingleQuotedString(
+                    "young".to_string()
+                )))),
+            }
+        );
+
+        let mut parser =
+            Parser::new(Tokenizer::new("CASE id WHEN 1 THEN 2 WHEN 3 THEN 4 END").tokenize()?);
+        assert!(matches!(
+            parser.parse_expr()?,
+            Expr::Case { operand: Some(_), conditions, else_result: None, .. } if conditions.len() == 2
+        ));
+
+        assert!(Parser::parse_sql("SELECT CASE WHEN id = 1 THEN 2 FROM test").is_err());
+        assert!(Parser::parse_sql("SELECT CASE id END FROM test").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cast() -> Result<()> {
+        let mut parser = Parser::new(
+            Tokenizer::new("CAST(age AS DOUBLE PRECISION) + TRY_CAST(name AS BIGINT UNSIGNED)")
+                .tokenize()?,
+        );
+        assert_eq!(
+            parser.parse_expr()?,
+            Expr::BinaryOp {
+                left: Box::new(Expr::Cast {
+                    expr: ident("age"),
+                    data_type: DataType::Float64,
+                }),
+                op: Operator::Add,
+                right: Box::new(Expr::TryCast {
+                    expr: ident("name"),
+                    data_type: DataType::UInt64,
+                }),
+            }
+        );
+
+        let mut parser = Parser::new(Tokenizer::new("CAST(id AS VARCHAR(10))").tokenize()?);
+        assert!(matches!(
+            parser.parse_expr()?,
+            Expr::Cast {
+                data_type: DataType::Utf8,
+                ..
+            }
+        ));
+
+        assert!(Parser::parse_sql("SELECT CAST(id AS BLOB) FROM test").is_err());
+        assert!(Parser::parse_sql("SELECT CAST(id) FROM test").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_placeholders() -> Result<()> {
+        let mut parser =
+            Parser::new(Tokenizer::new("age = ? AND score > ? OR id = $1").tokenize()?);
+        assert_eq!(
+            parser.parse_expr()?,
+            Expr::BinaryOp {
+                left: Box::new(Expr::BinaryOp {
+                    left: Box::new(Expr::BinaryOp {
+                        left: ident("age"),
+                        op: Operator::Eq,
+                        right: Box::new(Expr::Placeholder(1)),
+                    }),
+                    op: Operator::And,
+                    right: Box::new(Expr::BinaryOp {
+                        left: ident("score"),
+                        op: Operator::Gt,
+                        right: Box::new(Expr::Placeholder(2)),
+                    }),
+                }),
+                op: Operator::Or,
+                right: Box::new(Expr::BinaryOp {
+                    left: ident("id"),
+                    op: Operator::Eq,
+                    right: Box::new(Expr::Placeholder(1)),
+                }),
+            }
+        );
+
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE age = $0").is_err());
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE age = $").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_order_by() -> Result<()> {
+        let statement =
+            Parser::parse_sql("SELECT name FROM test ORDER BY age DESC, score NULLS FIRST")?;
+
+        let order_by = match statement {
+            Statement::Query(query) => query.order_by,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        assert_eq!(
+            order_by,
+            vec![
+                OrderByExpr {
+                    expr: Expr::Identifier(Ident::new("age")),
+                    asc: Some(false),
+                    nulls_first: None,
+                },
+                OrderByExpr {
+                    expr: Expr::Identifier(Ident::new("score")),
+                    asc: None,
+                    nulls_first: Some(true),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_limit_offset() -> Result<()> {
+        for sql in [
+            "SELECT * FROM test LIMIT 3 OFFSET 1",
+            "SELECT * FROM test OFFSET 1 ROWS LIMIT 3",
+        ] {
+            match Parser::parse_sql(sql)? {
+                Statement::Query(query) => {
+                    assert_eq!(
+                        query.limit,
+                        Some(Expr::Value(Value::Number("3".to_string())))
+                    );
+                    assert_eq!(
+                        query.offset,
+                        Some(Expr::Value(Value::Number("1".to_string())))
+                    );
+                }
+                statement => panic!("Expected a query, found {:?}", statement),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_create_external_table() -> Result<()> {
+        let statement = Parser::parse_sql(
+            "CREATE EXTERNAL TABLE IF NOT EXISTS people (id INT NOT NULL, name VARCHAR(20)) \
+             STORED AS csv WITH HEADER ROW DELIMITER ';' LOCATION 'data/people.csv'",
+        )?;
+        assert_eq!(
+            statement,
+            Statement::CreateExternalTable(CreateExternalTable {
+                name: "people".to_string(),
+                columns: vec![
+                    ColumnDef {
+                        name: "id".to_string(),
+                        data_type: DataType::Int64,
+                        nullable: false,
+                    },
+                    ColumnDef {
+                        name: "name".to_string(),
+                        data_type: DataType::Utf8,
+                        nullable: true,
+                    },
+                ],
+                file_type: "CSV".to_string(),
+                has_header: true,
+                delimiter: ';',
+                location: "data/people.csv".to_string(),
+                if_not_exists: true,
+                or_replace: false,
+            })
+        );
+
+        match Parser::parse_sql(
+            "CREATE OR REPLACE EXTERNAL TABLE t LOCATION 'a.csv' STORED AS CSV",
+        )? {
+            Statement::CreateExternalTable(create) => {
+                assert!(create.or_replace);
+                assert!(create.columns.is_empty());
+                assert!(!create.has_header);
+                assert_eq!(create.delimiter, ',');
+            }
+            statement => panic!("Expected CREATE EXTERNAL TABLE, found {:?}", statement),
+        }
+
+        assert!(Parser::parse_sql("CREATE EXTERNAL TABLE t STORED AS CSV").is_err());
+        assert!(Parser::parse_sql(
+            "CREATE EXTERNAL TABLE t STORED AS CSV DELIMITER ';;' LOCATION 'a'"
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_view_statements() -> Result<()> {
+        match Parser::parse_sql(
+            "CREATE OR REPLACE VIEW adults AS SELECT name FROM test WHERE age >= 18",
+        )? {
+            Statement::CreateView {
+                name,
+                query,
+                or_replace,
+            } => {
+                assert_eq!(name, "adults");
+                assert!(or_replace);
+                assert!(select(&query).selection.is_some());
+            }
+            statement => panic!("Expected CREATE VIEW, found {:?}", statement),
+        }
+
+        assert_eq!(
+            Parser::parse_sql("DROP VIEW IF EXISTS adults")?,
+            Statement::DropView {
+                name: "adults".to_string(),
+                if_exists: true,
+            }
+        );
+        assert!(Parser::parse_sql("DROP TABLE adults").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_insert_and_create_table() -> Result<()> {
+        match Parser::parse_sql("INSERT INTO people VALUES (1, 'a'), (2, NULL)")? {
+            Statement::Insert { table_name, source } => {
+                assert_eq!(table_name, "people");
+                assert_eq!(
+                    source.body,
+                    SetExpr::Values(vec![
+                        vec![
+                            Expr::Value(Value::Number("1".to_string())),
+                            Expr::Value(Value::SingleQuotedString("a".to_string())),
+                        ],
+                        vec![
+                            Expr::Value(Value::Number("2".to_string())),
+                            Expr::Value(Value::Null),
+                        ],
+                    ])
+                );
+            }
+            statement => panic!("Expected INSERT, found {:?}", statement),
+        }
+
+        match Parser::parse_sql("INSERT INTO people SELECT id, name FROM test")? {
+            Statement::Insert { source, .. } => assert!(matches!(source.body, SetExpr::Select(_))),
+            statement => panic!("Expected INSERT, found {:?}", statement),
+        }
+
+        match Parser::parse_sql("CREATE TABLE IF NOT EXISTS adults AS SELECT * FROM test")? {
+            Statement::CreateTable(create) => {
+                assert_eq!(create.name, "adults");
+                assert!(create.if_not_exists);
+                assert!(create.columns.is_empty());
+                assert!(create.query.is_some());
+            }
+            statement => panic!("Expected CREATE TABLE, found {:?}", statement),
+        }
+
+        match Parser::parse_sql("CREATE TABLE people (id INT NOT NULL, name TEXT)")? {
+            Statement::CreateTable(create) => {
+                assert_eq!(create.columns.len(), 2);
+                assert!(create.query.is_none());
+            }
+            statement => panic!("Expected CREATE TABLE, found {:?}", statement),
+        }
+        assert!(Parser::parse_sql("CREATE TABLE people").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_window_functions() -> Result<()> {
+        let statement = Parser::parse_sql(
+            "SELECT SUM(salary) OVER (PARTITION BY dept ORDER BY id DESC \
+             ROWS BETWEEN 2 PRECEDING AND CURRENT ROW) AS total FROM salary",
+        )?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        assert_eq!(
+            select(&query).projection,
+            vec![SelectItem::ExprWithAlias {
+                expr: Expr::Function {
+                    name: Ident::new("SUM"),
+                    args: vec![Expr::Identifier(Ident::new("salary"))],
+                    distinct: false,
+                    filter: None,
+                    over: Some(WindowSpec {
+                        partition_by: vec![Expr::Identifier(Ident::new("dept"))],
+                        order_by: vec![OrderByExpr {
+                            expr: Expr::Identifier(Ident::new("id")),
+                            asc: Some(false),
+                            nulls_first: None,
+                        }],
+                        window_frame: Some(WindowFrame {
+                            units: WindowFrameUnits::Rows,
+                            start_bound: WindowFrameBound::Preceding(2),
+                            end_bound: WindowFrameBound::CurrentRow,
+                        }),
+                    }),
+                },
+                alias: "total".to_string(),
+            }]
+        );
+
+        // A frame with only a start ends with the current row
+        let statement =
+            Parser::parse_sql("SELECT ROW_NUMBER() OVER (RANGE UNBOUNDED PRECEDING) FROM test")?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        assert_eq!(
+            select(&query).projection,
+            vec![SelectItem::UnnamedExpr(Expr::Function {
+                name: Ident::new("ROW_NUMBER"),
+                args: vec![],
+                distinct: false,
+                filter: None,
+                over: Some(WindowSpec {
+                    partition_by: vec![],
+                    order_by: vec![],
+                    window_frame: Some(WindowFrame {
+                        units: WindowFrameUnits::Range,
+                        start_bound: WindowFrameBound::UnboundedPreceding,
+                        end_bound: WindowFrameBound::CurrentRow,
+                    }),
+                }),
+            })]
+        );
+
+        assert!(Parser::parse_sql("SELECT RANK() OVER (ROWS 2) FROM test").is_err());
+        assert!(Parser::parse_sql("SELECT RANK() OVER ORDER BY id FROM test").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_distinct_and_filtered_aggregates() -> Result<()> {
+        let statement = Parser::parse_sql(
+            "SELECT COUNT(DISTINCT name), SUM(salary) FILTER (WHERE awards > 5) filter FROM salary",
+        )?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        assert_eq!(
+            select(&query).projection,
+            vec![
+                SelectItem::UnnamedExpr(Expr::Function {
+                    name: Ident::new("COUNT"),
+                    args: vec![Expr::Identifier(Ident::new("name"))],
+                    distinct: true,
+                    filter: None,
+                    over: None,
+                }),
+                // A FILTER that is not followed by a parenthesis is an alias
+                SelectItem::ExprWithAlias {
+                    expr: Expr::Function {
+                        name: Ident::new("SUM"),
+                        args: vec![Expr::Identifier(Ident::new("salary"))],
+                        distinct: false,
+                        filter: Some(Box::new(Expr::BinaryOp {
+                            left: Box::new(Expr::Identifier(Ident::new("awards"))),
+                            op: Operator::Gt,
+                            right: Box::new(Expr::Value(Value::Number("5".to_string()))),
+                        })),
+                        over: None,
+                    },
+                    alias: "filter".to_string(),
+                },
+            ]
+        );
+
+        assert!(Parser::parse_sql("SELECT COUNT(DISTINCT) FROM salary").is_err());
+        assert!(Parser::parse_sql("SELECT SUM(salary) FILTER (awards > 5) FROM salary").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_pattern_matches() -> Result<()> {
+        let name = || Box::new(Expr::Identifier(Ident::new("name")));
+        let pattern = |s: &str| Box::new(Expr::Value(Value::SingleQuotedString(s.to_string())));
+        let statement = Parser::parse_sql(
+            "SELECT id FROM test WHERE name NOT LIKE 'a!%' ESCAPE '!' AND name ILIKE 'b%' \
+             AND name SIMILAR TO '(c|d)%' AND name !~ '^e'",
+        )?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        let and = |left, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op: Operator::And,
+            right: Box::new(right),
+        };
+        assert_eq!(
+            select(&query).selection,
+            Some(and(
+                and(
+                    and(
+                        Expr::PatternMatch {
+                            kind: PatternKind::Like,
+                            negated: true,
+                            expr: name(),
+                            pattern: pattern("a!%"),
+                            escape_char: Some('!'),
+                        },
+                        Expr::PatternMatch {
+                            kind: PatternKind::ILike,
+                            negated: false,
+                            expr: name(),
+                            pattern: pattern("b%"),
+                            escape_char: None,
+                        }
+                    ),
+                    Expr::PatternMatch {
+                        kind: PatternKind::SimilarTo,
+                        negated: false,
+                        expr: name(),
+                        pattern: pattern("(c|d)%"),
+                        escape_char: None,
+                    }
+                ),
+                Expr::PatternMatch {
+                    kind: PatternKind::Regex,
+                    negated: true,
+                    expr: name(),
+                    pattern: pattern("^e"),
+                    escape_char: None,
+                }
+            ))
+        );
+
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE name LIKE 'a' ESCAPE '!!'").is_err());
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE name NOT 'a'").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_unary_and_is_operators() -> Result<()> {
+        let ident = |name: &str| Expr::Identifier(Ident::new(name));
+        let unary = |op, expr| Expr::UnaryOp {
+            op,
+            expr: Box::new(expr),
+        };
+        let binary = |left, op, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        };
+        let statement = Parser::parse_sql(
+            "SELECT -score * 2, -1 FROM test WHERE NOT age > 20 AND name IS NOT NULL \
+             AND age = 24 IS TRUE OR age IS DISTINCT FROM -id",
+        )?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+
+        // Unary minus binds more tightly than `*`, a negative number is a literal
+        assert_eq!(
+            select(&query).projection,
+            vec![
+                SelectItem::UnnamedExpr(binary(
+                    unary(UnaryOperator::Negative, ident("score")),
+                    Operator::Mul,
+                    Expr::Value(Value::Number("2".to_string()))
+                )),
+                SelectItem::UnnamedExpr(Expr::Value(Value::Number("-1".to_string()))),
+            ]
+        );
+        // NOT binds less tightly than comparisons and IS less tightly than `=`
+        assert_eq!(
+            select(&query).selection,
+            Some(binary(
+                binary(
+                    binary(
+                        unary(
+                            UnaryOperator::Not,
+                            binary(
+                                ident("age"),
+                                Operator::Gt,
+                                Expr::Value(Value::Number("20".to_string()))
+                            )
+                        ),
+                        Operator::And,
+                        unary(UnaryOperator::IsNotNull, ident("name"))
+                    ),
+                    Operator::And,
+                    unary(
+                        UnaryOperator::IsTrue,
+                        binary(
+                            ident("age"),
+                            Operator::Eq,
+                            Expr::Value(Value::Number("24".to_string()))
+                        )
+                    )
+                ),
+                Operator::Or,
+                binary(
+                    ident("age"),
+                    Operator::IsDistinctFrom,
+                    unary(UnaryOperator::Negative, ident("id"))
+                )
+            ))
+        );
+
+        // NOT EXISTS is still a subquery expression
+        let statement = Parser::parse_sql("SELECT 1 WHERE NOT EXISTS (SELECT 1)")?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        assert!(matches!(
+            select(&query).selection,
+            Some(Expr::Exists { negated: true, .. })
+        ));
+
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE name IS 'a'").is_err());
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE name IS DISTINCT id").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_in_list_and_between() -> Result<()> {
+        let ident = |name: &str| Box::new(Expr::Identifier(Ident::new(name)));
+        let number = |n: &str| Expr::Value(Value::Number(n.to_string()));
+        let statement = Parser::parse_sql(
+            "SELECT id FROM test WHERE id NOT IN (1, -2, age + 1) \
+             AND age BETWEEN 18 AND id * 10 AND score NOT BETWEEN 0 AND 50",
+        )?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        let and = |left, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op: Operator::And,
+            right: Box::new(right),
+        };
+        assert_eq!(
+            select(&query).selection,
+            Some(and(
+                and(
+                    Expr::InList {
+                        expr: ident("id"),
+                        list: vec![
+                            number("1"),
+                            number("-2"),
+                            Expr::BinaryOp {
+                                left: ident("age"),
+                                op: Operator::Add,
+                                right: Box::new(number("1")),
+                            },
+                        ],
+                        negated: true,
+                    },
+                    Expr::Between {
+                        expr: ident("age"),
+                        negated: false,
+                        low: Box::new(number("18")),
+                        high: Box::new(Expr::BinaryOp {
+                            left: ident("id"),
+                            op: Operator::Mul,
+                            right: Box::new(number("10")),
+                        }),
+                    }
+                ),
+                Expr::Between {
+                    expr: ident("score"),
+                    negated: true,
+                    low: Box::new(number("0")),
+                    high: Box::new(number("50")),
+                }
+            ))
+        );
+
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE age BETWEEN 18").is_err());
+        assert!(Parser::parse_sql("SELECT id FROM test WHERE id IN (1, 2").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_grouping_sets() -> Result<()> {
+        let id = || Expr::Identifier(Ident::new("id"));
+        let name = || Expr::Identifier(Ident::new("name"));
+        let statement = Parser::parse_sql(
+            "SELECT COUNT(id) FROM test GROUP BY age, ROLLUP(id, (id, name)), CUBE(name), \
+             GROUPING SETS ((id, name), id, ())",
+        )?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        assert_eq!(
+            select(&query).group_by,
+            vec![
+                Expr::Identifier(Ident::new("age")),
+                Expr::Rollup(vec![vec![id()], vec![id(), name()]]),
+                Expr::Cube(vec![vec![name()]]),
+                Expr::GroupingSets(vec![vec![id(), name()], vec![id()], vec![]]),
+            ]
+        );
+
+        // Without parentheses ROLLUP and CUBE are ordinary identifiers
+        let statement = Parser::parse_sql("SELECT rollup FROM test GROUP BY rollup")?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            statement => panic!("Expected a query, found {:?}", statement),
+        };
+        assert_eq!(
+            select(&query).group_by,
+            vec![Expr::Identifier(Ident::new("rollup"))]
+        );
+
+        assert!(Parser::parse_sql("SELECT id FROM test GROUP BY GROUPING SETS id").is_err());
+        assert!(Parser::parse_sql("SELECT id FROM test GROUP BY ROLLUP(id").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_show_statements() -> Result<()> {
+        assert_eq!(Parser::parse_sql("SHOW TABLES")?, Statement::ShowTables);
+        for sql in [
+            "DESCRIBE test",
+            "DESC test",
+            "SHOW COLUMNS FROM test",
+            "SHOW COLUMNS IN test",
+        ] {
+            assert_eq!(
+                Parser::parse_sql(sql)?,
+                Statement::ShowColumns {
+                    table_name: "test".to_string()
+                }
+            );
+        }
+        assert_eq!(
+            Parser::parse_sql("DESCRIBE information_schema.columns")?,
+            Statement::ShowColumns {
+                table_name: "information_schema.columns".to_string()
+            }
+        );
+        assert!(Parser::parse_sql("SHOW test").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_error_reports_location() {
+        let err = Parser::parse_sql("SELECT name FROM test WHERE").unwrap_err();
+        match err {
+            Error::ParserError(msg) => {
+                assert_eq!(
+                    msg,
+                    "Expected an expression, found EOF at line 1, column 28"
+                )
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn parse_names_with_spans() -> Result<()> {
+        let statement = Parser::parse_sql("SELECT t.id\nFROM test AS t")?;
+        let query = match statement {
+            Statement::Query(query) => query,
+            _ => panic!("Expected a query"),
+        };
+        let select = select(&query);
+
+        let span = |line, start, end| Span {
+            start: Location {
+                line,
+                column: start,
+            },
+            end: Location { line, column: end },
+        };
+        match &select.projection[0] {
+            SelectItem::UnnamedExpr(Expr::CompoundIdentifier(idents)) => {
+                assert_eq!(idents[0].span, Some(span(1, 8, 8)));
+                assert_eq!(idents[1].span, Some(span(1, 10, 11)));
+                assert_eq!(join_spans(idents), Some(span(1, 8, 11)));
+            }
+            item => panic!("Expected a column reference, found {:?}", item),
+        }
+        match &select.from.as_ref().unwrap().relation {
+            TableFactor::Table { name, .. } => assert_eq!(name.span, Some(span(2, 6, 9))),
+            relation => panic!("Expected a table, found {:?}", relation),
+        }
+
+        Ok(())
+    }
+}
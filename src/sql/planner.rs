@@ -0,0 +1,3099 @@
+use crate::catalog::Catalog;
+use crate::datatype::scalar::Scalar;
+use crate::datatype::schema::Schema;
+use crate::error::{Error, Result};
+use crate::information_schema::{self, describe_table};
+use crate::logical_plan::data_frame::DataFrame;
+use crate::logical_plan::logical_expr::{
+    find_aggregate_exprs, find_window_exprs, rebase_expr, rebase_window_expr, AggregateFunc,
+    AggregateFuncExpr, Alias, Between, BinaryExpr, Case, Cast, Exists, InList, InSubquery,
+    LogicalExpr, Operator, PatternMatch, SortExpr, Subquery, TryCast, UnaryExpr, WindowFrame,
+    WindowFrameBound, WindowFrameUnits, WindowFunc, WindowFuncExpr,
+};
+use crate::logical_plan::logical_plan::{self, LogicalPlan, Scan, WorkTableScan};
+use crate::logical_plan::subquery::check_subquery;
+use crate::sql::ast::{
+    join_spans, Cte, Expr, Join, JoinType, OrderByExpr, Query, Select, SelectItem, SetExpr,
+    SetOperator, Statement, TableFactor, TableWithJoins, Value, WindowSpec, With,
+};
+use crate::sql::tokenizer::Span;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// SqlPlanner converts the SQL AST into a logical plan
+pub struct SqlPlanner<'a> {
+    catalog: &'a Catalog,
+    /// The schema of the enclosing query when planning a subquery,
+    /// columns that are only found there become outer column references
+    outer_schema: Option<Schema>,
+    /// The common table expressions in scope, they take precedence over catalog tables
+    ctes: HashMap<String, LogicalPlan>,
+}
+
+impl<'a> SqlPlanner<'a> {
+    pub fn new(catalog: &'a Catalog) -> Self {
+        Self {
+            catalog,
+            outer_schema: None,
+            ctes: HashMap::new(),
+        }
+    }
+
+    /// Creates a planner for a nested scope of this planner
+    fn scoped(&self, outer_schema: Option<Schema>, ctes: HashMap<String, LogicalPlan>) -> Self {
+        Self {
+            catalog: self.catalog,
+            outer_schema,
+            ctes,
+        }
+    }
+
+    /// Plans a subquery whose enclosing query has `schema`
+    fn subquery_to_plan(&self, query: &Query, schema: &Schema) -> Result<Subquery> {
+        let planner = self.scoped(Some(schema.clone()), self.ctes.clone());
+        Ok(Subquery {
+            plan: Arc::new(planner.query_to_plan(query.clone())?),
+        })
+    }
+
+    pub fn statement_to_plan(&self, statement: Statement) -> Result<LogicalPlan> {
+        match statement {
+            Statement::Query(query) => self.query_to_plan(*query),
+            Statement::Explain {
+                analyze,
+                verbose,
+                statement,
+            } => DataFrame::new(self.statement_to_plan(*statement)?)
+                .explain(verbose, analyze)
+                .map(|df| df.plan),
+            Statement::ShowTables => self
+                .catalog
+                .get_table_df(information_schema::TABLES)
+                .map(|df| df.plan),
+            Statement::ShowColumns { table_name } => {
+                let df = self.catalog.get_table_df(&table_name)?;
+                Ok(LogicalPlan::Scan(Scan {
+                    data_source: describe_table(df.schema())?,
+                    projection: None,
+                }))
+            }
+            Statement::CreateExternalTable(_)
+            | Statement::CreateView { .. }
+            | Statement::DropView { .. }
+            | Statement::CreateTable(_)
+            | Statement::Insert { .. } => Err(Error::LogicalPlanError(
+                "DDL statements can only be executed by the catalog, not planned".to_string(),
+            )),
+        }
+    }
+
+    pub fn query_to_plan(&self, query: Query) -> Result<LogicalPlan> {
+        let planner = match query.with {
+            Some(with) => self.with_to_planner(with)?,
+            None => self.scoped(self.outer_schema.clone(), self.ctes.clone()),
+        };
+
+        let plan = planner.query_body_to_plan(query.body, &query.order_by)?;
+        planner.limit_plan(plan, &query.limit, &query.offset)
+    }
+
+    /// Plans the CTEs, each of them can be referenced by the following ones and by the query
+    fn with_to_planner(&self, with: With) -> Result<SqlPlanner<'a>> {
+        let mut ctes = self.ctes.clone();
+
+        for cte in with.cte_tables {
+            let planner = self.scoped(self.outer_schema.clone(), ctes.clone());
+            let alias = cte.alias.clone();
+            let plan = if with.recursive {
+                planner.recursive_cte_to_plan(cte)?
+            } else {
+                rename_columns(planner.query_to_plan(*cte.query)?, &cte.alias, &cte.columns)?
+            };
+            ctes.insert(alias, plan);
+        }
+
+        Ok(self.scoped(self.outer_schema.clone(), ctes))
+    }
+
+    /// A recursive CTE is `static_term UNION [ALL] recursive_term`, where the recursive term
+    /// reads the rows of the previous iteration by referencing the CTE.
+    /// A CTE without a self reference is planned like a regular one.
+    fn recursive_cte_to_plan(&self, cte: Cte) -> Result<LogicalPlan> {
+        let Query {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+        } = *cte.query;
+
+        let planner = match with {
+            Some(with) => self.with_to_planner(with)?,
+            None => self.scoped(self.outer_schema.clone(), self.ctes.clone()),
+        };
+
+        let (all, static_term, recursive_term) = match body {
+            SetExpr::SetOperation {
+                op: SetOperator::Union,
+                all,
+                left,
+                right,
+            } => (all, left, right),
+            body => {
+                let plan = planner.query_body_to_plan(body, &order_by)?;
+                let plan = planner.limit_plan(plan, &limit, &offset)?;
+                return rename_columns(plan, &cte.alias, &cte.columns);
+            }
+        };
+
+        let static_plan = rename_columns(
+            planner.set_expr_to_plan(*static_term)?,
+            &cte.alias,
+            &cte.columns,
+        )?;
+
+        let mut ctes = planner.ctes.clone();
+        ctes.insert(
+            cte.alias.clone(),
+            LogicalPlan::WorkTableScan(WorkTableScan {
+                name: cte.alias.clone(),
+                schema: static_plan.schema().clone(),
+            }),
+        );
+        let recursive_plan = planner
+            .scoped(planner.outer_schema.clone(), ctes)
+            .set_expr_to_plan(*recursive_term)?;
+
+        // Without a self reference the CTE is a plain UNION
+        let df = if reads_work_table(&recursive_plan, &cte.alias) {
+            DataFrame::new(static_plan).recursive_query(
+                &cte.alias,
+                recursive_plan,
+                !all,
+                self.catalog.max_recursive_iterations(),
+            )?
+        } else {
+            DataFrame::new(static_plan).union(DataFrame::new(recursive_plan), all)?
+        };
+
+        let plan = planner.sort_by_output(df.plan, &order_by)?;
+        planner.limit_plan(plan, &limit, &offset)
+    }
+
+    fn query_body_to_plan(&self, body: SetExpr, order_by: &[OrderByExpr]) -> Result<LogicalPlan> {
+        match body {
+            SetExpr::Select(select) => self.select_to_plan(*select, order_by),
+            body => {
+                let plan = self.set_expr_to_plan(body)?;
+                self.sort_by_output(plan, order_by)
+            }
+        }
+    }
+
+    fn set_expr_to_plan(&self, body: SetExpr) -> Result<LogicalPlan> {
+        match body {
+            SetExpr::Select(select) => self.select_to_plan(*select, &[]),
+            SetExpr::Query(query) => self.query_to_plan(*query),
+            SetExpr::Values(rows) => {
+                // The values cannot refer to any columns
+                let schema = Schema::new(vec![]);
+                let values = rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|expr| self.sql_to_expr(expr, &schema))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(DataFrame::values(values)?.plan)
+            }
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let left = DataFrame::new(self.set_expr_to_plan(*left)?);
+                let right = DataFrame::new(self.set_expr_to_plan(*right)?);
+                let df = match op {
+                    SetOperator::Union => left.union(right, all)?,
+                    SetOperator::Intersect => left.intersect(right, all)?,
+                    SetOperator::Except => left.except(right, all)?,
+                };
+                Ok(df.plan)
+            }
+        }
+    }
+
+    /// Sorts the rows of a plan by ORDER BY items that refer to its output columns
+    fn sort_by_output(&self, plan: LogicalPlan, order_by: &[OrderByExpr]) -> Result<LogicalPlan> {
+        if order_by.is_empty() {
+            return Ok(plan);
+        }
+
+        let df = DataFrame::new(plan);
+        let output_exprs = df
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| LogicalExpr::Column(field.qualified_name()))
+            .collect::<Vec<_>>();
+        let sort_exprs = order_by
+            .iter()
+            .map(|order_by_expr| {
+                self.order_by_to_sort_expr(order_by_expr, &output_exprs, df.schema())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(df.sort(sort_exprs)?.plan)
+    }
+
+    fn limit_plan(
+        &self,
+        plan: LogicalPlan,
+        limit: &Option<Expr>,
+        offset: &Option<Expr>,
+    ) -> Result<LogicalPlan> {
+        if limit.is_none() && offset.is_none() {
+            return Ok(plan);
+        }
+
+        let skip = match offset {
+            Some(offset) => self.expr_to_row_count(offset, "OFFSET")?,
+            None => 0,
+        };
+        let fetch = match limit {
+            Some(limit) => Some(self.expr_to_row_count(limit, "LIMIT")?),
+            None => None,
+        };
+
+        Ok(DataFrame::new(plan).limit(skip, fetch)?.plan)
+    }
+
+    /// LIMIT and OFFSET only accept non-negative integer literals
+    fn expr_to_row_count(&self, expr: &Expr, clause: &str) -> Result<usize> {
+        match expr {
+            Expr::Value(Value::Number(n)) => n.parse::<usize>().map_err(|_| {
+                Error::LogicalPlanError(format!(
+                    "{} must be a non-negative integer, found {}",
+                    clause, n
+                ))
+            }),
+            _ => Err(Error::LogicalPlanError(format!(
+                "{} must be a non-negative integer, found {}",
+                clause, expr
+            ))),
+        }
+    }
+
+    fn select_to_plan(&self, select: Select, order_by: &[OrderByExpr]) -> Result<LogicalPlan> {
+        let mut df = match select.from {
+            Some(from) => self.plan_from(from)?,
+            // The select list is evaluated once, on a single row without columns
+            None => DataFrame::values(vec![vec![]])?,
+        };
+
+        if let Some(selection) = &select.selection {
+            let expr = self.sql_to_expr(selection, df.schema())?;
+            check_no_window_exprs(&expr, "WHERE")?;
+            df = df.select(expr)?;
+        }
+
+        let mut select_exprs = vec![];
+        for item in &select.projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) => {
+                    let expr = self.sql_to_expr(expr, df.schema())?;
+                    // A window function is named as written, its columns are renamed
+                    // when the select list is rebased on the output of an aggregation
+                    select_exprs.push(match expr {
+                        LogicalExpr::WindowFuncExpr(window_expr) => LogicalExpr::Alias(Alias {
+                            name: window_expr.to_string(),
+                            expr: Box::new(LogicalExpr::WindowFuncExpr(window_expr)),
+                        }),
+                        expr => expr,
+                    })
+                }
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    select_exprs.push(LogicalExpr::Alias(Alias {
+                        name: alias.clone(),
+                        expr: Box::new(self.sql_to_expr(expr, df.schema())?),
+                    }))
+                }
+                SelectItem::Wildcard => select_exprs.extend(
+                    df.schema()
+                        .fields()
+                        .iter()
+                        .map(|field| LogicalExpr::Column(field.qualified_name())),
+                ),
+            }
+        }
+
+        let (group_exprs, grouping_sets) = self.group_by_to_grouping_sets(&select.group_by, &df)?;
+        for expr in &group_exprs {
+            check_no_window_exprs(expr, "GROUP BY")?;
+        }
+
+        // The rows are sorted before the projection is applied,
+        // so ORDER BY can use columns of the input that are not selected.
+        let mut sort_exprs = order_by
+            .iter()
+            .map(|order_by_expr| {
+                self.order_by_to_sort_expr(order_by_expr, &select_exprs, df.schema())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let having_expr = match &select.having {
+            Some(having) => Some(self.sql_to_expr(having, df.schema())?),
+            None => None,
+        };
+        if let Some(having_expr) = &having_expr {
+            check_no_window_exprs(having_expr, "HAVING")?;
+        }
+
+        let mut aggr_exprs = vec![];
+        for expr in &select_exprs {
+            find_aggregate_exprs(expr, df.plan(), &mut aggr_exprs)?;
+        }
+        for sort_expr in &sort_exprs {
+            find_aggregate_exprs(&sort_expr.expr, df.plan(), &mut aggr_exprs)?;
+        }
+        if let Some(having_expr) = &having_expr {
+            // Aggregates only used by HAVING are computed too, the projection drops them later
+            find_aggregate_exprs(having_expr, df.plan(), &mut aggr_exprs)?;
+        }
+
+        if group_exprs.is_empty() && aggr_exprs.is_empty() && having_expr.is_some() {
+            return Err(Error::LogicalPlanError(
+                "HAVING requires a GROUP BY clause or aggregate functions".to_string(),
+            ));
+        }
+
+        if !select.group_by.is_empty() || !aggr_exprs.is_empty() {
+            // The select list is evaluated on the output of the aggregation,
+            // so group expressions and aggregates are replaced by references to its columns.
+            let mut base_exprs = group_exprs.clone();
+            base_exprs.extend(
+                aggr_exprs
+                    .iter()
+                    .cloned()
+                    .map(LogicalExpr::AggregateFuncExpr),
+            );
+
+            select_exprs = select_exprs
+                .iter()
+                .map(|expr| rebase_expr(expr, &base_exprs, df.plan()))
+                .collect::<Result<Vec<_>>>()?;
+
+            for sort_expr in sort_exprs.iter_mut() {
+                sort_expr.expr = rebase_expr(&sort_expr.expr, &base_exprs, df.plan())?;
+            }
+
+            let having_expr = match &having_expr {
+                Some(having_expr) => Some(rebase_expr(having_expr, &base_exprs, df.plan())?),
+                None => None,
+            };
+
+            df = df.aggregate_grouping_sets(group_exprs, grouping_sets, aggr_exprs)?;
+
+            if let Some(having_expr) = having_expr {
+                df = df.select(having_expr)?;
+            }
+        }
+
+        // Window functions are computed on the rows left after aggregation and HAVING,
+        // the select list and ORDER BY then refer to the columns holding their results.
+        let mut window_exprs = vec![];
+        for expr in &select_exprs {
+            find_window_exprs(expr, &mut window_exprs);
+        }
+        for sort_expr in &sort_exprs {
+            find_window_exprs(&sort_expr.expr, &mut window_exprs);
+        }
+        if !window_exprs.is_empty() {
+            df = df.window(window_exprs)?;
+            select_exprs = select_exprs.iter().map(rebase_window_expr).collect();
+            for sort_expr in sort_exprs.iter_mut() {
+                sort_expr.expr = rebase_window_expr(&sort_expr.expr);
+            }
+        }
+
+        if !sort_exprs.is_empty() {
+            if select.distinct {
+                check_sort_exprs_selected(&sort_exprs, &select_exprs, df.plan())?;
+            }
+            df = df.sort(sort_exprs)?;
+        }
+
+        df = df.project(select_exprs)?;
+
+        // Duplicates are removed after sorting, the first occurrence of each row keeps its place
+        if select.distinct {
+            df = df.distinct()?;
+        }
+
+        Ok(df.plan)
+    }
+
+    /// Plans the GROUP BY clause as the group exprs and the grouping sets, as indices into them.
+    /// The sets are the cross product of the sets of the items, e.g.
+    /// `GROUP BY a, ROLLUP(b, c)` has the sets `(a, b, c), (a, b), (a)`.
+    fn group_by_to_grouping_sets(
+        &self,
+        group_by: &[Expr],
+        df: &DataFrame,
+    ) -> Result<(Vec<LogicalExpr>, Vec<Vec<usize>>)> {
+        let is_grouping_construct = |expr: &Expr| {
+            matches!(
+                expr,
+                Expr::GroupingSets(_) | Expr::Rollup(_) | Expr::Cube(_)
+            )
+        };
+        if !group_by.iter().any(is_grouping_construct) {
+            let group_exprs = group_by
+                .iter()
+                .map(|expr| self.sql_to_expr(expr, df.schema()))
+                .collect::<Result<Vec<_>>>()?;
+            let grouping_sets = vec![(0..group_exprs.len()).collect()];
+            return Ok((group_exprs, grouping_sets));
+        }
+
+        let mut sets: Vec<Vec<&Expr>> = vec![vec![]];
+        for item in group_by {
+            let item_sets = match item {
+                Expr::GroupingSets(elements) => {
+                    elements.iter().map(|set| set.iter().collect()).collect()
+                }
+                Expr::Rollup(elements) => (0..=elements.len())
+                    .rev()
+                    .map(|len| elements[..len].iter().flatten().collect())
+                    .collect(),
+                Expr::Cube(elements) => {
+                    if elements.len() > 12 {
+                        return Err(too_many_grouping_sets());
+                    }
+                    let n = elements.len();
+                    (0..1usize << n)
+                        .rev()
+                        .map(|mask| {
+                            (0..n)
+                                .filter(|i| mask & (1 << (n - 1 - i)) != 0)
+                                .flat_map(|i| elements[i].iter())
+                                .collect()
+                        })
+                        .collect()
+                }
+                expr => vec![vec![expr]],
+            };
+            if sets.len() * item_sets.len() > MAX_GROUPING_SETS {
+                return Err(too_many_grouping_sets());
+            }
+            sets = sets
+                .iter()
+                .flat_map(|set| {
+                    item_sets.iter().map(move |item_set: &Vec<&Expr>| {
+                        set.iter().chain(item_set.iter()).copied().collect()
+                    })
+                })
+                .collect();
+        }
+
+        // The same expression may appear in several sets, it is grouped by only once
+        let mut group_exprs = vec![];
+        let mut names = vec![];
+        let mut grouping_sets = vec![];
+        for set in sets {
+            let mut grouping_set = vec![];
+            for expr in set {
+                let expr = self.sql_to_expr(expr, df.schema())?;
+                let name = expr.to_field(df.plan())?.qualified_name();
+                let index = match names.iter().position(|n| n == &name) {
+                    Some(index) => index,
+                    None => {
+                        names.push(name);
+                        group_exprs.push(expr);
+                        group_exprs.len() - 1
+                    }
+                };
+                if !grouping_set.contains(&index) {
+                    grouping_set.push(index);
+                }
+            }
+            grouping_set.sort_unstable();
+            grouping_sets.push(grouping_set);
+        }
+
+        Ok((group_exprs, grouping_sets))
+    }
+
+    /// ORDER BY items may refer to select items by their alias or by their position
+    fn order_by_to_sort_expr(
+        &self,
+        order_by_expr: &OrderByExpr,
+        select_exprs: &[LogicalExpr],
+        schema: &Schema,
+    ) -> Result<SortExpr> {
+        let expr = match &order_by_expr.expr {
+            Expr::Value(Value::Number(n)) if !n.contains('.') => {
+                let position = n.parse::<usize>().unwrap_or(0);
+                if position == 0 || position > select_exprs.len() {
+                    return Err(Error::LogicalPlanError(format!(
+                        "ORDER BY position {} is not in select list",
+                        n
+                    )));
+                }
+                match &select_exprs[position - 1] {
+                    LogicalExpr::Alias(alias) => alias.expr.as_ref().clone(),
+                    expr => expr.clone(),
+                }
+            }
+            Expr::Identifier(name) => {
+                let aliased = select_exprs.iter().find_map(|expr| match expr {
+                    LogicalExpr::Alias(alias) if alias.name == name.value => {
+                        Some(alias.expr.clone())
+                    }
+                    _ => None,
+                });
+                match aliased {
+                    Some(expr) => *expr,
+                    None => self.sql_to_expr(&order_by_expr.expr, schema)?,
+                }
+            }
+            expr => self.sql_to_expr(expr, schema)?,
+        };
+
+        let asc = order_by_expr.asc.unwrap_or(true);
+
+        Ok(SortExpr {
+            expr,
+            asc,
+            // NULLs are treated as larger than any other value by default
+            nulls_first: order_by_expr.nulls_first.unwrap_or(!asc),
+        })
+    }
+
+    fn plan_from(&self, from: TableWithJoins) -> Result<DataFrame> {
+        let mut left = self.plan_table_factor(&from.relation)?;
+
+        for join in from.joins {
+            left = self.plan_join(left, join)?;
+        }
+
+        Ok(left)
+    }
+
+    /// Columns of a table are qualified by its alias, or by the table name without an alias
+    fn plan_table_factor(&self, table_factor: &TableFactor) -> Result<DataFrame> {
+        match table_factor {
+            TableFactor::Table { name, alias } => {
+                let df = match self.ctes.get(&name.value) {
+                    Some(plan) => DataFrame::new(plan.clone()),
+                    None => self
+                        .catalog
+                        .get_table_df(&name.value)
+                        .map_err(|e| e.at(name.span))?,
+                };
+                // The columns of `information_schema.tables` are qualified by `tables`
+                let default_alias = name.value.rsplit('.').next().unwrap_or(&name.value);
+                df.alias(alias.as_deref().unwrap_or(default_alias))
+            }
+            TableFactor::Derived { subquery, alias } => {
+                let df = DataFrame::new(self.query_to_plan(subquery.as_ref().clone())?);
+                match alias {
+                    Some(alias) => df.alias(alias),
+                    None => Ok(df),
+                }
+            }
+        }
+    }
+
+    /// Equalities between a column of each side become the join keys. The other conditions
+    /// of an inner join filter the joined rows, an outer join only supports join keys.
+    fn plan_join(&self, left: DataFrame, join: Join) -> Result<DataFrame> {
+        let right = self.plan_table_factor(&join.relation)?;
+
+        let mut left_keys = vec![];
+        let mut right_keys = vec![];
+        let mut filters = vec![];
+
+        let mut conjuncts = vec![];
+        split_conjunction(&join.constraint, &mut conjuncts);
+
+        for expr in conjuncts {
+            match join_keys(expr, left.schema(), right.schema())? {
+                Some((l_name, r_name)) => {
+                    left_keys.push(l_name);
+                    right_keys.push(r_name);
+                }
+                None => filters.push(expr),
+            }
+        }
+
+        let join_type = match join.join_type {
+            JoinType::Inner => logical_plan::JoinType::Inner,
+            JoinType::Left => logical_plan::JoinType::Left,
+            JoinType::Right => logical_plan::JoinType::Right,
+        };
+        if let (Some(filter), JoinType::Left | JoinType::Right) = (filters.first(), join.join_type)
+        {
+            return Err(Error::NotImplemented(format!(
+                "Only equalities between a column of each side are supported in the ON clause \
+                of an outer join, found {}",
+                filter
+            )));
+        }
+
+        let df = left.join(right.plan(), join_type, (left_keys, right_keys))?;
+        let schema = df.schema().clone();
+        let predicates = filters
+            .into_iter()
+            .map(|expr| self.sql_to_expr(expr, &schema))
+            .collect::<Result<Vec<_>>>()?;
+        match predicates.into_iter().reduce(|left, right| {
+            LogicalExpr::BinaryExpr(BinaryExpr {
+                left: Box::new(left),
+                op: Operator::And,
+                right: Box::new(right),
+            })
+        }) {
+            Some(predicate) => df.select(predicate),
+            None => Ok(df),
+        }
+    }
+
+    /// Converts a SQL expression into a logical expression,
+    /// column references are checked against `schema`
+    pub fn sql_to_expr(&self, expr: &Expr, schema: &Schema) -> Result<LogicalExpr> {
+        match expr {
+            Expr::Identifier(_) | Expr::CompoundIdentifier(_) => self
+                .column_to_expr(expr, schema)
+                .map_err(|e| e.at(column_span(expr))),
+            Expr::Subquery(query) => check_subquery(LogicalExpr::ScalarSubquery(
+                self.subquery_to_plan(query, schema)?,
+            )),
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => check_subquery(LogicalExpr::InSubquery(InSubquery {
+                expr: Box::new(self.sql_to_expr(expr, schema)?),
+                subquery: self.subquery_to_plan(subquery, schema)?,
+                negated: *negated,
+            })),
+            Expr::Exists { subquery, negated } => check_subquery(LogicalExpr::Exists(Exists {
+                subquery: self.subquery_to_plan(subquery, schema)?,
+                negated: *negated,
+            })),
+            Expr::Value(value) => Ok(LogicalExpr::Literal(value_to_scalar(value)?)),
+            Expr::BinaryOp { left, op, right } => Ok(LogicalExpr::BinaryExpr(BinaryExpr {
+                left: Box::new(self.sql_to_expr(left, schema)?),
+                op: op.clone(),
+                right: Box::new(self.sql_to_expr(right, schema)?),
+            })),
+            Expr::Nested(expr) => self.sql_to_expr(expr, schema),
+            Expr::Cast { expr, data_type } => Ok(LogicalExpr::Cast(Cast {
+                expr: Box::new(self.sql_to_expr(expr, schema)?),
+                data_type: data_type.clone(),
+            })),
+            Expr::TryCast { expr, data_type } => Ok(LogicalExpr::TryCast(TryCast {
+                expr: Box::new(self.sql_to_expr(expr, schema)?),
+                data_type: data_type.clone(),
+            })),
+            Expr::Placeholder(id) => Ok(LogicalExpr::Placeholder(*id)),
+            Expr::Wildcard => Err(Error::LogicalPlanError(
+                "* can only be the argument of COUNT".to_string(),
+            )),
+            Expr::PatternMatch {
+                kind,
+                negated,
+                expr,
+                pattern,
+                escape_char,
+            } => Ok(LogicalExpr::PatternMatch(PatternMatch {
+                kind: kind.clone(),
+                negated: *negated,
+                expr: Box::new(self.sql_to_expr(expr, schema)?),
+                pattern: Box::new(self.sql_to_expr(pattern, schema)?),
+                escape_char: *escape_char,
+            })),
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => Ok(LogicalExpr::InList(InList {
+                expr: Box::new(self.sql_to_expr(expr, schema)?),
+                list: list
+                    .iter()
+                    .map(|expr| self.sql_to_expr(expr, schema))
+                    .collect::<Result<Vec<_>>>()?,
+                negated: *negated,
+            })),
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => Ok(LogicalExpr::Between(Between {
+                expr: Box::new(self.sql_to_expr(expr, schema)?),
+                negated: *negated,
+                low: Box::new(self.sql_to_expr(low, schema)?),
+                high: Box::new(self.sql_to_expr(high, schema)?),
+            })),
+            Expr::UnaryOp { op, expr } => Ok(LogicalExpr::UnaryExpr(UnaryExpr {
+                op: op.clone(),
+                expr: Box::new(self.sql_to_expr(expr, schema)?),
+            })),
+            Expr::GroupingSets(_) | Expr::Rollup(_) | Expr::Cube(_) => {
+                Err(Error::LogicalPlanError(
+                    "GROUPING SETS, ROLLUP and CUBE can only be used in GROUP BY".to_string(),
+                ))
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => {
+                let expr = |expr: &Expr| self.sql_to_expr(expr, schema).map(Box::new);
+                Ok(LogicalExpr::Case(Case {
+                    expr: operand.as_deref().map(expr).transpose()?,
+                    when_then_expr: conditions
+                        .iter()
+                        .zip(results)
+                        .map(|(condition, result)| Ok((expr(condition)?, expr(result)?)))
+                        .collect::<Result<Vec<_>>>()?,
+                    else_expr: else_result.as_deref().map(expr).transpose()?,
+                }))
+            }
+            Expr::Function {
+                name,
+                args,
+                distinct,
+                filter,
+                over,
+            } => self
+                .function_to_expr(
+                    &name.value,
+                    args,
+                    *distinct,
+                    filter.as_deref(),
+                    over.as_ref(),
+                    schema,
+                )
+                .map_err(|e| e.at(name.span)),
+        }
+    }
+
+    /// Plans a column reference, a column that is only found in the enclosing query
+    /// is an outer column reference
+    fn column_to_expr(&self, expr: &Expr, schema: &Schema) -> Result<LogicalExpr> {
+        let name = column_name(expr)?;
+        if contains_column(schema, &name)? {
+            return Ok(LogicalExpr::Column(name));
+        }
+        match &self.outer_schema {
+            Some(outer_schema) if contains_column(outer_schema, &name)? => Ok(
+                LogicalExpr::OuterColumn(outer_schema.find_field_by_name(&name)?),
+            ),
+            _ => Err(Error::NoSuchColumn(name)),
+        }
+    }
+
+    /// Plans a function call, it is a window function if it has an OVER clause
+    fn function_to_expr(
+        &self,
+        name: &str,
+        args: &[Expr],
+        distinct: bool,
+        filter: Option<&Expr>,
+        over: Option<&WindowSpec>,
+        schema: &Schema,
+    ) -> Result<LogicalExpr> {
+        if let Some(over) = over {
+            if distinct || filter.is_some() {
+                return Err(Error::NotImplemented(format!(
+                    "DISTINCT and FILTER in window function {}",
+                    name
+                )));
+            }
+            return self.window_function_to_expr(name, args, over, schema);
+        }
+
+        let func = match name.to_uppercase().as_str() {
+            "SUM" => AggregateFunc::SUM,
+            "MIN" => AggregateFunc::MIN,
+            "MAX" => AggregateFunc::MAX,
+            "AVG" => AggregateFunc::AVG,
+            "COUNT" => AggregateFunc::COUNT,
+            "GROUPING" if !distinct && filter.is_none() => {
+                return self.grouping_to_expr(args, schema)
+            }
+            "GROUPING" => {
+                return Err(Error::LogicalPlanError(
+                    "GROUPING cannot have DISTINCT or FILTER".to_string(),
+                ))
+            }
+            "ROW_NUMBER" | "RANK" | "DENSE_RANK" | "LAG" | "LEAD" | "FIRST_VALUE"
+            | "LAST_VALUE" => {
+                return Err(Error::LogicalPlanError(format!(
+                    "Window function {} requires an OVER clause",
+                    name
+                )))
+            }
+            _ => {
+                return Err(Error::LogicalPlanError(format!(
+                    "Unsupported function {}",
+                    name
+                )))
+            }
+        };
+
+        if args.len() != 1 {
+            return Err(Error::LogicalPlanError(format!(
+                "Aggregate function {} expects exactly one argument",
+                name
+            )));
+        }
+
+        let filter = match filter {
+            Some(filter) => {
+                let filter = self.sql_to_expr(filter, schema)?;
+                check_no_window_exprs(&filter, "FILTER")?;
+                Some(Box::new(filter))
+            }
+            None => None,
+        };
+
+        let expr = match (&func, &args[0]) {
+            (AggregateFunc::COUNT, Expr::Wildcard) => LogicalExpr::Wildcard,
+            (_, arg) => self.sql_to_expr(arg, schema)?,
+        };
+
+        Ok(LogicalExpr::AggregateFuncExpr(AggregateFuncExpr {
+            func,
+            expr: Box::new(expr),
+            distinct,
+            filter,
+        }))
+    }
+
+    /// Plans `GROUPING(args)`, with several arguments it is a bit mask with a bit for each
+    /// argument, the last argument being the lowest bit, e.g. `GROUPING(a) * 2 + GROUPING(b)`
+    fn grouping_to_expr(&self, args: &[Expr], schema: &Schema) -> Result<LogicalExpr> {
+        let args = args
+            .iter()
+            .map(|arg| self.sql_to_expr(arg, schema))
+            .collect::<Result<Vec<_>>>()?;
+        let grouping = |arg: &LogicalExpr| {
+            LogicalExpr::AggregateFuncExpr(AggregateFuncExpr {
+                func: AggregateFunc::GROUPING,
+                expr: Box::new(arg.clone()),
+                distinct: false,
+                filter: None,
+            })
+        };
+
+        let (first, rest) = args.split_first().ok_or_else(|| {
+            Error::LogicalPlanError("GROUPING expects at least one argument".to_string())
+        })?;
+        if rest.is_empty() {
+            return Ok(grouping(first));
+        }
+
+        let mask = rest.iter().fold(grouping(first), |higher_bits, arg| {
+            LogicalExpr::BinaryExpr(BinaryExpr {
+                left: Box::new(LogicalExpr::BinaryExpr(BinaryExpr {
+                    left: Box::new(higher_bits),
+                    op: Operator::Mul,
+                    right: Box::new(LogicalExpr::Literal(Scalar::Int64(Some(2)))),
+                })),
+                op: Operator::Add,
+                right: Box::new(grouping(arg)),
+            })
+        });
+        let names = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        Ok(LogicalExpr::Alias(Alias {
+            name: format!("GROUPING({})", names.join(", ")),
+            expr: Box::new(mask),
+        }))
+    }
+
+    /// Plans `name(args) OVER (...)`, LAG and LEAD get the default offset 1 and default NULL
+    fn window_function_to_expr(
+        &self,
+        name: &str,
+        args: &[Expr],
+        over: &WindowSpec,
+        schema: &Schema,
+    ) -> Result<LogicalExpr> {
+        let (func, num_args) = match name.to_uppercase().as_str() {
+            "ROW_NUMBER" => (WindowFunc::RowNumber, 0..=0),
+            "RANK" => (WindowFunc::Rank, 0..=0),
+            "DENSE_RANK" => (WindowFunc::DenseRank, 0..=0),
+            "LAG" => (WindowFunc::Lag, 1..=3),
+            "LEAD" => (WindowFunc::Lead, 1..=3),
+            "FIRST_VALUE" => (WindowFunc::FirstValue, 1..=1),
+            "LAST_VALUE" => (WindowFunc::LastValue, 1..=1),
+            "SUM" => (WindowFunc::Aggregate(AggregateFunc::SUM), 1..=1),
+            "MIN" => (WindowFunc::Aggregate(AggregateFunc::MIN), 1..=1),
+            "MAX" => (WindowFunc::Aggregate(AggregateFunc::MAX), 1..=1),
+            "AVG" => (WindowFunc::Aggregate(AggregateFunc::AVG), 1..=1),
+            "COUNT" => (WindowFunc::Aggregate(AggregateFunc::COUNT), 1..=1),
+            _ => {
+                return Err(Error::LogicalPlanError(format!(
+                    "Unsupported window function {}",
+                    name
+                )))
+            }
+        };
+
+        if !num_args.contains(&args.len()) {
+            let expected = if num_args.start() == num_args.end() {
+                num_args.start().to_string()
+            } else {
+                format!("{} to {}", num_args.start(), num_args.end())
+            };
+            return Err(Error::LogicalPlanError(format!(
+                "Window function {} expects {} arguments, got {}",
+                name,
+                expected,
+                args.len()
+            )));
+        }
+
+        let mut args = args
+            .iter()
+            .map(|arg| match (&func, arg) {
+                (WindowFunc::Aggregate(AggregateFunc::COUNT), Expr::Wildcard) => {
+                    Ok(LogicalExpr::Wildcard)
+                }
+                _ => self.sql_to_expr(arg, schema),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if matches!(func, WindowFunc::Lag | WindowFunc::Lead) {
+            if args.len() < 2 {
+                args.push(LogicalExpr::Literal(Scalar::Int64(Some(1))));
+            }
+            if !matches!(&args[1], LogicalExpr::Literal(Scalar::Int64(Some(offset))) if *offset >= 0)
+            {
+                return Err(Error::LogicalPlanError(format!(
+                    "The offset of {} must be a non-negative integer, found {}",
+                    name, args[1]
+                )));
+            }
+            if args.len() < 3 {
+                args.push(LogicalExpr::Literal(Scalar::Null));
+            }
+        }
+
+        let partition_by = over
+            .partition_by
+            .iter()
+            .map(|expr| self.sql_to_expr(expr, schema))
+            .collect::<Result<Vec<_>>>()?;
+        let order_by = over
+            .order_by
+            .iter()
+            .map(|order_by_expr| {
+                let asc = order_by_expr.asc.unwrap_or(true);
+                Ok(SortExpr {
+                    expr: self.sql_to_expr(&order_by_expr.expr, schema)?,
+                    asc,
+                    nulls_first: order_by_expr.nulls_first.unwrap_or(!asc),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let sort_exprs = order_by.iter().map(|sort_expr| &sort_expr.expr);
+        for expr in args.iter().chain(&partition_by).chain(sort_exprs) {
+            check_no_window_exprs(expr, "the arguments or window of a window function")?;
+        }
+        if let Some(window_frame) = &over.window_frame {
+            check_window_frame(window_frame)?;
+        }
+
+        Ok(LogicalExpr::WindowFuncExpr(WindowFuncExpr {
+            func,
+            args,
+            partition_by,
+            order_by,
+            window_frame: over.window_frame.clone(),
+        }))
+    }
+}
+
+/// The maximum number of grouping sets a GROUP BY clause may expand to
+const MAX_GROUPING_SETS: usize = 4096;
+
+fn too_many_grouping_sets() -> Error {
+    Error::LogicalPlanError(format!(
+        "GROUP BY expands to more than {} grouping sets",
+        MAX_GROUPING_SETS
+    ))
+}
+
+/// Window functions are computed after WHERE, GROUP BY and HAVING, so they cannot be used there
+fn check_no_window_exprs(expr: &LogicalExpr, clause: &str) -> Result<()> {
+    let mut window_exprs = vec![];
+    find_window_exprs(expr, &mut window_exprs);
+    match window_exprs.first() {
+        Some(window_expr) => Err(Error::LogicalPlanError(format!(
+            "Window function {} is not allowed in {}",
+            window_expr, clause
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// A frame must not start after it ends, and RANGE frames only support
+/// UNBOUNDED and CURRENT ROW bounds
+fn check_window_frame(window_frame: &WindowFrame) -> Result<()> {
+    // Orders the bounds from the start of the partition to its end
+    let position = |bound: &WindowFrameBound| match bound {
+        WindowFrameBound::UnboundedPreceding => (0, 0),
+        WindowFrameBound::Preceding(n) => (1, -(*n as i64)),
+        WindowFrameBound::CurrentRow => (1, 0),
+        WindowFrameBound::Following(n) => (1, *n as i64),
+        WindowFrameBound::UnboundedFollowing => (2, 0),
+    };
+
+    if window_frame.start_bound == WindowFrameBound::UnboundedFollowing {
+        return Err(Error::LogicalPlanError(
+            "A window frame cannot start at UNBOUNDED FOLLOWING".to_string(),
+        ));
+    }
+    if window_frame.end_bound == WindowFrameBound::UnboundedPreceding {
+        return Err(Error::LogicalPlanError(
+            "A window frame cannot end at UNBOUNDED PRECEDING".to_string(),
+        ));
+    }
+    if position(&window_frame.start_bound) > position(&window_frame.end_bound) {
+        return Err(Error::LogicalPlanError(format!(
+            "The window frame {} starts after it ends",
+            window_frame
+        )));
+    }
+
+    let has_offset = |bound: &WindowFrameBound| {
+        matches!(
+            bound,
+            WindowFrameBound::Preceding(_) | WindowFrameBound::Following(_)
+        )
+    };
+    if window_frame.units == WindowFrameUnits::Range
+        && (has_offset(&window_frame.start_bound) || has_offset(&window_frame.end_bound))
+    {
+        return Err(Error::NotImplemented(format!(
+            "{}, RANGE frames only support UNBOUNDED and CURRENT ROW bounds",
+            window_frame
+        )));
+    }
+
+    Ok(())
+}
+
+fn value_to_scalar(value: &Value) -> Result<Scalar> {
+    match value {
+        Value::Number(n) if n.contains('.') => n
+            .parse::<f64>()
+            .map(|v| Scalar::Float64(Some(v)))
+            .map_err(|_| Error::ParserError(format!("Cannot parse {} as Float64", n))),
+        // Integers above i64::MAX are unsigned
+        Value::Number(n) => n
+            .parse::<i64>()
+            .map(|v| Scalar::Int64(Some(v)))
+            .or_else(|_| n.parse::<u64>().map(|v| Scalar::UInt64(Some(v))))
+            .map_err(|_| Error::ParserError(format!("Cannot parse {} as Int64", n))),
+        Value::SingleQuotedString(s) => Ok(Scalar::Utf8(Some(s.clone()))),
+        Value::Boolean(b) => Ok(Scalar::Boolean(Some(*b))),
+        Value::Null => Ok(Scalar::Null),
+    }
+}
+
+/// Returns the name of a column reference, `relation.column` for a qualified reference
+fn column_name(expr: &Expr) -> Result<String> {
+    match expr {
+        Expr::Identifier(ident) => Ok(ident.value.clone()),
+        Expr::CompoundIdentifier(idents) if idents.len() == 2 => {
+            Ok(format!("{}.{}", idents[0], idents[1]))
+        }
+        _ => Err(Error::LogicalPlanError(format!(
+            "Expected a column reference, found {}",
+            expr
+        ))),
+    }
+}
+
+/// Returns the columns of the left and the right side compared by `expr` if it is an equality
+/// between a column of each side, which makes them join keys
+fn join_keys(expr: &Expr, left: &Schema, right: &Schema) -> Result<Option<(String, String)>> {
+    let (l, r) = match expr {
+        Expr::BinaryOp {
+            left: l,
+            op: Operator::Eq,
+            right: r,
+        } => (l.as_ref(), r.as_ref()),
+        _ => return Ok(None),
+    };
+    let (l_name, r_name) = match (column_name(l), column_name(r)) {
+        (Ok(l_name), Ok(r_name)) => (l_name, r_name),
+        _ => return Ok(None),
+    };
+
+    // Both sides are visible in the ON clause, so a bare name present on both is ambiguous
+    let join_schema = left.join(right);
+    for (expr, name) in [(l, &l_name), (r, &r_name)] {
+        let found = contains_column(&join_schema, name).map_err(|e| e.at(column_span(expr)))?;
+        if !found {
+            return Err(Error::NoSuchColumn(name.clone()).at(column_span(expr)));
+        }
+    }
+
+    match (
+        contains_column(left, &l_name)?,
+        contains_column(left, &r_name)?,
+    ) {
+        (true, false) => Ok(Some((l_name, r_name))),
+        (false, true) => Ok(Some((r_name, l_name))),
+        // Both columns are on the same side
+        _ => Ok(None),
+    }
+}
+
+/// The part of the SQL text a column reference was parsed from
+fn column_span(expr: &Expr) -> Option<Span> {
+    match expr {
+        Expr::Identifier(ident) => ident.span,
+        Expr::CompoundIdentifier(idents) => join_spans(idents),
+        _ => None,
+    }
+}
+
+/// Whether the schema has the column, an ambiguous reference is an error
+fn contains_column(schema: &Schema, name: &str) -> Result<bool> {
+    match schema.index_of(name) {
+        Ok(_) => Ok(true),
+        Err(Error::NoSuchField(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Names the columns of a CTE by its column list, if it has one
+fn rename_columns(plan: LogicalPlan, name: &str, columns: &[String]) -> Result<LogicalPlan> {
+    if columns.is_empty() {
+        return Ok(plan);
+    }
+
+    let fields = plan.schema().fields();
+    if fields.len() != columns.len() {
+        return Err(Error::LogicalPlanError(format!(
+            "{} has {} columns but {} column names were given",
+            name,
+            fields.len(),
+            columns.len()
+        )));
+    }
+
+    let exprs = fields
+        .iter()
+        .zip(columns)
+        .map(|(field, column)| {
+            LogicalExpr::Alias(Alias {
+                name: column.clone(),
+                expr: Box::new(LogicalExpr::Column(field.qualified_name())),
+            })
+        })
+        .collect();
+
+    Ok(DataFrame::new(plan).project(exprs)?.plan)
+}
+
+/// Whether the plan reads the work table of the recursive query `name`
+fn reads_work_table(plan: &LogicalPlan, name: &str) -> bool {
+    match plan {
+        LogicalPlan::WorkTableScan(work_table_scan) => work_table_scan.name == name,
+        plan => plan
+            .children()
+            .iter()
+            .any(|child| reads_work_table(child, name)),
+    }
+}
+
+/// Flattens `a AND b AND c` into `[a, b, c]`
+fn split_conjunction<'e>(expr: &'e Expr, exprs: &mut Vec<&'e Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: Operator::And,
+            right,
+        } => {
+            split_conjunction(left, exprs);
+            split_conjunction(right, exprs);
+        }
+        Expr::Nested(expr) => split_conjunction(expr, exprs),
+        _ => exprs.push(expr),
+    }
+}
+
+/// With SELECT DISTINCT, the rows can only be ordered by expressions in the select list
+fn check_sort_exprs_selected(
+    sort_exprs: &[SortExpr],
+    select_exprs: &[LogicalExpr],
+    input: &LogicalPlan,
+) -> Result<()> {
+    let mut selected = vec![];
+    for expr in select_exprs {
+        let expr = match expr {
+            LogicalExpr::Alias(alias) => alias.expr.as_ref(),
+            expr => expr,
+        };
+        selected.push(expr.to_field(input)?.qualified_name());
+    }
+
+    for sort_expr in sort_exprs {
+        let name = sort_expr.expr.to_field(input)?.qualified_name();
+        if !selected.contains(&name) {
+            return Err(Error::LogicalPlanError(format!(
+                "For SELECT DISTINCT, ORDER BY expression {} must appear in select list",
+                name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalog::Catalog;
+    use crate::error::{Error, Result};
+    use crate::planner::QueryPlanner;
+    use crate::sql::tokenizer::Location;
+    use arrow::array::StringArray;
+    use arrow::util::pretty;
+
+    fn run(catalog: &Catalog, sql: &str) -> Result<String> {
+        let df = catalog.sql(sql)?;
+        let batches = QueryPlanner::create_physical_plan(df.plan())?.execute()?;
+        Ok(pretty::pretty_format_batches(&batches)?.to_string())
+    }
+
+    fn catalog() -> Result<Catalog> {
+        let mut catalog = Catalog::default();
+        catalog.add_csv_table("test", "data/test.csv")?;
+        catalog.add_csv_table("salary", "data/salary.csv")?;
+        catalog.add_csv_table("employee", "data/employee.csv")?;
+        Ok(catalog)
+    }
+
+    #[test]
+    fn sql_selection_and_projection() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(&catalog, "SELECT name, score FROM test WHERE age >= 24")?,
+            "+-------------+-------+\
+            \n| name        | score |\
+            \n+-------------+-------+\
+            \n| bigboss2063 | 0     |\
+            \n| Vincent Hu  | 100   |\
+            \n| Brian       | 99.97 |\
+            \n+-------------+-------+"
+        );
+
+        assert!(run(&catalog, "SELECT id FROM test WHERE height > 170").is_err());
+
+        assert_eq!(
+            run(
+                &catalog,
+                "select id, (age + 1) * 2 as doubled from test where age < 20 and score > 99.98"
+            )?,
+            "+----+---------+\
+            \n| id | doubled |\
+            \n+----+---------+\
+            \n| 3  | 38      |\
+            \n+----+---------+"
+        );
+
+        // Without FROM the select list is evaluated on a single row
+        assert_eq!(
+            run(&catalog, "SELECT 1 + 2 AS three, 'a' AS s, COUNT(*) AS cnt")?,
+            "+-------+---+-----+\
+            \n| three | s | cnt |\
+            \n+-------+---+-----+\
+            \n| 3     | a | 1   |\
+            \n+-------+---+-----+"
+        );
+        assert_eq!(
+            run(&catalog, "SELECT 1 AS one WHERE 1 > 2")?,
+            "+-----+\
+            \n| one |\
+            \n+-----+\
+            \n+-----+"
+        );
+        assert!(run(&catalog, "SELECT id").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_aggregation() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, COUNT(id) AS cnt, MAX(score) FROM test GROUP BY age"
+            )?,
+            "+-----+-----+------------+\
+            \n| age | cnt | MAX(score) |\
+            \n+-----+-----+------------+\
+            \n| 24  | 2   | 100        |\
+            \n| 18  | 2   | 99.99      |\
+            \n| 26  | 1   | 99.97      |\
+            \n+-----+-----+------------+"
+        );
+
+        assert_eq!(
+            run(&catalog, "SELECT SUM(salary), MIN(awards) FROM salary")?,
+            "+-------------+-------------+\
+            \n| SUM(salary) | MIN(awards) |\
+            \n+-------------+-------------+\
+            \n| 142000      | 0           |\
+            \n+-------------+-------------+"
+        );
+
+        // Each group is averaged over its own rows
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, AVG(id) AS avg_id, AVG(score) AS avg_score FROM test GROUP BY age"
+            )?,
+            "+-----+--------+-----------+\
+            \n| age | avg_id | avg_score |\
+            \n+-----+--------+-----------+\
+            \n| 24  | 1.5    | 50        |\
+            \n| 18  | 3.5    | 99.985    |\
+            \n| 26  | 5      | 99.97     |\
+            \n+-----+--------+-----------+"
+        );
+
+        assert!(run(&catalog, "SELECT name, COUNT(id) FROM test GROUP BY age").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_order_by() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name, score AS s FROM test ORDER BY age DESC, s"
+            )?,
+            "+-------------+-------+\
+            \n| name        | s     |\
+            \n+-------------+-------+\
+            \n| Brian       | 99.97 |\
+            \n| bigboss2063 | 0     |\
+            \n| Vincent Hu  | 100   |\
+            \n| nutswalker  | 99.98 |\
+            \n| KamenRider  | 99.99 |\
+            \n+-------------+-------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, COUNT(id) FROM test GROUP BY age ORDER BY SUM(score) DESC"
+            )?,
+            "+-----+-----------+\
+            \n| age | COUNT(id) |\
+            \n+-----+-----------+\
+            \n| 18  | 2         |\
+            \n| 24  | 2         |\
+            \n| 26  | 1         |\
+            \n+-----+-----------+"
+        );
+
+        assert_eq!(
+            run(&catalog, "SELECT id, name FROM salary ORDER BY 1 DESC")?,
+            "+----+-----------------+\
+            \n| id | name            |\
+            \n+----+-----------------+\
+            \n| 5  | Big Mountain    |\
+            \n| 4  | God Feng        |\
+            \n| 3  | God Hai         |\
+            \n| 2  | Tiger of Wisdom |\
+            \n| 1  | bigboss2063     |\
+            \n+----+-----------------+"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_limit_offset() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, name FROM salary ORDER BY salary DESC LIMIT 2 OFFSET 1"
+            )?,
+            "+----+----------+\
+            \n| id | name     |\
+            \n+----+----------+\
+            \n| 4  | God Feng |\
+            \n| 3  | God Hai  |\
+            \n+----+----------+"
+        );
+
+        assert_eq!(
+            run(&catalog, "SELECT id FROM test WHERE age < 25 OFFSET 3")?,
+            "+----+\
+            \n| id |\
+            \n+----+\
+            \n| 4  |\
+            \n+----+"
+        );
+
+        assert!(run(&catalog, "SELECT id FROM test LIMIT 'a'").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_distinct() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT DISTINCT age, age > 20 AS adult FROM test ORDER BY age LIMIT 2"
+            )?,
+            "+-----+-------+\
+            \n| age | adult |\
+            \n+-----+-------+\
+            \n| 18  | false |\
+            \n| 24  | true  |\
+            \n+-----+-------+"
+        );
+
+        assert!(run(&catalog, "SELECT DISTINCT age FROM test ORDER BY score").is_err());
+
+        // -0.0 equals 0.0 and NaN equals NaN
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT DISTINCT column1 AS v FROM (VALUES (0.0), (-0.0), \
+                 (CAST('NaN' AS DOUBLE)), (-CAST('NaN' AS DOUBLE))) t"
+            )?,
+            "+-----+\
+            \n| v   |\
+            \n+-----+\
+            \n| 0   |\
+            \n| NaN |\
+            \n+-----+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT COUNT(*) AS cnt FROM (VALUES (-0.0), (0.0)) t GROUP BY column1"
+            )?,
+            "+-----+\
+            \n| cnt |\
+            \n+-----+\
+            \n| 2   |\
+            \n+-----+"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_having() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, MAX(score) AS best FROM test GROUP BY age HAVING SUM(age) > 30 AND MIN(score) > 50.0"
+            )?,
+            "+-----+-------+\
+            \n| age | best  |\
+            \n+-----+-------+\
+            \n| 18  | 99.99 |\
+            \n+-----+-------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT SUM(salary) FROM salary HAVING SUM(salary) > 1000000"
+            )?,
+            "+-------------+\
+            \n| SUM(salary) |\
+            \n+-------------+\
+            \n+-------------+"
+        );
+
+        assert!(run(
+            &catalog,
+            "SELECT age FROM test GROUP BY age HAVING score > 1.0"
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_join() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT test.name, salary FROM test JOIN salary ON test.id = salary.id WHERE salary > 20000"
+            )?,
+            "+------------+--------+\
+            \n| name       | salary |\
+            \n+------------+--------+\
+            \n| KamenRider | 30000  |\
+            \n| nutswalker | 40000  |\
+            \n| Brian      | 50000  |\
+            \n+------------+--------+"
+        );
+
+        // The conditions that are not join keys filter the joined rows of an inner join
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT test.id, salary FROM test JOIN salary ON test.id = salary.id \
+                 AND salary.salary > 20000 AND test.age < salary.awards + 20 ORDER BY test.id"
+            )?,
+            "+----+--------+\
+            \n| id | salary |\
+            \n+----+--------+\
+            \n| 3  | 30000  |\
+            \n| 4  | 40000  |\
+            \n| 5  | 50000  |\
+            \n+----+--------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT test.id, salary.id AS sid FROM test JOIN salary ON test.id > salary.awards \
+                 ORDER BY test.id, sid"
+            )?,
+            "+----+-----+\
+            \n| id | sid |\
+            \n+----+-----+\
+            \n| 1  | 1   |\
+            \n| 2  | 1   |\
+            \n| 3  | 1   |\
+            \n| 4  | 1   |\
+            \n| 5  | 1   |\
+            \n+----+-----+"
+        );
+
+        // The keys are compared as their common type, Float64 here
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT t.name, s.id FROM test t JOIN (SELECT id * 50 AS id FROM salary) s ON t.score = s.id"
+            )?,
+            "+------------+-----+\
+            \n| name       | id  |\
+            \n+------------+-----+\
+            \n| Vincent Hu | 100 |\
+            \n+------------+-----+"
+        );
+
+        let err = run(
+            &catalog,
+            "SELECT * FROM test LEFT JOIN salary ON test.id = salary.id AND salary.salary > 1",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Not implemented: Only equalities between a column of each side are supported \
+             in the ON clause of an outer join, found salary.salary > 1"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_table_alias_and_derived_table() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT t.name, s.name AS nickname FROM test AS t JOIN salary s ON t.id = s.id WHERE s.awards > 5"
+            )?,
+            "+------------+--------------+\
+            \n| name       | nickname     |\
+            \n+------------+--------------+\
+            \n| KamenRider | God Hai      |\
+            \n| nutswalker | God Feng     |\
+            \n| Brian      | Big Mountain |\
+            \n+------------+--------------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT young.name FROM (SELECT name, age FROM test WHERE age < 25) young WHERE young.age > 18"
+            )?,
+            "+-------------+\
+            \n| name        |\
+            \n+-------------+\
+            \n| bigboss2063 |\
+            \n| Vincent Hu  |\
+            \n+-------------+"
+        );
+
+        // The original table name cannot be used once the table has an alias
+        assert!(run(&catalog, "SELECT test.id FROM test t").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_ambiguous_column() -> Result<()> {
+        let catalog = catalog()?;
+
+        let err = run(
+            &catalog,
+            "SELECT name FROM test JOIN salary ON test.id = salary.id",
+        )
+        .unwrap_err();
+        assert!(matches!(err.root(), Error::AmbiguousColumn(_)));
+
+        let err = run(&catalog, "SELECT * FROM test JOIN salary ON id = id").unwrap_err();
+        assert!(matches!(err.root(), Error::AmbiguousColumn(_)));
+
+        // Without aliases both sides of a self-join have the same qualified names
+        let err = run(
+            &catalog,
+            "SELECT test.name FROM test JOIN test ON test.id = test.id",
+        )
+        .unwrap_err();
+        assert!(matches!(err.root(), Error::AmbiguousColumn(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_uncorrelated_subqueries() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name, salary FROM salary WHERE salary > (SELECT MIN(salary) FROM salary WHERE awards > 5)"
+            )?,
+            "+--------------+--------+\
+            \n| name         | salary |\
+            \n+--------------+--------+\
+            \n| God Feng     | 40000  |\
+            \n| Big Mountain | 50000  |\
+            \n+--------------+--------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, name FROM salary WHERE id IN (SELECT id FROM test WHERE age > 20)"
+            )?,
+            "+----+-----------------+\
+            \n| id | name            |\
+            \n+----+-----------------+\
+            \n| 1  | bigboss2063     |\
+            \n| 2  | Tiger of Wisdom |\
+            \n| 5  | Big Mountain    |\
+            \n+----+-----------------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id FROM salary WHERE id + 1 NOT IN (SELECT id FROM test WHERE age > 20)"
+            )?,
+            "+----+\
+            \n| id |\
+            \n+----+\
+            \n| 2  |\
+            \n| 3  |\
+            \n| 5  |\
+            \n+----+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name, (SELECT MAX(score) FROM test) AS best FROM test WHERE id = 1"
+            )?,
+            "+-------------+------+\
+            \n| name        | best |\
+            \n+-------------+------+\
+            \n| bigboss2063 | 100  |\
+            \n+-------------+------+"
+        );
+
+        assert!(run(
+            &catalog,
+            "SELECT id FROM test WHERE score > (SELECT score FROM test)"
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_correlated_subqueries() -> Result<()> {
+        let catalog = catalog()?;
+
+        // Students with the best score of their age
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name, age FROM test t WHERE score = (SELECT MAX(score) FROM test WHERE test.age = t.age)"
+            )?,
+            "+------------+-----+\
+            \n| name       | age |\
+            \n+------------+-----+\
+            \n| Vincent Hu | 24  |\
+            \n| KamenRider | 18  |\
+            \n| Brian      | 26  |\
+            \n+------------+-----+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name FROM test WHERE EXISTS (SELECT * FROM salary s WHERE s.id = test.id AND s.awards > 6)"
+            )?,
+            "+------------+\
+            \n| name       |\
+            \n+------------+\
+            \n| nutswalker |\
+            \n| Brian      |\
+            \n+------------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id FROM test WHERE NOT EXISTS (SELECT id FROM salary WHERE salary.id = test.id AND salary > 20000) AND age > 20"
+            )?,
+            "+----+\
+            \n| id |\
+            \n+----+\
+            \n| 1  |\
+            \n| 2  |\
+            \n+----+"
+        );
+
+        // A COUNT is 0 for the outer rows without matching inner rows
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id FROM test WHERE (SELECT COUNT(id) FROM salary s WHERE s.id = test.id AND s.salary > 20000) = 0"
+            )?,
+            "+----+\
+            \n| id |\
+            \n+----+\
+            \n| 1  |\
+            \n| 2  |\
+            \n+----+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, (SELECT COUNT(id) FROM salary s WHERE s.id = test.id AND s.salary > 20000) AS cnt FROM test"
+            )?,
+            "+----+-----+\
+            \n| id | cnt |\
+            \n+----+-----+\
+            \n| 1  | 0   |\
+            \n| 2  | 0   |\
+            \n| 3  | 1   |\
+            \n| 4  | 1   |\
+            \n| 5  | 1   |\
+            \n+----+-----+"
+        );
+        assert!(matches!(
+            catalog
+                .sql("SELECT id, (SELECT COUNT(id) + 1 FROM salary s WHERE s.id = test.id) FROM test")
+                .err()
+                .unwrap()
+                .root(),
+            Error::NotImplemented(_)
+        ));
+
+        // An unsupported correlation fails when the query is planned
+        let err = catalog
+            .sql("SELECT id FROM test WHERE EXISTS (SELECT id FROM salary WHERE salary.id > test.id)")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Correlated predicates must be equalities with an outer column, \
+             found salary.id > outer_ref(test.id)"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_common_table_expressions() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "WITH young AS (SELECT id, name FROM test WHERE age < 20), \
+                 rich(rich_id) AS (SELECT id FROM salary WHERE salary > 35000) \
+                 SELECT name FROM young JOIN rich ON id = rich_id \
+                 WHERE id IN (SELECT id FROM young)"
+            )?,
+            "+------------+\
+            \n| name       |\
+            \n+------------+\
+            \n| nutswalker |\
+            \n+------------+"
+        );
+
+        // A CTE shadows the table with the same name
+        assert_eq!(
+            run(
+                &catalog,
+                "WITH test AS (SELECT id FROM salary WHERE awards > 6) SELECT * FROM test"
+            )?,
+            "+----+\
+            \n| id |\
+            \n+----+\
+            \n| 4  |\
+            \n| 5  |\
+            \n+----+"
+        );
+
+        assert!(matches!(
+            run(
+                &catalog,
+                "WITH t(a, b) AS (SELECT id FROM test) SELECT * FROM t"
+            ),
+            Err(Error::LogicalPlanError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_recursive_cte() -> Result<()> {
+        let mut catalog = catalog()?;
+
+        // Everyone reporting to employee 2, directly or indirectly
+        let sql = "WITH RECURSIVE chain(id, name, manager_id) AS ( \
+                   SELECT id, name, manager_id FROM employee WHERE id = 2 \
+                   UNION ALL \
+                   SELECT e.id, e.name, e.manager_id FROM employee e JOIN chain c ON e.manager_id = c.id) \
+                   SELECT id, name FROM chain ORDER BY id DESC";
+        assert_eq!(
+            run(&catalog, sql)?,
+            "+----+-----------------+\
+            \n| id | name            |\
+            \n+----+-----------------+\
+            \n| 5  | Big Mountain    |\
+            \n| 4  | God Feng        |\
+            \n| 2  | Tiger of Wisdom |\
+            \n+----+-----------------+"
+        );
+
+        // The anchor can be a SELECT without FROM
+        assert_eq!(
+            run(
+                &catalog,
+                "WITH RECURSIVE r(n) AS (SELECT 1 UNION ALL SELECT n+1 FROM r WHERE n < 5) \
+                 SELECT n FROM r"
+            )?,
+            "+---+\
+            \n| n |\
+            \n+---+\
+            \n| 1 |\
+            \n| 2 |\
+            \n| 3 |\
+            \n| 4 |\
+            \n| 5 |\
+            \n+---+"
+        );
+
+        catalog.set_max_recursive_iterations(2);
+        assert!(matches!(
+            run(&catalog, sql),
+            Err(Error::PhysicalPlanError(_))
+        ));
+
+        // UNION removes the rows that were already returned, which ends the cycle
+        assert_eq!(
+            run(
+                &catalog,
+                "WITH RECURSIVE ids(id) AS ( \
+                 SELECT id FROM employee WHERE id = 1 \
+                 UNION \
+                 SELECT employee.id FROM employee JOIN ids ON employee.id = ids.id) \
+                 SELECT * FROM ids"
+            )?,
+            "+----+\
+            \n| id |\
+            \n+----+\
+            \n| 1  |\
+            \n+----+"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_set_operations() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age FROM test UNION SELECT awards FROM salary WHERE awards > 6 ORDER BY age"
+            )?,
+            "+-----+\
+            \n| age |\
+            \n+-----+\
+            \n| 7   |\
+            \n| 8   |\
+            \n| 18  |\
+            \n| 24  |\
+            \n| 26  |\
+            \n+-----+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age FROM test UNION ALL SELECT age FROM test WHERE age > 24 LIMIT 1 OFFSET 5"
+            )?,
+            "+-----+\
+            \n| age |\
+            \n+-----+\
+            \n| 26  |\
+            \n+-----+"
+        );
+
+        // INTERSECT binds more tightly than EXCEPT, the int ids are converted to floats
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT score FROM test EXCEPT SELECT id FROM salary INTERSECT SELECT score FROM test"
+            )?,
+            "+-------+\
+            \n| score |\
+            \n+-------+\
+            \n| 0     |\
+            \n| 100   |\
+            \n| 99.99 |\
+            \n| 99.98 |\
+            \n| 99.97 |\
+            \n+-------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age FROM test INTERSECT ALL (SELECT age FROM test WHERE id > 1 EXCEPT ALL SELECT age FROM test WHERE id = 3)"
+            )?,
+            "+-----+\
+            \n| age |\
+            \n+-----+\
+            \n| 24  |\
+            \n| 18  |\
+            \n| 26  |\
+            \n+-----+"
+        );
+
+        assert!(matches!(
+            run(
+                &catalog,
+                "SELECT id, name FROM test UNION SELECT id FROM salary"
+            ),
+            Err(Error::LogicalPlanError(_))
+        ));
+        assert!(matches!(
+            run(
+                &catalog,
+                "SELECT name FROM test UNION SELECT id FROM salary"
+            ),
+            Err(Error::LogicalPlanError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_case_when() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name, CASE WHEN score >= 99.98 THEN 'A' WHEN score >= 99.0 THEN 'B' ELSE 'C' END AS grade FROM test"
+            )?,
+            "+-------------+-------+\
+            \n| name        | grade |\
+            \n+-------------+-------+\
+            \n| bigboss2063 | C     |\
+            \n| Vincent Hu  | A     |\
+            \n| KamenRider  | A     |\
+            \n| nutswalker  | A     |\
+            \n| Brian       | B     |\
+            \n+-------------+-------+"
+        );
+
+        // The simple form without ELSE, and a CASE on an aggregate
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, CASE age WHEN 18 THEN 'junior' WHEN 24 THEN 'senior' END AS level, \
+                 CASE WHEN MIN(score) > 99.0 THEN MAX(score) ELSE 0 END AS best \
+                 FROM test GROUP BY age ORDER BY age"
+            )?,
+            "+-----+--------+-------+\
+            \n| age | level  | best  |\
+            \n+-----+--------+-------+\
+            \n| 18  | junior | 99.99 |\
+            \n| 24  | senior | 0     |\
+            \n| 26  |        | 99.97 |\
+            \n+-----+--------+-------+"
+        );
+
+        assert!(matches!(
+            run(
+                &catalog,
+                "SELECT CASE WHEN age > 20 THEN name ELSE 1 END FROM test"
+            ),
+            Err(Error::LogicalPlanError(_))
+        ));
+        assert!(matches!(
+            run(&catalog, "SELECT CASE WHEN age THEN name END FROM test"),
+            Err(Error::LogicalPlanError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_cast_and_coercion() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name, age + 0.5 AS older FROM test WHERE age > 20.5"
+            )?,
+            "+-------------+-------+\
+            \n| name        | older |\
+            \n+-------------+-------+\
+            \n| bigboss2063 | 24.5  |\
+            \n| Vincent Hu  | 24.5  |\
+            \n| Brian       | 26.5  |\
+            \n+-------------+-------+"
+        );
+
+        // AVG returns Float64 and COUNT returns UInt64, both are compared with Int64 values
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name FROM salary WHERE salary > (SELECT AVG(salary) FROM salary)"
+            )?,
+            "+--------------+\
+            \n| name         |\
+            \n+--------------+\
+            \n| God Hai      |\
+            \n| God Feng     |\
+            \n| Big Mountain |\
+            \n+--------------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age FROM test GROUP BY age HAVING COUNT(id) > 1"
+            )?,
+            "+-----+\
+            \n| age |\
+            \n+-----+\
+            \n| 24  |\
+            \n| 18  |\
+            \n+-----+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT CAST(score AS INT) AS s, TRY_CAST(name AS INT) AS n, CAST(id AS VARCHAR) AS t FROM test WHERE id = 2"
+            )?,
+            "+-----+---+---+\
+            \n| s   | n | t |\
+            \n+-----+---+---+\
+            \n| 100 |   | 2 |\
+            \n+-----+---+---+"
+        );
+
+        // UInt64 is compared with Int64 as Int64, without losing precision above 2^53
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id FROM test WHERE CAST('9007199254740993' AS BIGINT UNSIGNED) > 9007199254740992 AND id < 3"
+            )?,
+            "+----+\
+            \n| id |\
+            \n+----+\
+            \n| 1  |\
+            \n| 2  |\
+            \n+----+"
+        );
+        assert_eq!(
+            run(&catalog, "SELECT COUNT(id) + 1 AS n FROM test")?,
+            "+---+\
+            \n| n |\
+            \n+---+\
+            \n| 6 |\
+            \n+---+"
+        );
+        // A value above i64::MAX cannot be cast to Int64
+        assert!(matches!(
+            run(
+                &catalog,
+                "SELECT id FROM test WHERE id < 18446744073709551615"
+            ),
+            Err(Error::ArrowError(_))
+        ));
+
+        assert!(matches!(
+            run(&catalog, "SELECT CAST(name AS INT) FROM test"),
+            Err(Error::ArrowError(_))
+        ));
+        assert!(matches!(
+            run(&catalog, "SELECT name FROM test WHERE name > 1"),
+            Err(Error::LogicalPlanError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_information_schema() -> Result<()> {
+        let catalog = catalog()?;
+        catalog.sql("CREATE VIEW adults AS SELECT id, name FROM test WHERE age > 18")?;
+
+        assert_eq!(
+            run(&catalog, "SHOW TABLES")?,
+            "+------------+------------+-------------+\
+            \n| table_name | table_type | source_type |\
+            \n+------------+------------+-------------+\
+            \n| adults     | VIEW       |             |\
+            \n| employee   | BASE TABLE | CSV file    |\
+            \n| salary     | BASE TABLE | CSV file    |\
+            \n| test       | BASE TABLE | CSV file    |\
+            \n+------------+------------+-------------+"
+        );
+
+        assert_eq!(
+            run(&catalog, "DESCRIBE test")?,
+            "+-------------+-----------+-------------+\
+            \n| column_name | data_type | is_nullable |\
+            \n+-------------+-----------+-------------+\
+            \n| id          | Int64     | NO          |\
+            \n| name        | Utf8      | NO          |\
+            \n| age         | Int64     | NO          |\
+            \n| score       | Float64   | NO          |\
+            \n+-------------+-----------+-------------+"
+        );
+        assert_eq!(
+            run(&catalog, "SHOW COLUMNS FROM adults")?,
+            "+-------------+-----------+-------------+\
+            \n| column_name | data_type | is_nullable |\
+            \n+-------------+-----------+-------------+\
+            \n| id          | Int64     | NO          |\
+            \n| name        | Utf8      | NO          |\
+            \n+-------------+-----------+-------------+"
+        );
+        let err = run(&catalog, "DESCRIBE missing").unwrap_err();
+        assert!(matches!(err.root(), Error::NoSuchTable(_)));
+
+        // The information_schema tables are queried and joined like other tables
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT tables.table_name, COUNT(column_name) AS columns \
+                 FROM information_schema.tables \
+                 JOIN information_schema.columns c ON tables.table_name = c.table_name \
+                 WHERE c.data_type = 'Int64' GROUP BY tables.table_name ORDER BY tables.table_name"
+            )?,
+            "+------------+---------+\
+            \n| table_name | columns |\
+            \n+------------+---------+\
+            \n| adults     | 1       |\
+            \n| employee   | 2       |\
+            \n| salary     | 3       |\
+            \n| test       | 2       |\
+            \n+------------+---------+"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_errors() -> Result<()> {
+        let catalog = catalog()?;
+        let error = |sql: &str| run(&catalog, sql).unwrap_err();
+
+        // Unknown tables and columns point at the SQL text
+        let err = error("SELECT name\nFROM test\nWHERE test.agee > 1");
+        assert_eq!(
+            err.to_string(),
+            "Column test.agee does not exist at line 3, columns 7-15"
+        );
+        match err {
+            Error::Sql { source, span } => {
+                assert!(matches!(*source, Error::NoSuchColumn(name) if name == "test.agee"));
+                assert_eq!(span.start, Location { line: 3, column: 7 });
+            }
+            err => panic!("Expected a located error, found {:?}", err),
+        }
+        assert_eq!(
+            error("SELECT * FROM tset").to_string(),
+            "Table tset does not exist at line 1, columns 15-18"
+        );
+        // The error points at the failing use of a name, not the first place it appears
+        assert_eq!(
+            error("SELECT score AS nope FROM test WHERE nope > 1").to_string(),
+            "Column nope does not exist at line 1, columns 38-41"
+        );
+        assert_eq!(
+            error("SELECT t.id FROM test AS t JOIN salary AS s ON t.id = s.idd").to_string(),
+            "Column s.idd does not exist at line 1, columns 55-59"
+        );
+        assert_eq!(
+            error("SELECT id, nme(id) FROM test").to_string(),
+            "Unsupported function nme at line 1, columns 12-14"
+        );
+
+        // Errors show the SQL of the expression
+        assert_eq!(
+            error("SELECT test.age.x FROM test").to_string(),
+            "Expected a column reference, found test.age.x at line 1, columns 8-17"
+        );
+        assert_eq!(
+            error("SELECT id FROM test LIMIT -(1 + 2)").to_string(),
+            "LIMIT must be a non-negative integer, found -(1 + 2)"
+        );
+
+        // Unsupported operations return errors instead of panicking
+        assert_eq!(
+            error("SELECT SUM(name) FROM test").to_string(),
+            "Not implemented: SUM of type Utf8"
+        );
+        assert_eq!(
+            error("SELECT name FROM test WHERE id").to_string(),
+            "The predicate id is Int64, but it must be Boolean"
+        );
+
+        let err = error("CREATE EXTERNAL TABLE t STORED AS CSV LOCATION 'data/missing.csv'");
+        assert!(err
+            .to_string()
+            .starts_with("Cannot open CSV file data/missing.csv: IO error: "));
+        assert!(matches!(err.root(), Error::IOError(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_values() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(&catalog, "VALUES (1, 'a', NULL), (2 + 3, 'b', 1.5)")?,
+            "+---------+---------+---------+\
+            \n| column1 | column2 | column3 |\
+            \n+---------+---------+---------+\
+            \n| 1       | a       |         |\
+            \n| 5       | b       | 1.5     |\
+            \n+---------+---------+---------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT test.name, t.column2 FROM test JOIN (VALUES (1, 10.5), (3, 30)) AS t \
+                 ON test.id = t.column1 ORDER BY t.column2 DESC"
+            )?,
+            "+-------------+---------+\
+            \n| name        | column2 |\
+            \n+-------------+---------+\
+            \n| KamenRider  | 30      |\
+            \n| bigboss2063 | 10.5    |\
+            \n+-------------+---------+"
+        );
+
+        assert!(run(&catalog, "VALUES (1, 2), (3)").is_err());
+        assert!(run(&catalog, "VALUES (1), ('a')").is_err());
+        assert!(run(&catalog, "VALUES (id)").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_window_functions() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, age, ROW_NUMBER() OVER (ORDER BY age, id) AS rn, \
+                 RANK() OVER (ORDER BY age) AS rnk, DENSE_RANK() OVER (ORDER BY age) AS dense, \
+                 COUNT(id) OVER (ORDER BY age) AS peers FROM test ORDER BY id"
+            )?,
+            "+----+-----+----+-----+-------+-------+\
+            \n| id | age | rn | rnk | dense | peers |\
+            \n+----+-----+----+-----+-------+-------+\
+            \n| 1  | 24  | 3  | 3   | 2     | 4     |\
+            \n| 2  | 24  | 4  | 3   | 2     | 4     |\
+            \n| 3  | 18  | 1  | 1   | 1     | 2     |\
+            \n| 4  | 18  | 2  | 1   | 1     | 2     |\
+            \n| 5  | 26  | 5  | 5   | 3     | 5     |\
+            \n+----+-----+----+-----+-------+-------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, ROW_NUMBER() OVER (PARTITION BY age ORDER BY id DESC) AS rn, \
+                 COUNT(id) OVER (PARTITION BY age) AS cnt FROM test ORDER BY id"
+            )?,
+            "+----+----+-----+\
+            \n| id | rn | cnt |\
+            \n+----+----+-----+\
+            \n| 1  | 2  | 2   |\
+            \n| 2  | 1  | 2   |\
+            \n| 3  | 2  | 2   |\
+            \n| 4  | 1  | 2   |\
+            \n| 5  | 1  | 1   |\
+            \n+----+----+-----+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, LAG(salary) OVER (ORDER BY id) AS prev, \
+                 LEAD(salary, 2, 0) OVER (ORDER BY id) AS next, \
+                 FIRST_VALUE(name) OVER (ORDER BY salary DESC) AS top FROM salary ORDER BY id"
+            )?,
+            "+----+-------+-------+--------------+\
+            \n| id | prev  | next  | top          |\
+            \n+----+-------+-------+--------------+\
+            \n| 1  |       | 30000 | Big Mountain |\
+            \n| 2  | 2000  | 40000 | Big Mountain |\
+            \n| 3  | 20000 | 50000 | Big Mountain |\
+            \n| 4  | 30000 | 0     | Big Mountain |\
+            \n| 5  | 40000 | 0     | Big Mountain |\
+            \n+----+-------+-------+--------------+"
+        );
+
+        // Running totals within each partition and a moving sum over three rows
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, SUM(salary) OVER (PARTITION BY awards > 5 ORDER BY id \
+                 ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS running, \
+                 SUM(salary) OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING) AS moving \
+                 FROM salary ORDER BY id"
+            )?,
+            "+----+---------+--------+\
+            \n| id | running | moving |\
+            \n+----+---------+--------+\
+            \n| 1  | 2000    | 22000  |\
+            \n| 2  | 22000   | 52000  |\
+            \n| 3  | 30000   | 90000  |\
+            \n| 4  | 70000   | 120000 |\
+            \n| 5  | 120000  | 90000  |\
+            \n+----+---------+--------+"
+        );
+
+        // The accumulator is cleared between partitions
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT name, AVG(score) OVER (PARTITION BY age ORDER BY id) AS avg_score \
+                 FROM test ORDER BY id"
+            )?,
+            "+-------------+-----------+\
+            \n| name        | avg_score |\
+            \n+-------------+-----------+\
+            \n| bigboss2063 | 0         |\
+            \n| Vincent Hu  | 50        |\
+            \n| KamenRider  | 99.99     |\
+            \n| nutswalker  | 99.985    |\
+            \n| Brian       | 99.97     |\
+            \n+-------------+-----------+"
+        );
+
+        // Window functions are computed on the output of the aggregation
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, COUNT(id) AS cnt, RANK() OVER (ORDER BY COUNT(id) DESC) AS r \
+                 FROM test GROUP BY age ORDER BY r, age"
+            )?,
+            "+-----+-----+---+\
+            \n| age | cnt | r |\
+            \n+-----+-----+---+\
+            \n| 18  | 2   | 1 |\
+            \n| 24  | 2   | 1 |\
+            \n| 26  | 1   | 3 |\
+            \n+-----+-----+---+"
+        );
+
+        // A window function is named as written, with or without GROUP BY
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, DENSE_RANK() OVER (ORDER BY age) FROM test GROUP BY age ORDER BY age"
+            )?,
+            "+-----+----------------------------------+\
+            \n| age | DENSE_RANK() OVER (ORDER BY age) |\
+            \n+-----+----------------------------------+\
+            \n| 18  | 1                                |\
+            \n| 24  | 2                                |\
+            \n| 26  | 3                                |\
+            \n+-----+----------------------------------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, DENSE_RANK() OVER (ORDER BY age) FROM test WHERE id < 3 ORDER BY age"
+            )?,
+            "+-----+----------------------------------+\
+            \n| age | DENSE_RANK() OVER (ORDER BY age) |\
+            \n+-----+----------------------------------+\
+            \n| 24  | 1                                |\
+            \n| 24  | 1                                |\
+            \n+-----+----------------------------------+"
+        );
+
+        assert!(run(&catalog, "SELECT ROW_NUMBER() FROM test").is_err());
+        assert!(run(
+            &catalog,
+            "SELECT id FROM test WHERE ROW_NUMBER() OVER () > 1"
+        )
+        .is_err());
+        assert!(run(&catalog, "SELECT RANK(id) OVER () FROM test").is_err());
+        assert!(run(&catalog, "SELECT LAG(id, 'a') OVER () FROM test").is_err());
+        assert!(run(
+            &catalog,
+            "SELECT SUM(id) OVER (ORDER BY id ROWS BETWEEN CURRENT ROW AND 1 PRECEDING) FROM test"
+        )
+        .is_err());
+        assert!(matches!(
+            run(
+                &catalog,
+                "SELECT SUM(id) OVER (ORDER BY id RANGE BETWEEN 1 PRECEDING AND CURRENT ROW) \
+                 FROM test"
+            )
+            .unwrap_err()
+            .root(),
+            Error::NotImplemented(_)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_grouping_sets() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, COUNT(id) AS cnt, GROUPING(age) AS g FROM test \
+                 GROUP BY ROLLUP(age) ORDER BY g, age"
+            )?,
+            "+-----+-----+---+\
+            \n| age | cnt | g |\
+            \n+-----+-----+---+\
+            \n| 18  | 2   | 0 |\
+            \n| 24  | 2   | 0 |\
+            \n| 26  | 1   | 0 |\
+            \n|     | 5   | 1 |\
+            \n+-----+-----+---+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, id, COUNT(id) AS cnt, GROUPING(age, id) AS g FROM test \
+                 WHERE id < 4 GROUP BY CUBE(age, id) ORDER BY g, age, id"
+            )?,
+            "+-----+----+-----+---+\
+            \n| age | id | cnt | g |\
+            \n+-----+----+-----+---+\
+            \n| 18  | 3  | 1   | 0 |\
+            \n| 24  | 1  | 1   | 0 |\
+            \n| 24  | 2  | 1   | 0 |\
+            \n| 18  |    | 1   | 1 |\
+            \n| 24  |    | 2   | 1 |\
+            \n|     | 1  | 1   | 2 |\
+            \n|     | 2  | 1   | 2 |\
+            \n|     | 3  | 1   | 2 |\
+            \n|     |    | 3   | 3 |\
+            \n+-----+----+-----+---+"
+        );
+
+        // The mask of several columns is named after the call
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, GROUPING(age, name), GROUPING(age, name) + 1 AS next FROM test \
+                 WHERE id < 3 GROUP BY ROLLUP(age, name) ORDER BY 2, name"
+            )?,
+            "+-----+---------------------+------+\
+            \n| age | GROUPING(age, name) | next |\
+            \n+-----+---------------------+------+\
+            \n| 24  | 0                   | 1    |\
+            \n| 24  | 0                   | 1    |\
+            \n| 24  | 1                   | 2    |\
+            \n|     | 3                   | 4    |\
+            \n+-----+---------------------+------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, name, MAX(score) AS top FROM test \
+                 GROUP BY GROUPING SETS ((age), (age, name), ()) \
+                 HAVING GROUPING(name) = 1 ORDER BY age"
+            )?,
+            "+-----+------+-------+\
+            \n| age | name | top   |\
+            \n+-----+------+-------+\
+            \n| 18  |      | 99.99 |\
+            \n| 24  |      | 100   |\
+            \n| 26  |      | 99.97 |\
+            \n|     |      | 100   |\
+            \n+-----+------+-------+"
+        );
+
+        assert!(run(
+            &catalog,
+            "SELECT age, GROUPING(name) FROM test GROUP BY ROLLUP(age)"
+        )
+        .is_err());
+        assert!(run(&catalog, "SELECT age FROM test WHERE ROLLUP(age)").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_distinct_and_filtered_aggregates() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT COUNT(DISTINCT age) AS ages, SUM(DISTINCT age) AS total, \
+                 COUNT(id) FILTER (WHERE age > 20) AS adults FROM test"
+            )?,
+            "+------+-------+--------+\
+            \n| ages | total | adults |\
+            \n+------+-------+--------+\
+            \n| 3    | 68    | 3      |\
+            \n+------+-------+--------+"
+        );
+
+        // The distinct values are kept for each group
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, SUM(DISTINCT age) AS total, \
+                 COUNT(id) FILTER (WHERE score > 99.98) AS high, COUNT(id) AS cnt \
+                 FROM test GROUP BY age ORDER BY age"
+            )?,
+            "+-----+-------+------+-----+\
+            \n| age | total | high | cnt |\
+            \n+-----+-------+------+-----+\
+            \n| 18  | 18    | 1    | 2   |\
+            \n| 24  | 24    | 1    | 2   |\
+            \n| 26  | 26    | 0    | 1   |\
+            \n+-----+-------+------+-----+"
+        );
+
+        // An aggregate of no rows is NULL
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, MAX(score) FILTER (WHERE id > 2) AS max_score, \
+                 MIN(score) FILTER (WHERE id > 2) AS min_score, \
+                 SUM(id) FILTER (WHERE id > 2) AS total, AVG(id) FILTER (WHERE id > 2) AS avg_id \
+                 FROM test GROUP BY age ORDER BY age"
+            )?,
+            "+-----+-----------+-----------+-------+--------+\
+            \n| age | max_score | min_score | total | avg_id |\
+            \n+-----+-----------+-----------+-------+--------+\
+            \n| 18  | 99.99     | 99.98     | 7     | 3.5    |\
+            \n| 24  |           |           |       |        |\
+            \n| 26  | 99.97     | 99.97     | 5     | 5      |\
+            \n+-----+-----------+-----------+-------+--------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT SUM(age) AS total, MAX(age) AS max_age, AVG(age) AS avg_age \
+                 FROM test WHERE age > 100"
+            )?,
+            "+-------+---------+---------+\
+            \n| total | max_age | avg_age |\
+            \n+-------+---------+---------+\
+            \n|       |         |         |\
+            \n+-------+---------+---------+"
+        );
+
+        assert!(run(&catalog, "SELECT COUNT(id) FILTER (WHERE age) FROM test").is_err());
+        assert!(matches!(
+            run(
+                &catalog,
+                "SELECT COUNT(DISTINCT age) OVER (ORDER BY id) FROM test"
+            )
+            .unwrap_err()
+            .root(),
+            Error::NotImplemented(_)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_count_star() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(&catalog, "SELECT COUNT(*) AS cnt FROM test")?,
+            "+-----+\
+            \n| cnt |\
+            \n+-----+\
+            \n| 5   |\
+            \n+-----+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT age, COUNT(*) AS cnt, COUNT(*) FILTER (WHERE id > 2) AS later \
+                 FROM test GROUP BY age ORDER BY age"
+            )?,
+            "+-----+-----+-------+\
+            \n| age | cnt | later |\
+            \n+-----+-----+-------+\
+            \n| 18  | 2   | 2     |\
+            \n| 24  | 2   | 0     |\
+            \n| 26  | 1   | 1     |\
+            \n+-----+-----+-------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, COUNT(*) OVER (PARTITION BY age) AS cnt FROM test ORDER BY id"
+            )?,
+            "+----+-----+\
+            \n| id | cnt |\
+            \n+----+-----+\
+            \n| 1  | 2   |\
+            \n| 2  | 2   |\
+            \n| 3  | 2   |\
+            \n| 4  | 2   |\
+            \n| 5  | 1   |\
+            \n+----+-----+"
+        );
+
+        assert!(matches!(
+            run(&catalog, "SELECT SUM(*) FROM test").unwrap_err().root(),
+            Error::LogicalPlanError(_)
+        ));
+        assert!(run(&catalog, "SELECT COUNT(DISTINCT *) FROM test").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_pattern_matching() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, name LIKE '%e%r' AS e_r, name NOT ILIKE 'b%' AS not_b, \
+                 name ~ '^[A-Z][a-z]+$' AS word, name SIMILAR TO '(Brian|Kamen)%' AS similar \
+                 FROM test ORDER BY id"
+            )?,
+            "+----+-------+-------+-------+---------+\
+            \n| id | e_r   | not_b | word  | similar |\
+            \n+----+-------+-------+-------+---------+\
+            \n| 1  | false | false | false | false   |\
+            \n| 2  | false | true  | false | false   |\
+            \n| 3  | true  | true  | false | true    |\
+            \n| 4  | true  | true  | false | false   |\
+            \n| 5  | false | false | true  | true    |\
+            \n+----+-------+-------+-------+---------+"
+        );
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT column1 FROM (VALUES ('100%'), ('100 apples')) AS t \
+                 WHERE column1 LIKE '100!%' ESCAPE '!'"
+            )?,
+            "+---------+\
+            \n| column1 |\
+            \n+---------+\
+            \n| 100%    |\
+            \n+---------+"
+        );
+
+        // The pattern may come from a column, a NULL pattern matches nothing
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT column1, column1 LIKE column2 AS m, column1 !~ column2 AS n \
+                 FROM (VALUES ('apple', 'a%'), ('banana', '%x'), ('cherry', NULL)) AS t"
+            )?,
+            "+---------+-------+------+\
+            \n| column1 | m     | n    |\
+            \n+---------+-------+------+\
+            \n| apple   | true  | true |\
+            \n| banana  | false | true |\
+            \n| cherry  |       |      |\
+            \n+---------+-------+------+"
+        );
+
+        assert!(run(&catalog, "SELECT id FROM test WHERE age LIKE '2%'").is_err());
+        assert!(run(&catalog, "SELECT id FROM test WHERE name ~ '('").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_unary_and_is_operators() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, -age AS neg_age, -score AS neg_score, -(age - id) AS diff FROM test \
+                 WHERE NOT age > 20 ORDER BY id"
+            )?,
+            "+----+---------+-----------+------+\
+            \n| id | neg_age | neg_score | diff |\
+            \n+----+---------+-----------+------+\
+            \n| 3  | -18     | -99.99    | -15  |\
+            \n| 4  | -18     | -99.98    | -14  |\
+            \n+----+---------+-----------+------+"
+        );
+
+        // NULLs are only equal to each other for IS [NOT] DISTINCT FROM
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT column1 IS NULL AS a_null, column1 = column2 AS eq, \
+                 column1 IS DISTINCT FROM column2 AS distinct, \
+                 column1 IS NOT DISTINCT FROM column2 AS not_distinct, \
+                 column3 IS TRUE AS is_true, column3 IS NOT FALSE AS not_false, NOT column3 AS not \
+                 FROM (VALUES (1, 1, TRUE), (1, NULL, FALSE), (NULL, NULL, NULL), (2, 3, NULL)) AS t"
+            )?,
+            "+--------+-------+----------+--------------+---------+-----------+-------+\
+            \n| a_null | eq    | distinct | not_distinct | is_true | not_false | not   |\
+            \n+--------+-------+----------+--------------+---------+-----------+-------+\
+            \n| false  | true  | false    | true         | true    | true      | false |\
+            \n| false  |       | true     | false        | false   | false     | true  |\
+            \n| true   |       | false    | true         | false   | true      |       |\
+            \n| false  | false | true     | false        | false   | true      |       |\
+            \n+--------+-------+----------+--------------+---------+-----------+-------+"
+        );
+
+        // Rows whose predicate is NULL are filtered out
+        let values = "(VALUES ('a', 1, 1, TRUE), ('b', 1, NULL, FALSE), ('c', NULL, NULL, NULL), \
+                      ('d', 2, 3, NULL)) AS t";
+        assert_eq!(
+            run(
+                &catalog,
+                &format!("SELECT column1 FROM {} WHERE NOT column4", values)
+            )?,
+            "+---------+\
+            \n| column1 |\
+            \n+---------+\
+            \n| b       |\
+            \n+---------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                &format!(
+                    "SELECT column1 FROM {} WHERE column2 = column3 OR column2 <> column3",
+                    values
+                )
+            )?,
+            "+---------+\
+            \n| column1 |\
+            \n+---------+\
+            \n| a       |\
+            \n| d       |\
+            \n+---------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                &format!(
+                    "SELECT column1 FROM {} WHERE column2 IS DISTINCT FROM column3",
+                    values
+                )
+            )?,
+            "+---------+\
+            \n| column1 |\
+            \n+---------+\
+            \n| b       |\
+            \n| d       |\
+            \n+---------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                &format!(
+                    "SELECT column1 FROM {} WHERE column2 IS NOT DISTINCT FROM column3",
+                    values
+                )
+            )?,
+            "+---------+\
+            \n| column1 |\
+            \n+---------+\
+            \n| a       |\
+            \n| c       |\
+            \n+---------+"
+        );
+
+        assert!(run(&catalog, "SELECT NOT age FROM test").is_err());
+        assert!(run(&catalog, "SELECT -name FROM test").is_err());
+        assert!(run(&catalog, "SELECT id FROM test WHERE name IS TRUE").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_in_list_and_between() -> Result<()> {
+        let catalog = catalog()?;
+
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, name FROM test WHERE id IN (1, 3.0, 5) AND age NOT BETWEEN 20 AND 25 \
+                 OR name IN ('Vincent Hu', 'Nobody') ORDER BY id"
+            )?,
+            "+----+------------+\
+            \n| id | name       |\
+            \n+----+------------+\
+            \n| 2  | Vincent Hu |\
+            \n| 3  | KamenRider |\
+            \n| 5  | Brian      |\
+            \n+----+------------+"
+        );
+
+        // A NULL in the list makes NOT IN NULL instead of true, NULL bounds make BETWEEN NULL
+        // unless the other bound already rules the value out
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT column1, column1 IN (1, NULL) AS in_list, \
+                 column1 NOT IN (1, NULL) AS not_in, column1 IN (column2, 3) AS in_column, \
+                 column1 BETWEEN column2 AND 3 AS between \
+                 FROM (VALUES (1, 0), (5, NULL), (3, 4), (NULL, 1)) AS t"
+            )?,
+            "+---------+---------+--------+-----------+---------+\
+            \n| column1 | in_list | not_in | in_column | between |\
+            \n+---------+---------+--------+-----------+---------+\
+            \n| 1       | true    | false  | false     | true    |\
+            \n| 5       |         |        |           | false   |\
+            \n| 3       |         |        | true      | false   |\
+            \n|         |         |        |           |         |\
+            \n+---------+---------+--------+-----------+---------+"
+        );
+
+        // Rows whose predicate is NULL are filtered out
+        assert_eq!(
+            run(&catalog, "SELECT id, name FROM test WHERE id IN (1, NULL)")?,
+            "+----+-------------+\
+            \n| id | name        |\
+            \n+----+-------------+\
+            \n| 1  | bigboss2063 |\
+            \n+----+-------------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT id, name FROM test WHERE id NOT IN (1, NULL)"
+            )?,
+            "+----+------+\
+            \n| id | name |\
+            \n+----+------+\
+            \n+----+------+"
+        );
+        assert_eq!(
+            run(
+                &catalog,
+                "SELECT column1 FROM (VALUES (1, 0), (5, NULL), (3, 4), (NULL, 1)) AS t \
+                 WHERE column1 NOT BETWEEN column2 AND 4"
+            )?,
+            "+---------+\
+            \n| column1 |\
+            \n+---------+\
+            \n| 5       |\
+            \n| 3       |\
+            \n+---------+"
+        );
+
+        assert!(run(&catalog, "SELECT id FROM test WHERE id IN (1, 'a')").is_err());
+        assert!(run(&catalog, "SELECT id FROM test WHERE name BETWEEN 1 AND 2").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_explain() -> Result<()> {
+        let catalog = catalog()?;
+
+        let explain = |sql: &str| -> Result<Vec<(String, String)>> {
+            let df = catalog.sql(sql)?;
+            let batches = QueryPlanner::create_physical_plan(df.plan())?.execute()?;
+            let column = |i: usize| {
+                batches[0]
+                    .column(i)
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .unwrap()
+                    .iter()
+                    .map(|value| value.unwrap().to_string())
+                    .collect::<Vec<_>>()
+            };
+            Ok(column(0).into_iter().zip(column(1)).collect())
+        };
+
+        let plans =
+            explain("EXPLAIN SELECT name, score * 2 FROM test WHERE age >= 24 ORDER BY name")?;
+        assert_eq!(
+            plans,
+            vec![
+                (
+                    "logical_plan".to_string(),
+                    "Projection: name, score * 2\
+                   \n  Sort: name ASC NULLS LAST\
+                   \n    Selection: age >= 24\
+                   \n      SubqueryAlias: test\
+                   \n        Scan: CSV file [id, name, age, score]"
+                        .to_string()
+                ),
+                (
+                    "optimized_logical_plan".to_string(),
+                    "Projection: name, score * CAST(2 AS Float64)\
+                   \n  Sort: name ASC NULLS LAST\
+                   \n    Selection: age >= 24\
+                   \n      SubqueryAlias: test\
+                   \n        Scan: CSV file [id, name, age, score]"
+                        .to_string()
+                ),
+                (
+                    "physical_plan".to_string(),
+                    "Projection: #1, #3 * CAST(2 AS Float64)\
+                   \n  Sort: #1 ASC NULLS LAST\
+                   \n    Selection: #2 >= 24\
+                   \n      Scan: CSV file [id, name, age, score]"
+                        .to_string()
+                ),
+            ]
+        );
+
+        let plan_types = explain("EXPLAIN VERBOSE SELECT id FROM test")?
+            .into_iter()
+            .map(|(plan_type, _)| plan_type)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            plan_types,
+            vec![
+                "logical_plan",
+                "logical_plan after decorrelate_subqueries",
+                "logical_plan after type_coercion",
+                "optimized_logical_plan",
+                "physical_plan",
+            ]
+        );
+
+        // The physical plan shown is the one executed for the query
+        let sql = "SELECT name FROM test WHERE id IN (SELECT id FROM salary) AND score > 50";
+        let physical_plan =
+            QueryPlanner::create_physical_plan(catalog.sql(sql)?.plan())?.display_indent(false)?;
+        let plans = explain(&format!("EXPLAIN {}", sql))?;
+        assert_eq!(
+            plans.last().unwrap(),
+            &("physical_plan".to_string(), physical_plan)
+        );
+
+        // The same plans are returned by the DataFrame API
+        let df = catalog.get_table_df("test")?.explain(false, false)?;
+        assert_eq!(df.schema().fields().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sql_explain_analyze() -> Result<()> {
+        let catalog = catalog()?;
+
+        let df = catalog.sql(
+            "EXPLAIN ANALYZE SELECT age, COUNT(id) FROM test WHERE score > 50 GROUP BY age ORDER BY age",
+        )?;
+        let batches = QueryPlanner::create_physical_plan(df.plan())?.execute()?;
+        let plan_types = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let plans = batches[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(plan_types.value(2), "physical_plan_with_metrics");
+
+        // The compute time and memory differ between runs, only the row counts are compared
+        let plan = plans
+            .value(2)
+            .lines()
+            .map(|line| line.split(", elapsed_compute").next().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            plan,
+            vec![
+                "Projection: #0, #1, metrics=[output_rows=3, output_batches=1",
+                "  Sort: #0 ASC NULLS LAST",
+                "    Aggregation: group_expr=[#2], aggr_expr=[COUNT(#0)], metrics=[output_rows=3, output_batches=1",
+                "      Selection: #3 > CAST(50 AS Float64), metrics=[output_rows=4, output_batches=1",
+                "        Scan: CSV file [id, name, age, score], metrics=[output_rows=5, output_batches=1",
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,479 @@
+use crate::error::{Error, Result};
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, Clone, PartialEq)]
+/// SQL tokens
+pub enum Token {
+    /// A keyword (like SELECT) or an identifier
+    Word(Word),
+    /// An unsigned numeric literal
+    Number(String),
+    /// A string literal enclosed in single quotes
+    SingleQuotedString(String),
+    /// A parameter of a prepared statement, `?` or `$` followed by a number
+    Placeholder(String),
+    /// Comma `,`
+    Comma,
+    /// Period `.`
+    Period,
+    /// SemiColon `;`
+    SemiColon,
+    /// Left parenthesis `(`
+    LParen,
+    /// Right parenthesis `)`
+    RParen,
+    /// Equality operator `=`
+    Eq,
+    /// Not equals operator `!=` or `<>`
+    Neq,
+    /// Less than operator `<`
+    Lt,
+    /// Less than or equals operator `<=`
+    LtEq,
+    /// Greater than operator `>`
+    Gt,
+    /// Greater than or equals operator `>=`
+    GtEq,
+    /// Plus operator `+`
+    Plus,
+    /// Minus operator `-`
+    Minus,
+    /// Multiplication operator `*`
+    Mul,
+    /// Division operator `/`
+    Div,
+    /// Modulo operator `%`
+    Mod,
+    /// Regex match operator `~`
+    Tilde,
+    /// Regex not match operator `!~`
+    ExclamationMarkTilde,
+    /// End of the SQL text
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A keyword or an identifier
+pub struct Word {
+    pub value: String,
+    /// Whether the word was enclosed in double quotes,
+    /// a quoted word is always an identifier and never a keyword.
+    pub quoted: bool,
+}
+
+impl Word {
+    /// Returns the upper case form of the word if it can be a keyword
+    pub fn keyword(&self) -> Option<String> {
+        if self.quoted {
+            None
+        } else {
+            Some(self.value.to_uppercase())
+        }
+    }
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) if word.quoted => write!(f, "\"{}\"", word.value),
+            Token::Word(word) => write!(f, "{}", word.value),
+            Token::Number(number) => write!(f, "{}", number),
+            Token::SingleQuotedString(s) => write!(f, "'{}'", s),
+            Token::Placeholder(s) => write!(f, "{}", s),
+            Token::Comma => write!(f, ","),
+            Token::Period => write!(f, "."),
+            Token::SemiColon => write!(f, ";"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Eq => write!(f, "="),
+            Token::Neq => write!(f, "!="),
+            Token::Lt => write!(f, "<"),
+            Token::LtEq => write!(f, "<="),
+            Token::Gt => write!(f, ">"),
+            Token::GtEq => write!(f, ">="),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Mul => write!(f, "*"),
+            Token::Div => write!(f, "/"),
+            Token::Mod => write!(f, "%"),
+            Token::Tilde => write!(f, "~"),
+            Token::ExclamationMarkTilde => write!(f, "!~"),
+            Token::Eof => write!(f, "EOF"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The position of a token in the SQL text, both line and column start from 1
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The part of the SQL text from `start` to `end`, both inclusive
+pub struct Span {
+    pub start: Location,
+    pub end: Location,
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else if self.start.line == self.end.line {
+            write!(
+                f,
+                "line {}, columns {}-{}",
+                self.start.line, self.start.column, self.end.column
+            )
+        } else {
+            write!(f, "{} to {}", self.start, self.end)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenWithLocation {
+    pub token: Token,
+    pub location: Location,
+    /// The location of the last character of the token
+    pub end: Location,
+}
+
+impl TokenWithLocation {
+    /// The part of the SQL text the token was read from
+    pub fn span(&self) -> Span {
+        Span {
+            start: self.location,
+            end: self.end,
+        }
+    }
+}
+
+/// Tokenizer splits the SQL text into a sequence of tokens
+pub struct Tokenizer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(sql: &'a str) -> Self {
+        Self {
+            chars: sql.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    pub fn tokenize(&mut self) -> Result<Vec<TokenWithLocation>> {
+        let mut tokens = vec![];
+
+        loop {
+            self.skip_whitespace_and_comments();
+
+            let location = Location {
+                line: self.line,
+                column: self.column,
+            };
+
+            let token = match self.next_token(location)? {
+                Some(token) => token,
+                None => {
+                    tokens.push(TokenWithLocation {
+                        token: Token::Eof,
+                        location,
+                        end: location,
+                    });
+                    return Ok(tokens);
+                }
+            };
+
+            // No token ends with a line break, so the last character is before the column
+            let end = Location {
+                line: self.line,
+                column: self.column - 1,
+            };
+            tokens.push(TokenWithLocation {
+                token,
+                location,
+                end,
+            });
+        }
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            match self.chars.peek() {
+                Some(ch) if ch.is_whitespace() => {
+                    self.next_char();
+                }
+                Some('-') => {
+                    // `--` starts a comment that lasts until the end of the line
+                    let mut lookahead = self.chars.clone();
+                    lookahead.next();
+                    if lookahead.peek() != Some(&'-') {
+                        return;
+                    }
+                    while let Some(ch) = self.next_char() {
+                        if ch == '\n' {
+                            break;
+                        }
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn next_token(&mut self, location: Location) -> Result<Option<Token>> {
+        let ch = match self.chars.peek() {
+            Some(ch) => *ch,
+            None => return Ok(None),
+        };
+
+        let token = match ch {
+            ch if ch.is_alphabetic() || ch == '_' => {
+                let value = self.take_while(|ch| ch.is_alphanumeric() || ch == '_');
+                Token::Word(Word {
+                    value,
+                    quoted: false,
+                })
+            }
+            ch if ch.is_ascii_digit() => {
+                let mut number = self.take_while(|ch| ch.is_ascii_digit());
+                if self.chars.peek() == Some(&'.') {
+                    self.next_char();
+                    number.push('.');
+                    number.push_str(&self.take_while(|ch| ch.is_ascii_digit()));
+                }
+                Token::Number(number)
+            }
+            '\'' => {
+                self.next_char();
+                Token::SingleQuotedString(self.take_quoted('\'', location)?)
+            }
+            '"' => {
+                self.next_char();
+                Token::Word(Word {
+                    value: self.take_quoted('"', location)?,
+                    quoted: true,
+                })
+            }
+            _ => {
+                self.next_char();
+                match ch {
+                    ',' => Token::Comma,
+                    '.' => Token::Period,
+                    ';' => Token::SemiColon,
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    '=' => Token::Eq,
+                    '+' => Token::Plus,
+                    '-' => Token::Minus,
+                    '*' => Token::Mul,
+                    '/' => Token::Div,
+                    '%' => Token::Mod,
+                    '~' => Token::Tilde,
+                    '?' => Token::Placeholder("?".to_string()),
+                    '$' => {
+                        let number = self.take_while(|ch| ch.is_ascii_digit());
+                        if number.is_empty() {
+                            return Err(Error::ParserError(format!(
+                                "Expected a parameter number after '$' at {}",
+                                location
+                            )));
+                        }
+                        Token::Placeholder(format!("${}", number))
+                    }
+                    '!' => match self.chars.peek() {
+                        Some('=') => {
+                            self.next_char();
+                            Token::Neq
+                        }
+                        Some('~') => {
+                            self.next_char();
+                            Token::ExclamationMarkTilde
+                        }
+                        _ => {
+                            return Err(Error::ParserError(format!(
+                                "Unexpected character '!' at {}",
+                                location
+                            )))
+                        }
+                    },
+                    '<' => match self.chars.peek() {
+                        Some('=') => {
+                            self.next_char();
+                            Token::LtEq
+                        }
+                        Some('>') => {
+                            self.next_char();
+                            Token::Neq
+                        }
+                        _ => Token::Lt,
+                    },
+                    '>' => match self.chars.peek() {
+                        Some('=') => {
+                            self.next_char();
+                            Token::GtEq
+                        }
+                        _ => Token::Gt,
+                    },
+                    _ => {
+                        return Err(Error::ParserError(format!(
+                            "Unexpected character '{}' at {}",
+                            ch, location
+                        )))
+                    }
+                }
+            }
+        };
+
+        Ok(Some(token))
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut s = String::new();
+        while let Some(ch) = self.chars.peek() {
+            if !predicate(*ch) {
+                break;
+            }
+            s.push(*ch);
+            self.next_char();
+        }
+        s
+    }
+
+    /// Reads until the closing quote, two consecutive quotes represent a quote itself
+    fn take_quoted(&mut self, quote: char, location: Location) -> Result<String> {
+        let mut s = String::new();
+        loop {
+            match self.next_char() {
+                Some(ch) if ch == quote => {
+                    if self.chars.peek() == Some(&quote) {
+                        self.next_char();
+                        s.push(quote);
+                    } else {
+                        return Ok(s);
+                    }
+                }
+                Some(ch) => s.push(ch),
+                None => {
+                    return Err(Error::ParserError(format!(
+                        "Unterminated quoted string starting at {}",
+                        location
+                    )))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: &str) -> Token {
+        Token::Word(Word {
+            value: value.to_string(),
+            quoted: false,
+        })
+    }
+
+    #[test]
+    fn tokenize_select() -> Result<()> {
+        let tokens = Tokenizer::new("SELECT name, score * 2 FROM test WHERE age >= 24")
+            .tokenize()?
+            .into_iter()
+            .map(|t| t.token)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+            vec![
+                word("SELECT"),
+                word("name"),
+                Token::Comma,
+                word("score"),
+                Token::Mul,
+                Token::Number("2".to_string()),
+                word("FROM"),
+                word("test"),
+                word("WHERE"),
+                word("age"),
+                Token::GtEq,
+                Token::Number("24".to_string()),
+                Token::Eof,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn tokenize_literals_and_locations() -> Result<()> {
+        let tokens = Tokenizer::new("-- comment\nSELECT 'it''s', \"Name\" <> 99.5").tokenize()?;
+
+        assert_eq!(tokens[0].token, word("SELECT"));
+        assert_eq!(tokens[0].location, Location { line: 2, column: 1 });
+        assert_eq!(
+            tokens[1].token,
+            Token::SingleQuotedString("it's".to_string())
+        );
+        assert_eq!(tokens[1].location, Location { line: 2, column: 8 });
+        assert_eq!(
+            tokens[1].end,
+            Location {
+                line: 2,
+                column: 14
+            }
+        );
+        assert_eq!(
+            tokens[3].token,
+            Token::Word(Word {
+                value: "Name".to_string(),
+                quoted: true,
+            })
+        );
+        assert_eq!(
+            tokens[3].span(),
+            Span {
+                start: Location {
+                    line: 2,
+                    column: 17
+                },
+                end: Location {
+                    line: 2,
+                    column: 22
+                },
+            }
+        );
+        assert_eq!(tokens[4].token, Token::Neq);
+        assert_eq!(tokens[5].token, Token::Number("99.5".to_string()));
+
+        assert!(Tokenizer::new("SELECT 'abc").tokenize().is_err());
+
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
 use crate::error::Result;
-use arrow::{compute::concat, datatypes::SchemaRef, record_batch::RecordBatch};
+use arrow::{
+    compute::concat,
+    datatypes::SchemaRef,
+    record_batch::{RecordBatch, RecordBatchOptions},
+};
 
 /// Concatenates an array of `RecordBatch` into one batch
 pub fn concat_batches(schema: &SchemaRef, batches: &[RecordBatch]) -> Result<RecordBatch> {
@@ -16,5 +20,12 @@ pub fn concat_batches(schema: &SchemaRef, batches: &[RecordBatch]) -> Result<Rec
         )?;
         arrays.push(array);
     }
-    Ok(RecordBatch::try_new(schema.clone(), arrays)?)
+    // The row count is needed for batches without columns
+    let mut options = RecordBatchOptions::default();
+    options.row_count = Some(batches.iter().map(|batch| batch.num_rows()).sum());
+    Ok(RecordBatch::try_new_with_options(
+        schema.clone(),
+        arrays,
+        &options,
+    )?)
 }